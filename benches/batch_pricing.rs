@@ -0,0 +1,41 @@
+//! Compares pricing contracts one by one against the SIMD batch pricing. Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use which_option::blackscholes::{BlackScholes, Call, Contract, Environment, Put};
+
+/// Contracts priced per run, roughly a heatmap's worth
+const CONTRACTS: usize = 3600;
+/// Runs averaged over for each timing
+const RUNS: u32 = 200;
+
+/// Average time taken by a run of the pricing function
+fn time(mut price: impl FnMut() -> Vec<f64>) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        black_box(price());
+    }
+    return start.elapsed() / RUNS;
+}
+
+fn report<T: BlackScholes>(name: &str, env: &Environment, contracts: &[Contract]) {
+    let scalar = time(|| contracts.iter().map(|contract| T::bsm_price(env, contract)).collect());
+    let batch = time(|| T::bsm_price_batch(env, contracts));
+    println!(
+        "{name}: one by one {:.1}ns/contract, batch {:.1}ns/contract ({:.2}x)",
+        scalar.as_nanos() as f64 / contracts.len() as f64,
+        batch.as_nanos() as f64 / contracts.len() as f64,
+        scalar.as_secs_f64() / batch.as_secs_f64(),
+    );
+}
+
+fn main() {
+    let env = Environment { stock: 100.0, risk_free: 0.04, vol: 0.3, div_yield: 0.01 };
+    // A grid of strikes and expiries, as swept by the charts and heatmap
+    let contracts: Vec<Contract> = (0..CONTRACTS)
+        .map(|i| Contract { strike: 50.0 + (i % 60) as f64 * 2.0, expiry: 0.05 + (i / 60) as f64 * 0.05 })
+        .collect();
+    report::<Call>("Call", &env, &contracts);
+    report::<Put>("Put", &env, &contracts);
+}
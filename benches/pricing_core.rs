@@ -0,0 +1,71 @@
+//! Times the pricing core the calculator is built on, so slowdowns from pricing changes show up. Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use which_option::blackscholes::{BlackScholesROIRounded, Call, Contract, Environment, Movement, Put};
+
+/// Points sampled per chart sweep, as drawn by a payoff chart
+const SWEEP_POINTS: usize = 500;
+
+/// Time spent repeating each benchmark
+const TARGET: Duration = Duration::from_millis(500);
+
+/// Average time taken by a run of the function, repeating it for about TARGET
+fn time<T>(mut run: impl FnMut() -> T) -> Duration {
+    // A single run to estimate how many fit in the target time
+    let start = Instant::now();
+    black_box(run());
+    let runs = (TARGET.as_secs_f64() / start.elapsed().as_secs_f64().max(1e-9)).clamp(1.0, 1e7) as u32;
+
+    let start = Instant::now();
+    for _ in 0..runs {
+        black_box(run());
+    }
+    return start.elapsed() / runs;
+}
+
+fn report<T>(name: &str, run: impl FnMut() -> T) {
+    let elapsed = time(run);
+    if elapsed < Duration::from_micros(10) {
+        println!("{name:<32} {:>10.1}ns", elapsed.as_nanos() as f64);
+    } else {
+        println!("{name:<32} {:>10.1}us", elapsed.as_nanos() as f64 / 1000.0);
+    }
+}
+
+fn bench<T: BlackScholesROIRounded>(name: &str, start_env: &Environment, end_env: &Environment, contract: &Contract, movement: &Movement) {
+    report(&format!("{name} bsm_price"), || T::bsm_price(black_box(start_env), black_box(contract)));
+    report(&format!("{name} roi"), || T::roi(black_box(start_env), black_box(end_env), black_box(contract), black_box(movement)));
+    report(&format!("{name} find_best_contract"), || T::find_best_contract(black_box(start_env), black_box(end_env), black_box(movement)));
+
+    // Sweeps of the practical ROI across a chart's x-axis, as the payoff charts sample them
+    report(&format!("{name} strike sweep"), || {
+        (0..SWEEP_POINTS)
+            .map(|i| Contract { strike: 50.0 + i as f64 * 0.2, ..contract.clone() })
+            .map(|contract| T::roi_practical(start_env, end_env, &contract, movement))
+            .collect::<Vec<f64>>()
+    });
+    report(&format!("{name} end price sweep"), || {
+        (0..SWEEP_POINTS)
+            .map(|i| Movement { stock: 50.0 + i as f64 * 0.2, ..movement.clone() })
+            .map(|movement| T::roi_practical(start_env, end_env, contract, &movement))
+            .collect::<Vec<f64>>()
+    });
+    report(&format!("{name} end vol sweep"), || {
+        (0..SWEEP_POINTS)
+            .map(|i| Environment { vol: 0.05 + i as f64 * 0.002, ..end_env.clone() })
+            .map(|end_env| T::roi_practical(start_env, &end_env, contract, movement))
+            .collect::<Vec<f64>>()
+    });
+}
+
+fn main() {
+    let start_env = Environment { stock: 100.0, risk_free: 0.04, vol: 0.3, div_yield: 0.01 };
+    let end_env = start_env.clone();
+    let movement = Movement { stock: 110.0, time: 0.25 };
+    let call = Contract { strike: 105.0, expiry: 0.5 };
+    let put = Contract { strike: 95.0, expiry: 0.5 };
+    bench::<Call>("Call", &start_env, &end_env, &call, &movement);
+    bench::<Put>("Put", &start_env, &end_env, &put, &Movement { stock: 90.0, ..movement.clone() });
+}
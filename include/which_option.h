@@ -0,0 +1,36 @@
+/* C declarations of the which_option pricing core. Build with `cargo build --release --features ffi`. */
+#ifndef WHICH_OPTION_H
+#define WHICH_OPTION_H
+
+#include <stdbool.h>
+
+/* Environmental variables that affect an option's price. Rates are annual fractions (e.g 4% is 0.04). */
+typedef struct {
+    double stock;
+    double risk_free;
+    double vol;
+    double div_yield;
+} Environment;
+
+/* Strike price and years left to expiry of an option */
+typedef struct {
+    double strike;
+    double expiry;
+} Contract;
+
+/* Stock price reached after the given number of years */
+typedef struct {
+    double stock;
+    double time;
+} Movement;
+
+/* Black-Scholes price of a call (is_call true) or put option */
+double which_option_bsm_price(bool is_call, Environment env, Contract contract);
+
+/* ROI from purchasing the option in start_env then selling at the end of the movement in end_env */
+double which_option_roi(bool is_call, Environment start_env, Environment end_env, Contract contract, Movement movement);
+
+/* Contract that maximises ROI for the movement */
+Contract which_option_find_best_contract(bool is_call, Environment start_env, Environment end_env, Movement movement);
+
+#endif
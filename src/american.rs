@@ -0,0 +1,102 @@
+//! American options, which can be exercised any time before expiry, priced by the Bjerksund-Stensland (1993)
+//! closed form approximation. It takes the exercise boundary to be flat, which slightly underprices them, but is
+//! cheap enough to evaluate in the same loops as the Black-Scholes prices. Perpetual options, which never expire,
+//! have an exact closed form.
+
+use crate::blackscholes::{Contract, Environment, OptionType, std_normal_cdf};
+
+/// Price of an American option. Never below the European price, since the option can always be held to expiry.
+pub fn price(option: OptionType, env: &Environment, contract: &Contract) -> f64 {
+    let european = option.bsm_price(env, contract);
+    if contract.expiry <= 0.0 || env.vol <= 0.0 {
+        return european;
+    }
+    // Cost of carry, which a put shares with the call on the reversed roles of stock and strike
+    let carry = env.risk_free - env.div_yield;
+    let american = match option {
+        OptionType::Call => call(env.stock, contract.strike, contract.expiry, env.risk_free, carry, env.vol),
+        OptionType::Put => call(contract.strike, env.stock, contract.expiry, env.risk_free - carry, -carry, env.vol),
+    };
+    if !american.is_finite() {
+        return european;
+    }
+    return american.max(european);
+}
+
+/// American call by Bjerksund-Stensland, of a stock at the given price and cost of carry
+fn call(stock: f64, strike: f64, expiry: f64, risk_free: f64, carry: f64, vol: f64) -> f64 {
+    // Never worth exercising early, so it's priced the same as a European call
+    if carry >= risk_free {
+        let env = Environment { stock, risk_free, vol, div_yield: risk_free - carry };
+        return OptionType::Call.bsm_price(&env, &Contract { strike, expiry });
+    }
+    let var = vol.powi(2);
+    let beta = (0.5 - carry / var) + ((carry / var - 0.5).powi(2) + 2.0 * risk_free / var).sqrt();
+    let boundary_inf = beta / (beta - 1.0) * strike;
+    let boundary_0 = strike.max(risk_free / (risk_free - carry) * strike);
+    let h = -(carry * expiry + 2.0 * vol * expiry.sqrt()) * boundary_0 / (boundary_inf - boundary_0);
+    // Stock price the call is exercised at
+    let trigger = boundary_0 + (boundary_inf - boundary_0) * (1.0 - h.exp());
+    if stock >= trigger {
+        return stock - strike;
+    }
+    let alpha = (trigger - strike) * trigger.powf(-beta);
+    let phi = |gamma: f64, barrier: f64| phi(stock, expiry, gamma, barrier, trigger, risk_free, carry, vol);
+    return alpha * stock.powf(beta)
+        - alpha * phi(beta, trigger)
+        + phi(1.0, trigger)
+        - phi(1.0, strike)
+        - strike * phi(0.0, trigger)
+        + strike * phi(0.0, strike);
+}
+
+/// The φ function of Bjerksund-Stensland, valuing a payoff of stock^gamma knocked out at the trigger
+#[allow(clippy::too_many_arguments)]
+fn phi(stock: f64, expiry: f64, gamma: f64, barrier: f64, trigger: f64, risk_free: f64, carry: f64, vol: f64) -> f64 {
+    let var = vol.powi(2);
+    let spread = vol * expiry.sqrt();
+    let lambda = (-risk_free + gamma * carry + 0.5 * gamma * (gamma - 1.0) * var) * expiry;
+    let d = -((stock / barrier).ln() + (carry + (gamma - 0.5) * var) * expiry) / spread;
+    let kappa = 2.0 * carry / var + (2.0 * gamma - 1.0);
+    return lambda.exp() * stock.powf(gamma)
+        * (std_normal_cdf(d) - (trigger / stock).powf(kappa) * std_normal_cdf(d - 2.0 * (trigger / stock).ln() / spread));
+}
+
+/// Price of a perpetual American option, one that never expires, in closed form. The limit the American price
+/// approaches as the expiry grows (e.g an intuition for very long dated LEAPS).
+///
+/// A call on a stock without a dividend yield, or a put without a positive risk free rate, is never worth exercising,
+/// so its price is the limit of the European price instead (the stock price and strike respectively).
+pub fn perpetual_price(option: OptionType, env: &Environment, strike: f64) -> f64 {
+    let var = env.vol.powi(2);
+    let carry = env.risk_free - env.div_yield;
+    let (intrinsic, never_exercised) = match option {
+        OptionType::Call => ((env.stock - strike).max(0.0), env.div_yield <= 0.0),
+        OptionType::Put => ((strike - env.stock).max(0.0), env.risk_free <= 0.0),
+    };
+    if never_exercised {
+        return match option {
+            OptionType::Call => env.stock,
+            OptionType::Put => strike,
+        };
+    }
+    if var <= 0.0 {
+        return intrinsic;
+    }
+    let root = ((carry / var - 0.5).powi(2) + 2.0 * env.risk_free / var).sqrt();
+    let beta = match option {
+        OptionType::Call => 0.5 - carry / var + root,
+        OptionType::Put => 0.5 - carry / var - root,
+    };
+    // Stock price the option is exercised at
+    let trigger = beta / (beta - 1.0) * strike;
+    let exercised = match option {
+        OptionType::Call => env.stock >= trigger,
+        OptionType::Put => env.stock <= trigger,
+    };
+    if exercised {
+        return intrinsic;
+    }
+    let price = (trigger - strike).abs() * (env.stock / trigger).powf(beta);
+    return if price.is_finite() { price.max(intrinsic) } else { intrinsic };
+}
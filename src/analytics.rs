@@ -0,0 +1,117 @@
+//! Analysis of historical price data to help estimate inputs for the pricing model.
+
+/// Approximate number of trading days in a year. Used to annualise daily volatility.
+pub const TRADING_DAYS: f64 = 252.0;
+
+/// Daily price history of a stock, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct PriceHistory {
+    pub closes: Vec<f64>,
+    /// Daily highs. Empty if the data had no high column.
+    pub highs: Vec<f64>,
+    /// Daily lows. Empty if the data had no low column.
+    pub lows: Vec<f64>,
+}
+
+/// Realised volatility estimates of a price history
+#[derive(Debug, Clone)]
+pub struct VolEstimates {
+    /// Number of trading days the estimates were computed over
+    pub days: usize,
+    pub close_to_close: Option<f64>,
+    pub parkinson: Option<f64>,
+}
+
+impl PriceHistory {
+    /// Parses a CSV of daily prices with rows in date order (either direction).
+    ///
+    /// If a header row is present, the "Adj Close"/"Close", "High" and "Low" columns are used.
+    /// Without a header, the last column containing a number is used as the close.
+    pub fn from_csv(csv: &str) -> Result<Self, String> {
+        let mut lines = csv.lines().map(str::trim).filter(|line| !line.is_empty()).peekable();
+        let first = *lines.peek().ok_or("File is empty")?;
+        let header: Vec<String> = first.split(',').map(|h| h.trim().trim_matches('"').to_lowercase()).collect();
+        let has_header = header.iter().all(|h| h.parse::<f64>().is_err());
+
+        let (mut close_col, mut high_col, mut low_col) = (None, None, None);
+        if has_header {
+            lines.next();
+            let find = |name: &str| header.iter().position(|h| h == name);
+            close_col = find("adj close").or(find("close"));
+            high_col = find("high");
+            low_col = find("low");
+            if close_col.is_none() {
+                return Err(String::from("No \"Close\" column found"));
+            }
+        }
+
+        let mut history = Self::default();
+        let mut dates: Vec<String> = Vec::new();
+        for line in lines {
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim().trim_matches('"')).collect();
+            let parse = |col: Option<usize>| col.and_then(|c| fields.get(c)).and_then(|f| f.parse::<f64>().ok());
+            let close = match close_col {
+                Some(_) => parse(close_col),
+                None => fields.iter().rev().find_map(|f| f.parse::<f64>().ok()),
+            };
+            // Skip rows with missing data (e.g "null" on non trading days)
+            let Some(close) = close.filter(|c| *c > 0.0) else {
+                continue;
+            };
+            history.closes.push(close);
+            if let (Some(high), Some(low)) = (parse(high_col), parse(low_col)) {
+                history.highs.push(high);
+                history.lows.push(low);
+            }
+            dates.push(fields.first().unwrap_or(&"").to_string());
+        }
+        // Only trust high/low data if every row had it
+        if history.highs.len() != history.closes.len() {
+            history.highs.clear();
+            history.lows.clear();
+        }
+        // Order of rows only matters for the sign of returns, but keep oldest first for consistency
+        if dates.len() > 1 && dates.first() > dates.last() {
+            history.closes.reverse();
+            history.highs.reverse();
+            history.lows.reverse();
+        }
+        if history.closes.len() < 3 {
+            return Err(String::from("Need at least 3 days of prices"));
+        }
+        return Ok(history);
+    }
+
+    pub fn vol_estimates(&self) -> VolEstimates {
+        VolEstimates {
+            days: self.closes.len(),
+            close_to_close: close_to_close_vol(&self.closes),
+            parkinson: parkinson_vol(&self.highs, &self.lows),
+        }
+    }
+}
+
+/// Annualised standard deviation of the daily log returns. None if fewer than 3 prices are given.
+pub fn close_to_close_vol(closes: &[f64]) -> Option<f64> {
+    let returns: Vec<f64> = closes.windows(2)
+        .map(|pair| f64::ln(pair[1] / pair[0]))
+        .collect();
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    return Some((variance * TRADING_DAYS).sqrt());
+}
+
+/// Annualised Parkinson volatility estimated from daily highs and lows. None if no (or mismatched) data is given.
+pub fn parkinson_vol(highs: &[f64], lows: &[f64]) -> Option<f64> {
+    if highs.is_empty() || highs.len() != lows.len() {
+        return None;
+    }
+    let sum_sq: f64 = highs.iter().zip(lows)
+        .map(|(high, low)| f64::ln(high / low).powi(2))
+        .sum();
+    let variance = sum_sq / (4.0 * f64::ln(2.0) * highs.len() as f64);
+    return Some((variance * TRADING_DAYS).sqrt());
+}
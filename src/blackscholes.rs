@@ -1,188 +1,510 @@
 use core::f64;
-use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+use num_traits::Float;
+use serde::{Deserialize, Serialize};
+use statrs::function::erf::{erfc, erfc_inv};
 use rust_decimal::{Decimal, RoundingStrategy, dec};
 use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use wide::{CmpEq, CmpGt, CmpLt, f64x4};
 
-#[derive(Debug, Default, Clone)]
+/// Converts an f64 constant into the float type being computed with
+fn lit<F: Float>(x: f64) -> F {
+    F::from(x).unwrap()
+}
+
+/// True if F is at least as precise as f64. Less precise floats (f32) compute the normal distribution in their
+/// own type instead of round-tripping through the f64 statrs functions, which they'd gain no precision from.
+fn has_f64_precision<F: Float>() -> bool {
+    return F::epsilon() <= lit(f64::EPSILON);
+}
+
+/// Evaluates the polynomial with the given coefficients, highest power first, at x
+fn polynomial<F: Float>(x: F, coefficients: &[f64]) -> F {
+    return coefficients.iter().fold(F::zero(), |sum, &c| sum * x + lit(c));
+}
+
+/// Complementary error function within a relative error of 1.2e-7, about the precision of f32.
+/// Chebyshev fit from Numerical Recipes (erfcc).
+fn erfc_approx<F: Float>(x: F) -> F {
+    const COEFFICIENTS: [f64; 10] = [
+        0.17087277, -0.82215223, 1.48851587, -1.13520398, 0.27886807,
+        -0.18628806, 0.09678418, 0.37409196, 1.00002368, -1.26551223,
+    ];
+    let z = x.abs();
+    let t = F::one() / (F::one() + lit::<F>(0.5) * z);
+    let ans = t * (polynomial(t, &COEFFICIENTS) - z * z).exp();
+    if x < F::zero() {
+        return lit::<F>(2.0) - ans;
+    }
+    return ans;
+}
+
+/// Inverse of the standard normal cumulative distribution function, for floats less precise than f64.
+/// Giles' single precision fit of the inverse error function away from the tails, within a relative error of
+/// 1.3e-7. The tails use Acklam's rational approximation refined by a Newton step on erfc_approx.
+fn std_normal_inv_cdf_approx<F: Float>(p: F) -> F {
+    const CENTRAL: [f64; 9] = [
+        2.81022636e-8, 3.43273939e-7, -3.5233877e-6, -4.39150654e-6, 2.1858087e-4,
+        -1.25372503e-3, -4.17768164e-3, 2.46640727e-1, 1.50140941,
+    ];
+    const TAIL_NUMERATOR: [f64; 6] = [
+        -7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838,
+        -2.549671010466179, 4.374664141464968, 2.938163982698783,
+    ];
+    const TAIL_DENOMINATOR: [f64; 5] = [
+        7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416, 1.0,
+    ];
+
+    if p <= F::zero() || p >= F::one() {
+        return match p {
+            p if p == F::zero() => F::neg_infinity(),
+            p if p == F::one() => F::infinity(),
+            _ => F::nan(),
+        };
+    }
+    // -ln(1 - (1 - 2p)^2) without the cancellation near either tail
+    let w = -(lit::<F>(4.0) * p * (F::one() - p)).ln();
+    if w < lit(5.0) {
+        return -lit::<F>(f64::consts::SQRT_2) * polynomial(w - lit(2.5), &CENTRAL) * (F::one() - lit::<F>(2.0) * p);
+    }
+    // Refined from the tail's own side, where the cdf keeps its relative precision
+    let lower = p.min(F::one() - p);
+    let q = (lit::<F>(-2.0) * lower.ln()).sqrt();
+    let x = polynomial(q, &TAIL_NUMERATOR) / polynomial(q, &TAIL_DENOMINATOR);
+    let x = x - (lit::<F>(0.5) * erfc_approx(-x / lit(f64::consts::SQRT_2)) - lower) / std_normal_pdf(x);
+    if p > lit(0.5) {
+        return -x;
+    }
+    return x;
+}
+
+/// Standard normal cumulative distribution function. Computed directly from erfc, without any
+/// distribution to set up, for pricing loops.
+pub fn std_normal_cdf<F: Float>(x: F) -> F {
+    if !has_f64_precision::<F>() {
+        return lit::<F>(0.5) * erfc_approx(-x / lit(f64::consts::SQRT_2));
+    }
+    let x = x.to_f64().unwrap_or(f64::NAN);
+    return F::from(0.5 * erfc(-x / f64::consts::SQRT_2)).unwrap_or(F::nan());
+}
+
+/// Inverse of the standard normal cumulative distribution function, for probabilities within (0, 1)
+pub fn std_normal_inv_cdf<F: Float>(p: F) -> F {
+    if !has_f64_precision::<F>() {
+        return std_normal_inv_cdf_approx(p);
+    }
+    let p = p.to_f64().unwrap_or(f64::NAN);
+    return F::from(-f64::consts::SQRT_2 * erfc_inv(2.0 * p)).unwrap_or(F::nan());
+}
+
+/// Standard normal probability density function
+pub fn std_normal_pdf<F: Float>(x: F) -> F {
+    return (-x.powi(2) / lit(2.0)).exp() / lit(f64::consts::TAU.sqrt());
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[repr(C)]
 /// Environmental variables that affect an option's price. 
-/// All member variables should not be negative.
-pub struct Environment {
+/// The stock price and volatility should not be negative. The risk free rate and dividend yield may be.
+pub struct Environment<F = f64> {
     /// Current stock price
-    pub stock: f64,
+    pub stock: F,
     /// Constant riskfree rate
-    pub risk_free: f64,
+    pub risk_free: F,
     /// Constant stock price volatility. (E.g 4% would be 0.04).
-    pub vol: f64,
+    pub vol: F,
     /// Constant dividend yield of the stock. (E.g 16% would be 0.16).
-    pub div_yield: f64,
+    pub div_yield: F,
 }
 
-#[derive(Debug, Default, Clone)]
+impl<F: Float> Environment<F> {
+    /// Environment of an option on a futures contract, priced by the Black-76 model off the futures price.
+    /// Black-76 discounts the forward at the risk free rate without any dividend yield, which is the same as
+    /// Black-Scholes with the dividend yield set to the risk free rate, so every pricer here works on futures as is.
+    pub fn black76(futures: F, risk_free: F, vol: F) -> Self {
+        return Self { stock: futures, risk_free, vol, div_yield: risk_free };
+    }
+
+    /// Environment of an option on a currency pair, priced by the Garman-Kohlhagen model off the spot rate
+    /// (in domestic currency per unit of foreign). The foreign currency earns its risk free rate the same way a
+    /// stock pays its dividend yield, so the foreign rate takes the dividend yield's place.
+    pub fn garman_kohlhagen(spot: F, domestic_rate: F, foreign_rate: F, vol: F) -> Self {
+        return Self { stock: spot, risk_free: domestic_rate, vol, div_yield: foreign_rate };
+    }
+
+    /// Risk-neutral probability density of the stock price being at the given price after the given time.
+    /// This is the lognormal distribution the option prices are computed under.
+    pub fn terminal_density(&self, time: F, stock: F) -> F {
+        let two: F = lit(2.0);
+        let spread = self.vol * time.sqrt();
+        let mean = self.stock.ln() + (self.risk_free - self.div_yield - self.vol.powi(2) / two) * time;
+        let z = (stock.ln() - mean) / spread;
+        return (-z.powi(2) / two).exp() / (stock * spread * lit(f64::consts::TAU.sqrt()));
+    }
+
+    /// Stock price after the given time that the stock only ends below with the given probability,
+    /// under the same distribution as terminal_density
+    pub fn terminal_quantile(&self, time: F, p: F) -> F {
+        let spread = self.vol * time.sqrt();
+        let mean = self.stock.ln() + (self.risk_free - self.div_yield - self.vol.powi(2) / lit(2.0)) * time;
+        return (mean + spread * std_normal_inv_cdf(p)).exp();
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[repr(C)]
 /// Variables specific to an option contract that affects it's price. 
 /// All member variables should not be negative.
-pub struct Contract {
+pub struct Contract<F = f64> {
     /// Strike price of the option
-    pub strike: f64,
+    pub strike: F,
     /// Time left to expiry of the option
-    pub expiry: f64,
+    pub expiry: F,
     
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[repr(C)]
 /// A potential future result of a stock price.
 /// All member variables should not be negative.
-pub struct Movement {
+pub struct Movement<F = f64> {
     /// Final stock price after the movement
-    pub stock: f64,
+    pub stock: F,
     /// Time frame/length of the price movement
-    pub time: f64,
+    pub time: F,
 }
-impl Movement {
+impl<F: Float> Movement<F> {
     /// Updates the Environment and Contract struct given such that they reflect
     /// what happens at the movement end duration. So the only things that are overwritten are:
     ///  - environ.stock
     ///  - con.expiry
     /// 
     /// Contract expiry of the output is clamped to always be non-negative.
-    pub fn apply(&self, environ: Environment, con: Contract) -> (Environment, Contract) {
+    pub fn apply(&self, environ: Environment<F>, con: Contract<F>) -> (Environment<F>, Contract<F>) {
         return (
             Environment {
                 stock: self.stock,
                 ..environ
             },
             Contract {
-                expiry: f64::max(con.expiry - self.time, 0.0),
+                expiry: F::max(con.expiry - self.time, F::zero()),
                 ..con
             }
         )
     }
+
+    /// ROI of buying the stock in the given environment (or shorting it if short) and closing at the movement endpoint,
+    /// for the full stock price put up. Dividends are taken to be reinvested, and paid out by a short position.
+    pub fn stock_roi(&self, environ: &Environment<F>, short: bool) -> F {
+        let held = self.stock * (environ.div_yield * self.time).exp();
+        if short {
+            return F::one() + (environ.stock - held) / environ.stock;
+        }
+        return held / environ.stock;
+    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Scheduled event (E.g an earnings release) after which the volatility changes, usually dropping ("IV crush")
+pub struct VolEvent<F = f64> {
+    /// Time until the event
+    pub time: F,
+    /// Volatility once the event has passed
+    pub vol: F,
+}
+impl<F: Float> VolEvent<F> {
+    /// Environment an option is sold in at the movement endpoint. Takes on the event's volatility if the event
+    /// has passed by then, otherwise the given (pre-event) environment is kept.
+    pub fn exit_env(&self, environ: &Environment<F>, movement: &Movement<F>) -> Environment<F> {
+        if movement.time >= self.time {
+            return Environment { vol: self.vol, ..environ.clone() };
+        }
+        return environ.clone();
+    }
+
+    /// Flat volatility over the given time from now, blending the given (pre-event) volatility with the event's by
+    /// the time spent either side of it. What an option expiring then is priced at to hold through the event.
+    pub fn term_vol(&self, vol: F, time: F) -> F {
+        if time <= F::zero() || time <= self.time {
+            return vol;
+        }
+        let before = self.time.max(F::zero());
+        return ((vol * vol * before + self.vol * self.vol * (time - before)) / time).sqrt();
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+/// Sensitivities of an option's price under the black-scholes model.
+/// Each is the rate of change per unit of the variable (E.g vega is per 1.0 of volatility, not per 1%).
+pub struct Greeks<F = f64> {
+    /// With respect to the stock price
+    pub delta: F,
+    /// Second derivative with respect to the stock price
+    pub gamma: F,
+    /// With respect to the passing of time in years. Usually negative.
+    pub theta: F,
+    /// With respect to volatility
+    pub vega: F,
+    /// With respect to the risk free rate
+    pub rho: F,
+}
 
+/// Number of contracts priced at once by batch pricing
+const BATCH_LANES: usize = 4;
+
+/// Prices calls (or puts if not is_call) for every contract under the same environment, BATCH_LANES contracts at a time
+/// with SIMD. Only the logs and the normal CDF are evaluated lane by lane. Computed in f64 whatever the float type given.
+#[allow(non_snake_case)]
+fn price_batch_simd<F: Float>(env: &Environment<F>, contracts: &[Contract<F>], is_call: bool) -> Vec<F> {
+    let to_f64 = |x: F| x.to_f64().unwrap_or(f64::NAN);
+    let (stock, risk_free, vol, div_yield) = (to_f64(env.stock), to_f64(env.risk_free), to_f64(env.vol), to_f64(env.div_yield));
+    let ln_stock = f64x4::splat(stock.ln());
+    let drift = f64x4::splat(risk_free - div_yield + vol.powi(2) / 2.0);
+    let cdf = |x: f64x4| f64x4::from(x.to_array().map(std_normal_cdf));
+
+    let mut out = Vec::with_capacity(contracts.len());
+    for chunk in contracts.chunks(BATCH_LANES) {
+        // Unused lanes of the last chunk are filled with a harmless contract
+        let (mut strikes, mut expiries) = ([1.0; BATCH_LANES], [1.0; BATCH_LANES]);
+        for (i, contract) in chunk.iter().enumerate() {
+            (strikes[i], expiries[i]) = (to_f64(contract.strike), to_f64(contract.expiry));
+        }
+        let (strike, time_left) = (f64x4::from(strikes), f64x4::from(expiries));
+        // Taken lane by lane to match bsm_price for strikes of 0
+        let ln_strike = f64x4::from(strikes.map(f64::ln));
+
+        let vol_sqrt_t = f64x4::splat(vol) * time_left.sqrt();
+        let stock_PV = f64x4::splat(stock) * (f64x4::splat(-div_yield) * time_left).exp();
+        let strike_PV = strike * (f64x4::splat(-risk_free) * time_left).exp();
+        let d_1 = (ln_stock - ln_strike + time_left * drift) / vol_sqrt_t;
+        let d_2 = d_1 - vol_sqrt_t;
+        // Lanes with a certain stock price take the limits of d_1 and d_2, as in SharedTerms::contract_terms
+        let certain = vol_sqrt_t.cmp_eq(f64x4::ZERO);
+        let d_limit = stock_PV.cmp_gt(strike_PV).blend(
+            f64x4::splat(f64::INFINITY),
+            stock_PV.cmp_lt(strike_PV).blend(f64x4::splat(f64::NEG_INFINITY), f64x4::ZERO),
+        );
+        let (d_1, d_2) = (certain.blend(d_limit, d_1), certain.blend(d_limit, d_2));
+        let prices = match is_call {
+            true => cdf(d_1) * stock_PV - cdf(d_2) * strike_PV,
+            false => cdf(-d_2) * strike_PV - cdf(-d_1) * stock_PV,
+        };
+        out.extend(prices.to_array()[..chunk.len()].iter().map(|&price| F::from(price).unwrap_or(F::nan())));
+    }
+    return out;
+}
+
+/// Limit of d_1 and d_2 as the volatility over the time left goes to 0. Infinite either side of the money, and 0 at
+/// the money so that both the call and put are worth nothing.
+#[allow(non_snake_case)]
+fn deterministic_d<F: Float>(stock_PV: F, strike_PV: F) -> F {
+    if stock_PV > strike_PV {
+        return F::infinity();
+    }
+    if stock_PV < strike_PV {
+        return F::neg_infinity();
+    }
+    return F::zero();
+}
+
+/// Terms of the black-scholes formula that only depend on the environment.
+/// Computed once when pricing many contracts under the same environment.
+struct SharedTerms<F> {
+    ln_stock: F,
+    /// Drift term of d_1 before being multiplied by time left
+    drift: F,
+}
+impl<F: Float> SharedTerms<F> {
+    fn new(env: &Environment<F>) -> Self {
+        Self {
+            ln_stock: env.stock.ln(),
+            drift: env.risk_free - env.div_yield + (env.vol.powi(2) / lit(2.0)),
+        }
+    }
+
+    /// Returns (d_1, d_2, stock_PV, strike_PV) for the given contract.
+    /// At zero expiry or volatility the stock price is certain, so d_1 and d_2 take their limits. Prices are then
+    /// the intrinsic value of the (discounted) forward.
+    #[allow(non_snake_case)]
+    fn contract_terms(&self, env: &Environment<F>, contract: &Contract<F>) -> (F, F, F, F) {
+        let strike = contract.strike;
+        let time_left = contract.expiry;
+        let vol_sqrt_t = env.vol * time_left.sqrt();
+        let stock_PV = env.stock * F::exp(-env.div_yield * time_left);
+        let strike_PV = strike * F::exp(-env.risk_free * time_left);
+        if vol_sqrt_t.is_zero() {
+            let d = deterministic_d(stock_PV, strike_PV);
+            return (d, d, stock_PV, strike_PV);
+        }
+        let d_1 = (self.ln_stock - strike.ln() + time_left * self.drift) / vol_sqrt_t;
+        let d_2 = d_1 - vol_sqrt_t;
+        return (d_1, d_2, stock_PV, strike_PV);
+    }
+
+    #[allow(non_snake_case)]
+    fn call_price(&self, env: &Environment<F>, contract: &Contract<F>) -> F {
+        let (d_1, d_2, stock_PV, strike_PV) = self.contract_terms(env, contract);
+        return std_normal_cdf(d_1) * stock_PV - std_normal_cdf(d_2) * strike_PV;
+    }
+
+    #[allow(non_snake_case)]
+    fn put_price(&self, env: &Environment<F>, contract: &Contract<F>) -> F {
+        let (d_1, d_2, stock_PV, strike_PV) = self.contract_terms(env, contract);
+        return std_normal_cdf(-d_2) * strike_PV - std_normal_cdf(-d_1) * stock_PV;
+    }
+
+    /// Greeks shared by calls and puts, returned as (gamma, vega, time decay term of theta)
+    #[allow(non_snake_case)]
+    fn common_greeks(&self, env: &Environment<F>, contract: &Contract<F>) -> (F, F, F) {
+        let (d_1, _, stock_PV, _) = self.contract_terms(env, contract);
+        let sqrt_t = contract.expiry.sqrt();
+        let gamma = stock_PV * std_normal_pdf(d_1) / (env.stock * env.stock * env.vol * sqrt_t);
+        let vega = stock_PV * std_normal_pdf(d_1) * sqrt_t;
+        let decay = -stock_PV * std_normal_pdf(d_1) * env.vol / (lit::<F>(2.0) * sqrt_t);
+        return (gamma, vega, decay);
+    }
+
+    #[allow(non_snake_case)]
+    fn call_greeks(&self, env: &Environment<F>, contract: &Contract<F>) -> Greeks<F> {
+        let (d_1, d_2, stock_PV, strike_PV) = self.contract_terms(env, contract);
+        let (gamma, vega, decay) = self.common_greeks(env, contract);
+        return Greeks {
+            delta: stock_PV / env.stock * std_normal_cdf(d_1),
+            gamma,
+            theta: decay - env.risk_free * strike_PV * std_normal_cdf(d_2) + env.div_yield * stock_PV * std_normal_cdf(d_1),
+            vega,
+            rho: contract.expiry * strike_PV * std_normal_cdf(d_2),
+        };
+    }
+
+    #[allow(non_snake_case)]
+    fn put_greeks(&self, env: &Environment<F>, contract: &Contract<F>) -> Greeks<F> {
+        let (d_1, d_2, stock_PV, strike_PV) = self.contract_terms(env, contract);
+        let (gamma, vega, decay) = self.common_greeks(env, contract);
+        return Greeks {
+            delta: -stock_PV / env.stock * std_normal_cdf(-d_1),
+            gamma,
+            theta: decay + env.risk_free * strike_PV * std_normal_cdf(-d_2) - env.div_yield * stock_PV * std_normal_cdf(-d_1),
+            vega,
+            rho: -contract.expiry * strike_PV * std_normal_cdf(-d_2),
+        };
+    }
+}
+
+/// Pricing functions of an option under the black-scholes model.
+/// 
+/// All functions are generic over the float type used so precision can be traded for speed (e.g f32 on WASM).
 pub trait BlackScholes {
-    fn bsm_price(env: &Environment, contract: &Contract) -> f64;
+    fn bsm_price<F: Float>(env: &Environment<F>, contract: &Contract<F>) -> F;
+    /// Prices every contract given under the same environment. Equivalent to calling bsm_price on each
+    /// contract but shares the setup work between them, and prices several contracts at once with SIMD.
+    fn bsm_price_batch<F: Float>(env: &Environment<F>, contracts: &[Contract<F>]) -> Vec<F>;
     #[allow(non_snake_case)]
-    fn bsm_price_k(env: &Environment, contract: &Contract) -> f64;
+    fn bsm_price_k<F: Float>(env: &Environment<F>, contract: &Contract<F>) -> F;
     #[allow(non_snake_case)]
-    fn bsm_price_t(env: &Environment, contract: &Contract) -> f64;
+    fn bsm_price_t<F: Float>(env: &Environment<F>, contract: &Contract<F>) -> F;
+    fn greeks<F: Float>(env: &Environment<F>, contract: &Contract<F>) -> Greeks<F>;
 }
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Call;
 impl BlackScholes for Call {
     /// Returns the price of a call option under the black-scholes pricing model.
+    /// At zero expiry or volatility this is the intrinsic value, against the discounted forward if any time is left.
     /// 
     /// NaN is return upon unexpected/erroneous arguments. E.g negative volatility.
-    #[allow(non_snake_case)]
-    fn bsm_price(env: &Environment, contract: &Contract) -> f64 {
-        let stock = env.stock;
-        let risk_free = env.risk_free;
-        let div_yield = env.div_yield;
-        let vol = env.vol;
-        let strike = contract.strike;
-        let time_left = contract.expiry;
-        let d_1 = (f64::ln(stock / strike) + time_left * (risk_free - div_yield + (vol.powi(2) / 2.0))) / (vol * time_left.sqrt());
-        let d_2 = d_1 - vol * time_left.sqrt();
-        let std_normal_dist = Normal::new(0.0, 1.0).unwrap();
-        let stock_PV = stock * f64::exp(-div_yield * time_left);
-        let strike_PV = strike * f64::exp(-risk_free * time_left);
-        let call_price = std_normal_dist.cdf(d_1) * stock_PV - std_normal_dist.cdf(d_2) * strike_PV;
-        return call_price;
+    fn bsm_price<F: Float>(env: &Environment<F>, contract: &Contract<F>) -> F {
+        return SharedTerms::new(env).call_price(env, contract);
+    }
+    /// Returns the prices of many call options under the black-scholes pricing model.
+    /// 
+    /// NaN is return for a contract upon unexpected/erroneous arguments. E.g negative volatility.
+    fn bsm_price_batch<F: Float>(env: &Environment<F>, contracts: &[Contract<F>]) -> Vec<F> {
+        return price_batch_simd(env, contracts, true);
     }
     /// Returns the partial derivative of a call option with respect to the strike price under the black-scholes pricing model.
     /// 
     /// NaN is return upon unexpected/erroneous arguments. E.g negative volatility.
-    fn bsm_price_k(env: &Environment, contract: &Contract) -> f64 {
-        let stock = env.stock;
-        let risk_free = env.risk_free;
-        let div_yield = env.div_yield;
-        let vol = env.vol;
-        let strike = contract.strike;
-        let time_left = contract.expiry;
-        let d_1 = (f64::ln(stock / strike) + time_left * (risk_free - div_yield + (vol.powi(2) / 2.0))) / (vol * time_left.sqrt());
-        let d_2 = d_1 - vol * time_left.sqrt();
-        let std_normal_dist = Normal::new(0.0, 1.0).unwrap();
-        let dual_delta = -f64::exp(-risk_free * time_left) * std_normal_dist.cdf(d_2);
+    fn bsm_price_k<F: Float>(env: &Environment<F>, contract: &Contract<F>) -> F {
+        let shared = SharedTerms::new(env);
+        let (_, d_2, _, _) = shared.contract_terms(env, contract);
+        let dual_delta = -F::exp(-env.risk_free * contract.expiry) * std_normal_cdf(d_2);
         return dual_delta;
     }
     /// Returns the partial derivative of a call option with respect to time under the black-scholes pricing model.
     /// 
     /// NaN is return upon unexpected/erroneous arguments. E.g negative volatility.
-    fn bsm_price_t(env: &Environment, contract: &Contract) -> f64 {
+    fn bsm_price_t<F: Float>(env: &Environment<F>, contract: &Contract<F>) -> F {
+        let shared = SharedTerms::new(env);
+        let (d_1, d_2, _, _) = shared.contract_terms(env, contract);
         let stock = env.stock;
         let risk_free = env.risk_free;
         let div_yield = env.div_yield;
         let vol = env.vol;
         let strike = contract.strike;
         let time_left = contract.expiry;
-        let d_1 = (f64::ln(stock / strike) + time_left * (risk_free - div_yield + (vol.powi(2) / 2.0))) / (vol * time_left.sqrt());
-        let d_2 = d_1 - vol * time_left.sqrt();
-        let std_normal_dist = Normal::new(0.0, 1.0).unwrap();
-        let a = ((stock * vol * f64::exp(- div_yield*time_left))/(2.0*time_left.sqrt())) * std_normal_dist.pdf(d_1);
-        let b = risk_free * strike * f64::exp(-risk_free*time_left) * std_normal_dist.cdf(d_2);
-        let c = -div_yield * stock * f64::exp(-div_yield*time_left) * std_normal_dist.cdf(d_1);
+        let a = ((stock * vol * F::exp(- div_yield*time_left))/(lit::<F>(2.0)*time_left.sqrt())) * std_normal_pdf(d_1);
+        let b = risk_free * strike * F::exp(-risk_free*time_left) * std_normal_cdf(d_2);
+        let c = -div_yield * stock * F::exp(-div_yield*time_left) * std_normal_cdf(d_1);
         let theta = a + b + c;
         return theta;
     }
+    /// Returns the greeks of a call option under the black-scholes pricing model.
+    /// 
+    /// NaN is return upon unexpected/erroneous arguments. E.g an expired contract.
+    fn greeks<F: Float>(env: &Environment<F>, contract: &Contract<F>) -> Greeks<F> {
+        return SharedTerms::new(env).call_greeks(env, contract);
+    }
 }
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Put;
 impl BlackScholes for Put {
     /// Returns the price of a put option under the black-scholes pricing model.
+    /// At zero expiry or volatility this is the intrinsic value, against the discounted forward if any time is left.
     /// 
     /// NaN is return upon unexpected/erroneous arguments. E.g negative volatility.
-    #[allow(non_snake_case)]
-    fn bsm_price(env: &Environment, contract: &Contract) -> f64 {
-        let stock = env.stock;
-        let risk_free = env.risk_free;
-        let div_yield = env.div_yield;
-        let vol = env.vol;
-        let strike = contract.strike;
-        let time_left = contract.expiry;
-        let d_1 = (f64::ln(stock / strike) + time_left * (risk_free - div_yield + (vol.powi(2) / 2.0))) / (vol * time_left.sqrt());
-        let d_2 = d_1 - vol * time_left.sqrt();
-        let std_normal_dist = Normal::new(0.0, 1.0).unwrap();
-        let stock_PV = stock * f64::exp(-div_yield * time_left);
-        let strike_PV = strike * f64::exp(-risk_free * time_left);
-        let put_price = std_normal_dist.cdf(-d_2) * strike_PV - std_normal_dist.cdf(-d_1) * stock_PV;
-        return put_price;
+    fn bsm_price<F: Float>(env: &Environment<F>, contract: &Contract<F>) -> F {
+        return SharedTerms::new(env).put_price(env, contract);
+    }
+    /// Returns the prices of many put options under the black-scholes pricing model.
+    /// 
+    /// NaN is return for a contract upon unexpected/erroneous arguments. E.g negative volatility.
+    fn bsm_price_batch<F: Float>(env: &Environment<F>, contracts: &[Contract<F>]) -> Vec<F> {
+        return price_batch_simd(env, contracts, false);
     }
     /// Returns the partial derivative of a put option with respect to the strike price under the black-scholes pricing model.
     /// 
     /// NaN is return upon unexpected/erroneous arguments. E.g negative volatility.
-    fn bsm_price_k(env: &Environment, contract: &Contract) -> f64 {
-        let stock = env.stock;
-        let risk_free = env.risk_free;
-        let div_yield = env.div_yield;
-        let vol = env.vol;
-        let strike = contract.strike;
-        let time_left = contract.expiry;
-        let d_1 = (f64::ln(stock / strike) + time_left * (risk_free - div_yield + (vol.powi(2) / 2.0))) / (vol * time_left.sqrt());
-        let d_2 = d_1 - vol * time_left.sqrt();
-        let std_normal_dist = Normal::new(0.0, 1.0).unwrap();
-        let dual_delta = f64::exp(-risk_free * time_left) * (1.0 - std_normal_dist.cdf(d_2));
+    fn bsm_price_k<F: Float>(env: &Environment<F>, contract: &Contract<F>) -> F {
+        let shared = SharedTerms::new(env);
+        let (_, d_2, _, _) = shared.contract_terms(env, contract);
+        let dual_delta = F::exp(-env.risk_free * contract.expiry) * (F::one() - std_normal_cdf(d_2));
         return dual_delta;
     }
     /// Returns the partial derivative of a put option with respect to time under the black-scholes pricing model.
     /// 
     /// NaN is return upon unexpected/erroneous arguments. E.g negative volatility.
-    fn bsm_price_t(env: &Environment, contract: &Contract) -> f64 {
+    fn bsm_price_t<F: Float>(env: &Environment<F>, contract: &Contract<F>) -> F {
+        let shared = SharedTerms::new(env);
+        let (d_1, d_2, _, _) = shared.contract_terms(env, contract);
         let stock = env.stock;
         let risk_free = env.risk_free;
         let div_yield = env.div_yield;
         let vol = env.vol;
         let strike = contract.strike;
         let time_left = contract.expiry;
-        let d_1 = (f64::ln(stock / strike) + time_left * (risk_free - div_yield + (vol.powi(2) / 2.0))) / (vol * time_left.sqrt());
-        let d_2 = d_1 - vol * time_left.sqrt();
-        let std_normal_dist = Normal::new(0.0, 1.0).unwrap();
-        let a = ((stock * vol * f64::exp(-div_yield*time_left))/(2.0*time_left.sqrt())) * std_normal_dist.pdf(d_1);
-        let b = risk_free * strike * f64::exp(-risk_free*time_left) * std_normal_dist.pdf(-d_2);
-        let c = -div_yield * stock * f64::exp(-div_yield*time_left) * std_normal_dist.pdf(-d_1);
+        let a = ((stock * vol * F::exp(-div_yield*time_left))/(lit::<F>(2.0)*time_left.sqrt())) * std_normal_pdf(d_1);
+        let b = risk_free * strike * F::exp(-risk_free*time_left) * std_normal_pdf(-d_2);
+        let c = -div_yield * stock * F::exp(-div_yield*time_left) * std_normal_pdf(-d_1);
         let theta = a + b + c;
         return theta;
     }
+    /// Returns the greeks of a put option under the black-scholes pricing model.
+    /// 
+    /// NaN is return upon unexpected/erroneous arguments. E.g an expired contract.
+    fn greeks<F: Float>(env: &Environment<F>, contract: &Contract<F>) -> Greeks<F> {
+        return SharedTerms::new(env).put_greeks(env, contract);
+    }
 }
 
 /// Rounds the a given floating point price to what would be the real-world buy price
@@ -219,11 +541,45 @@ impl BlackScholesRounded for Call {}
 impl BlackScholesRounded for Put {}
 
 /// The minimum threshold for the exit price in the ROI calculation such that the exit price is rounded down to 0
-const ROI_FLOOR_THRESHOLD: f64 = 0.00001;
+pub const ROI_FLOOR_THRESHOLD: f64 = 0.00001;
+
+/// Number of gradient ascent steps taken when finding the best contract
+const OPTIMISER_ITERATIONS: usize = 5000;
+/// Magnitude of the ROI gradient below which the optimiser is considered to have converged
+const CONVERGED_GRADIENT: f64 = 1e-6;
+
+/// Number of stock prices sampled when searching for breakevens
+const BREAKEVEN_SAMPLES: usize = 200;
+/// Bisection steps taken to refine each breakeven
+const BREAKEVEN_ITERATIONS: usize = 60;
+
+/// Number of equally likely stock prices the tail is split into when computing the expected shortfall
+const TAIL_RISK_STEPS: usize = 200;
+
+/// Report of a single run of the gradient ascent in find_best_contract
+#[derive(Debug, Clone)]
+pub struct OptimiserStats<F = f64> {
+    /// Steps taken until the gradient stayed within tolerance, or every step if it never converged
+    pub iterations: usize,
+    /// True if the ROI gradient was within tolerance when the optimiser finished
+    pub converged: bool,
+    /// ROI gradient with respect to strike at the final step
+    pub gradient: F,
+}
+
+/// Downside of holding an option from entry to the movement endpoint, with the stock price distributed as in
+/// Environment::terminal_density
+#[derive(Debug, Clone)]
+pub struct TailRisk<F = f64> {
+    /// Loss per option only exceeded with a probability of 1 - confidence
+    pub value_at_risk: F,
+    /// Average loss per option in the cases beyond the value at risk (also known as conditional VaR)
+    pub expected_shortfall: F,
+}
 
 pub trait BlackScholesROI: BlackScholes {
     /// Returns the (buying_price, selling_price) from purchasing the option imediately in the given environment and then selling at the movement endpoint
-    fn buy_sell_prices(start_env: &Environment, end_env: &Environment, contract: &Contract, movement: &Movement) -> (f64, f64) {
+    fn buy_sell_prices<F: Float>(start_env: &Environment<F>, end_env: &Environment<F>, contract: &Contract<F>, movement: &Movement<F>) -> (F, F) {
         let start_env = start_env.clone();
         let start_con = contract.clone();
         let (end_env, end_con) = movement.apply(end_env.clone(), contract.clone());
@@ -232,28 +588,56 @@ pub trait BlackScholesROI: BlackScholes {
         return (entry, exit);
     }
 
+    /// Batch version of buy_sell_prices. Returns the (buying_price, selling_price) of each contract given.
+    fn buy_sell_prices_batch<F: Float>(start_env: &Environment<F>, end_env: &Environment<F>, contracts: &[Contract<F>], movement: &Movement<F>) -> Vec<(F, F)> {
+        let end_env = Environment {
+            stock: movement.stock,
+            ..end_env.clone()
+        };
+        let end_contracts: Vec<Contract<F>> = contracts.iter()
+            .map(|contract| movement.apply(end_env.clone(), contract.clone()).1)
+            .collect();
+        let entries = Self::bsm_price_batch(start_env, contracts);
+        let exits = Self::bsm_price_batch(&end_env, &end_contracts);
+        return entries.into_iter().zip(exits).collect();
+    }
+
     /// Returns the ROI from purchasing the option imediately in the given environment and then selling at the movement endpoint
     #[allow(dead_code)]
-    fn roi(start_env: &Environment, end_env: &Environment, contract: &Contract, movement: &Movement) -> f64 {
+    fn roi<F: Float>(start_env: &Environment<F>, end_env: &Environment<F>, contract: &Contract<F>, movement: &Movement<F>) -> F {
         let (mut entry, mut exit) = Self::buy_sell_prices(start_env, end_env, contract, movement);
         // Ensure non-zero division
-        entry = f64::max(ROI_FLOOR_THRESHOLD, entry);
-        if exit <= ROI_FLOOR_THRESHOLD {
-            exit = 0.0
+        entry = F::max(lit(ROI_FLOOR_THRESHOLD), entry);
+        if exit <= lit(ROI_FLOOR_THRESHOLD) {
+            exit = F::zero()
         }
         let roi = exit / entry;
         return roi;
     }
 
+    /// Batch version of roi. Returns the ROI of each contract given.
+    #[allow(dead_code)]
+    fn roi_batch<F: Float>(start_env: &Environment<F>, end_env: &Environment<F>, contracts: &[Contract<F>], movement: &Movement<F>) -> Vec<F> {
+        return Self::buy_sell_prices_batch(start_env, end_env, contracts, movement)
+            .into_iter()
+            .map(|(entry, exit)| {
+                // Ensure non-zero division
+                let entry = F::max(lit(ROI_FLOOR_THRESHOLD), entry);
+                let exit = if exit <= lit(ROI_FLOOR_THRESHOLD) { F::zero() } else { exit };
+                exit / entry
+            })
+            .collect();
+    }
+
     /// Compute first partial derivative of ROI with respect to the strike price of the chosen option
-    fn roi_k(start_env: &Environment, end_env: &Environment, contract: &Contract, movement: &Movement) -> f64 {
+    fn roi_k<F: Float>(start_env: &Environment<F>, end_env: &Environment<F>, contract: &Contract<F>, movement: &Movement<F>) -> F {
         let (mut entry, mut exit) = Self::buy_sell_prices(start_env, end_env, contract, movement);
-        entry = f64::max(ROI_FLOOR_THRESHOLD, entry);
-        if exit <= ROI_FLOOR_THRESHOLD {
-            exit = 0.0
+        entry = F::max(lit(ROI_FLOOR_THRESHOLD), entry);
+        if exit <= lit(ROI_FLOOR_THRESHOLD) {
+            exit = F::zero()
         }
         let (updated_end_env, end_contract) = movement.apply(end_env.clone(), contract.clone());
-        let entry_k = Self::bsm_price_k(&start_env, contract);
+        let entry_k = Self::bsm_price_k(start_env, contract);
         let exit_k = Self::bsm_price_k(&updated_end_env, &end_contract);
         // Using quotient rule...
         let roi_k = (entry*exit_k - exit*entry_k) / entry.powi(2);
@@ -262,39 +646,113 @@ pub trait BlackScholesROI: BlackScholes {
 
     #[allow(dead_code)]
     /// Compute first partial derivative of ROI with respect to the expiry time of the chosen call option
-    fn roi_t(start_env: &Environment, end_env: &Environment, contract: &Contract, movement: &Movement) -> f64 {
+    fn roi_t<F: Float>(start_env: &Environment<F>, end_env: &Environment<F>, contract: &Contract<F>, movement: &Movement<F>) -> F {
         let (mut entry, mut exit) = Self::buy_sell_prices(start_env, end_env, contract, movement);
-        entry = f64::max(ROI_FLOOR_THRESHOLD, entry);
-        if exit <= ROI_FLOOR_THRESHOLD {
-            exit = 0.0
+        entry = F::max(lit(ROI_FLOOR_THRESHOLD), entry);
+        if exit <= lit(ROI_FLOOR_THRESHOLD) {
+            exit = F::zero()
         }
         let (updated_end_env, end_contract) = movement.apply(end_env.clone(), contract.clone());
-        let entry_t = Self::bsm_price_t(&start_env, contract);
+        let entry_t = Self::bsm_price_t(start_env, contract);
         let exit_t = Self::bsm_price_t(&updated_end_env, &end_contract);
         // Using quotient rule...
         let roi_t = (entry*exit_t - exit*entry_t) / entry.powi(2);
         return roi_t
     }
 
+    /// Finds the stock prices at the movement endpoint, within the given range, where selling the option exactly
+    /// pays back its buying price. Sign changes of the profit are found by sampling the range and then refined by bisection.
+    fn breakevens<F: Float>(start_env: &Environment<F>, end_env: &Environment<F>, contract: &Contract<F>, movement: &Movement<F>, range: (F, F)) -> Vec<F> {
+        let profit = |stock: F| {
+            let (entry, exit) = Self::buy_sell_prices(start_env, end_env, contract, &Movement { stock, ..movement.clone() });
+            exit - entry
+        };
+        let (start, end) = range;
+        let step = (end - start) / lit(BREAKEVEN_SAMPLES as f64);
+        let mut out = Vec::new();
+        let (mut low, mut low_profit) = (start, profit(start));
+        for i in 1..=BREAKEVEN_SAMPLES {
+            let high = start + step * lit(i as f64);
+            let high_profit = profit(high);
+            if low_profit == F::zero() {
+                out.push(low);
+            } else if low_profit.signum() != high_profit.signum() && !(low_profit.is_nan() || high_profit.is_nan()) && high_profit != F::zero() {
+                // Bisect keeping the root between a and b
+                let (mut a, mut b) = (low, high);
+                for _ in 0..BREAKEVEN_ITERATIONS {
+                    let mid = (a + b) / lit(2.0);
+                    if profit(mid).signum() == low_profit.signum() {
+                        a = mid;
+                    } else {
+                        b = mid;
+                    }
+                }
+                out.push((a + b) / lit(2.0));
+            }
+            (low, low_profit) = (high, high_profit);
+        }
+        if low_profit == F::zero() {
+            out.push(low);
+        }
+        return out;
+    }
+
+    /// Value at risk and expected shortfall at the given confidence (E.g 0.95) of buying the option immediately
+    /// and selling at the movement endpoint. The movement's stock price is ignored, with the stock price distributed
+    /// from the start environment over the movement's time frame instead.
+    fn tail_risk<F: Float>(start_env: &Environment<F>, end_env: &Environment<F>, contract: &Contract<F>, movement: &Movement<F>, confidence: F) -> TailRisk<F> {
+        let entry = Self::bsm_price(start_env, contract);
+        let loss = |p: F| {
+            let stock = start_env.terminal_quantile(movement.time, p);
+            let (end_env, end_con) = Movement { stock, time: movement.time }.apply(end_env.clone(), contract.clone());
+            return entry - Self::bsm_price(&end_env, &end_con);
+        };
+        // The option's value only moves one way with the stock price, so the losses are worst at whichever end of
+        // the distribution prices the option lower
+        let tail = F::one() - confidence;
+        let lower_tail = loss(tail) >= loss(confidence);
+        let quantile = |p: F| if lower_tail { p } else { F::one() - p };
+
+        // Average the loss over equally likely slices of the tail
+        let steps: F = lit(TAIL_RISK_STEPS as f64);
+        let shortfall = (0..TAIL_RISK_STEPS)
+            .map(|i| loss(quantile(tail * (lit::<F>(i as f64) + lit(0.5)) / steps)))
+            .fold(F::zero(), |sum, x| sum + x) / steps;
+        return TailRisk { value_at_risk: loss(quantile(tail)), expected_shortfall: shortfall };
+    }
+
     /// Computes the contract that generates the highest ROI (using gradient ascent)
-    fn find_best_contract(start_env: &Environment, end_env: &Environment, movement: &Movement) -> Contract {
+    fn find_best_contract<F: Float>(start_env: &Environment<F>, end_env: &Environment<F>, movement: &Movement<F>) -> Contract<F> {
+        return Self::find_best_contract_with_stats(start_env, end_env, movement).0;
+    }
+
+    /// Same as find_best_contract, but also reports how the gradient ascent went
+    fn find_best_contract_with_stats<F: Float>(start_env: &Environment<F>, end_env: &Environment<F>, movement: &Movement<F>) -> (Contract<F>, OptimiserStats<F>) {
         let start_env = start_env.clone();
         let end_env = end_env.clone();
-        let mut answer = Contract {strike: movement.stock, expiry: movement.time + 0.0001};
+        let mut answer = Contract {strike: movement.stock, expiry: movement.time + lit(0.0001)};
+        let mut stats = OptimiserStats { iterations: 0, converged: false, gradient: F::nan() };
 
-        for _ in 0..5000 {
+        for i in 0..OPTIMISER_ITERATIONS {
             // Optimal option expiry is automatically done when matching the price movement duration
             // So the only parameter left to optimise is option strike.
             // Todo: Pretty confident on this fact but need to find formal proof later
             let grad = Self::roi_k(&start_env, &end_env, &answer, movement);
-            let mut step_mult: f64 = 0.1;
-            let step_max = 0.01;
+            let mut step_mult: F = lit(0.1);
+            let step_max: F = lit(0.01);
             // Adjust step multiplier to ensure step magnitude does not exceed step_max
             step_mult = step_mult.min(step_max / grad.abs());
-            answer.strike += step_mult * grad;
+            answer.strike = answer.strike + step_mult * grad;
+
+            // Count the steps until the gradient settles within tolerance for good
+            stats.gradient = grad;
+            if grad.abs() >= lit(CONVERGED_GRADIENT) || grad.is_nan() {
+                stats.iterations = i + 1;
+            }
         }
+        stats.converged = stats.gradient.abs() < lit(CONVERGED_GRADIENT);
 
-        return answer;
+        return (answer, stats);
     }
 }
 impl BlackScholesROI for Call {}
@@ -315,6 +773,142 @@ pub trait BlackScholesROIRounded: BlackScholesROI + BlackScholesRounded {
         let roi = exit / entry;
         return roi.to_f64().unwrap();
     }
+
+    /// Batch version of buy_sell_prices_practical. Returns the real-world (buying_price, selling_price) of each contract given.
+    #[allow(dead_code)]
+    fn buy_sell_prices_practical_batch(start_env: &Environment, end_env: &Environment, contracts: &[Contract], movement: &Movement) -> Vec<(Decimal, Decimal)> {
+        return Self::buy_sell_prices_batch(start_env, end_env, contracts, movement)
+            .into_iter()
+            .map(|(entry, exit)| (buy_rounding(entry), sell_rounding(exit)))
+            .collect();
+    }
+
+    /// Batch version of roi_practical. Returns the practical/real-world ROI of each contract given.
+    #[allow(dead_code)]
+    fn roi_practical_batch(start_env: &Environment, end_env: &Environment, contracts: &[Contract], movement: &Movement) -> Vec<f64> {
+        return Self::buy_sell_prices_practical_batch(start_env, end_env, contracts, movement)
+            .into_iter()
+            // buy price is guaranteed to be >0.01
+            .map(|(entry, exit)| (exit / entry).to_f64().unwrap())
+            .collect();
+    }
 }
 impl BlackScholesROIRounded for Call {}
 impl BlackScholesROIRounded for Put {}
+
+/// Type of option chosen at runtime, pricing with the Call or Put model accordingly.
+/// Everything priced for an answer goes through the one value, so its prices, ROI and charts all come from the same model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OptionType {
+    #[default]
+    Call,
+    Put,
+}
+impl OptionType {
+    pub fn from_is_call(is_call: bool) -> Self {
+        if is_call {
+            return Self::Call;
+        }
+        return Self::Put;
+    }
+
+    pub fn is_call(self) -> bool {
+        return self == Self::Call;
+    }
+
+    /// See BlackScholes::bsm_price
+    pub fn bsm_price(self, env: &Environment, contract: &Contract) -> f64 {
+        match self {
+            Self::Call => return Call::bsm_price(env, contract),
+            Self::Put => return Put::bsm_price(env, contract),
+        }
+    }
+
+    /// See BlackScholes::bsm_price_batch
+    pub fn bsm_price_batch(self, env: &Environment, contracts: &[Contract]) -> Vec<f64> {
+        match self {
+            Self::Call => return Call::bsm_price_batch(env, contracts),
+            Self::Put => return Put::bsm_price_batch(env, contracts),
+        }
+    }
+
+    /// See BlackScholes::greeks
+    pub fn greeks(self, env: &Environment, contract: &Contract) -> Greeks {
+        match self {
+            Self::Call => return Call::greeks(env, contract),
+            Self::Put => return Put::greeks(env, contract),
+        }
+    }
+
+    /// See BlackScholesRounded::bsm_price_buy
+    pub fn bsm_price_buy(self, env: &Environment, contract: &Contract) -> Decimal {
+        match self {
+            Self::Call => return Call::bsm_price_buy(env, contract),
+            Self::Put => return Put::bsm_price_buy(env, contract),
+        }
+    }
+
+    /// See BlackScholesRounded::bsm_price_sell
+    pub fn bsm_price_sell(self, env: &Environment, contract: &Contract) -> Decimal {
+        match self {
+            Self::Call => return Call::bsm_price_sell(env, contract),
+            Self::Put => return Put::bsm_price_sell(env, contract),
+        }
+    }
+
+    /// See BlackScholesROI::buy_sell_prices
+    pub fn buy_sell_prices(self, start_env: &Environment, end_env: &Environment, contract: &Contract, movement: &Movement) -> (f64, f64) {
+        match self {
+            Self::Call => return Call::buy_sell_prices(start_env, end_env, contract, movement),
+            Self::Put => return Put::buy_sell_prices(start_env, end_env, contract, movement),
+        }
+    }
+
+    /// See BlackScholesROI::breakevens
+    pub fn breakevens(self, start_env: &Environment, end_env: &Environment, contract: &Contract, movement: &Movement, range: (f64, f64)) -> Vec<f64> {
+        match self {
+            Self::Call => return Call::breakevens(start_env, end_env, contract, movement, range),
+            Self::Put => return Put::breakevens(start_env, end_env, contract, movement, range),
+        }
+    }
+
+    /// See BlackScholesROI::tail_risk
+    pub fn tail_risk(self, start_env: &Environment, end_env: &Environment, contract: &Contract, movement: &Movement, confidence: f64) -> TailRisk {
+        match self {
+            Self::Call => return Call::tail_risk(start_env, end_env, contract, movement, confidence),
+            Self::Put => return Put::tail_risk(start_env, end_env, contract, movement, confidence),
+        }
+    }
+
+    /// See BlackScholesROI::find_best_contract_with_stats
+    pub fn find_best_contract_with_stats(self, start_env: &Environment, end_env: &Environment, movement: &Movement) -> (Contract, OptimiserStats) {
+        match self {
+            Self::Call => return Call::find_best_contract_with_stats(start_env, end_env, movement),
+            Self::Put => return Put::find_best_contract_with_stats(start_env, end_env, movement),
+        }
+    }
+
+    /// See BlackScholesROIRounded::buy_sell_prices_practical
+    pub fn buy_sell_prices_practical(self, start_env: &Environment, end_env: &Environment, contract: &Contract, movement: &Movement) -> (Decimal, Decimal) {
+        match self {
+            Self::Call => return Call::buy_sell_prices_practical(start_env, end_env, contract, movement),
+            Self::Put => return Put::buy_sell_prices_practical(start_env, end_env, contract, movement),
+        }
+    }
+
+    /// See BlackScholesROIRounded::roi_practical
+    pub fn roi_practical(self, start_env: &Environment, end_env: &Environment, contract: &Contract, movement: &Movement) -> f64 {
+        match self {
+            Self::Call => return Call::roi_practical(start_env, end_env, contract, movement),
+            Self::Put => return Put::roi_practical(start_env, end_env, contract, movement),
+        }
+    }
+
+    /// See BlackScholesROIRounded::roi_practical_batch
+    pub fn roi_practical_batch(self, start_env: &Environment, end_env: &Environment, contracts: &[Contract], movement: &Movement) -> Vec<f64> {
+        match self {
+            Self::Call => return Call::roi_practical_batch(start_env, end_env, contracts, movement),
+            Self::Put => return Put::roi_practical_batch(start_env, end_env, contracts, movement),
+        }
+    }
+}
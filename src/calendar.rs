@@ -0,0 +1,107 @@
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Rule used to convert the gap between two calendar dates into a year fraction
+/// that can be fed into the pricing model.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum DayCount {
+    /// Actual calendar days divided by 365
+    #[default]
+    Act365,
+    /// Actual trading days (weekdays) divided by 252. Public holidays are ignored.
+    Act252,
+}
+impl std::fmt::Display for DayCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Act365 => "ACT/365",
+            Self::Act252 => "ACT/252 (trading days)",
+        })
+    }
+}
+impl DayCount {
+    const COUNT: usize = 2;
+
+    pub fn everything() -> [Self; Self::COUNT] {
+        [Self::Act365, Self::Act252]
+    }
+
+    /// Number of counted days that make up a single year
+    fn days_per_year(&self) -> f64 {
+        match self {
+            Self::Act365 => 365.0,
+            Self::Act252 => 252.0,
+        }
+    }
+
+    /// Counts the days between start (exclusive) and end (inclusive) under this convention.
+    ///
+    /// Negative if end comes before start.
+    pub fn day_count(&self, start: NaiveDate, end: NaiveDate) -> i64 {
+        match self {
+            Self::Act365 => (end - start).num_days(),
+            Self::Act252 => {
+                if end < start {
+                    return -Self::Act252.day_count(end, start);
+                }
+                // Every whole week contains exactly 5 trading days
+                let weeks = (end - start).num_days() / 7;
+                let mut count = 5 * weeks;
+                let mut date = start + Days::new(7 * weeks as u64);
+                while date < end {
+                    date = date + Days::new(1);
+                    if is_trading_day(date) {
+                        count += 1;
+                    }
+                }
+                return count;
+            }
+        }
+    }
+
+    /// Converts the time between two dates into a year fraction
+    pub fn year_fraction(&self, start: NaiveDate, end: NaiveDate) -> f64 {
+        return self.day_count(start, end) as f64 / self.days_per_year();
+    }
+
+    /// Finds the date that lies the given year fraction after start. The inverse of year_fraction
+    /// (up to rounding to the nearest counted day).
+    ///
+    /// Returns None if the date would fall outside of what chrono can represent.
+    pub fn date_after(&self, start: NaiveDate, years: f64) -> Option<NaiveDate> {
+        if !years.is_finite() || years < 0.0 {
+            return None;
+        }
+        let days = (years * self.days_per_year()).round();
+        // Roughly the limit of chrono's supported range
+        if days > 1.0e8 {
+            return None;
+        }
+        let days = days as u64;
+        match self {
+            Self::Act365 => start.checked_add_days(Days::new(days)),
+            Self::Act252 => {
+                // Every 5 trading days spans exactly one week
+                let mut date = start.checked_add_days(Days::new(7 * (days / 5)))?;
+                let mut counted = 5 * (days / 5);
+                while counted < days {
+                    date = date.checked_add_days(Days::new(1))?;
+                    if is_trading_day(date) {
+                        counted += 1;
+                    }
+                }
+                return Some(date);
+            }
+        }
+    }
+}
+
+/// Whether the market is assumed to be open on the given date (i.e a weekday)
+fn is_trading_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// The current local date used as the valuation date for any date based inputs
+pub fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
@@ -0,0 +1,119 @@
+//! Live option chains pulled from broker APIs, and ranking of the quoted contracts against a prediction.
+//! Only compiled with the `broker` feature on desktop builds.
+
+use std::future::Future;
+use std::pin::Pin;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+
+use crate::blackscholes::{Contract, Environment, Movement, OptionType};
+use crate::calendar::DayCount;
+use which_option::sabr::{self, Sabr};
+
+/// Elasticity smiles are fit with. Lognormal, as is usual for equities.
+const SMILE_BETA: f64 = 1.0;
+
+pub mod tradier;
+pub use tradier::TradierSandbox;
+
+/// A single quoted option contract within a chain
+#[derive(Debug, Clone)]
+pub struct ChainQuote {
+    pub is_call: bool,
+    pub strike: f64,
+    pub expiry: NaiveDate,
+    pub bid: f64,
+    pub ask: f64,
+    /// Implied volatility of the contract if the provider supplies it
+    pub iv: Option<f64>,
+}
+
+pub type ChainFuture = Pin<Box<dyn Future<Output = Result<Vec<ChainQuote>, String>> + Send>>;
+
+/// Source of live option chains
+pub trait ChainProvider {
+    /// Name of the provider to display in the GUI
+    fn name(&self) -> &'static str;
+    /// Asynchronously pulls every quoted contract for the given underlying symbol
+    fn fetch_chain(&self, symbol: String) -> ChainFuture;
+}
+
+/// A quoted contract alongside its expected result under the prediction
+#[derive(Debug, Clone)]
+pub struct RankedQuote {
+    pub quote: ChainQuote,
+    /// Price the contract is sold for at the prediction end
+    pub exit: Decimal,
+    /// ROI of buying at the quoted ask then selling at the prediction end
+    pub roi: f64,
+}
+
+/// SABR smile fit to the implied volatilities quoted at one expiry of a chain
+#[derive(Debug, Clone)]
+pub struct SmileFit {
+    pub expiry: NaiveDate,
+    pub sabr: Sabr,
+    /// Root mean squared difference from the quoted implied volatilities
+    pub rms_error: f64,
+}
+
+/// Fits a SABR smile to the expiry of the chain with the most quoted implied volatilities, calls and puts alike.
+/// The forward of each strike is taken off the given current environment.
+///
+/// Returns None if no expiry quotes enough implied volatilities to fit to.
+pub fn fit_smile(quotes: &[ChainQuote], today: NaiveDate, env: &Environment) -> Option<SmileFit> {
+    let mut expiries: Vec<NaiveDate> = quotes.iter().filter(|quote| quote.iv.is_some()).map(|quote| quote.expiry).collect();
+    expiries.sort();
+    expiries.dedup();
+    let expiry = expiries.into_iter()
+        .filter(|expiry| *expiry > today)
+        .max_by_key(|expiry| quotes.iter().filter(|quote| quote.expiry == *expiry && quote.iv.is_some()).count())?;
+    let vols: Vec<(f64, f64)> = quotes.iter()
+        .filter(|quote| quote.expiry == expiry)
+        .filter_map(|quote| Some((quote.strike, quote.iv.filter(|iv| *iv > 0.0)?)))
+        .collect();
+    let time = DayCount::Act365.year_fraction(today, expiry);
+    let forward = sabr::forward(env, time);
+    let sabr = Sabr::calibrate(SMILE_BETA, forward, time, &vols)?;
+    return Some(SmileFit { expiry, sabr, rms_error: sabr.rms_error(forward, time, &vols) });
+}
+
+/// Ranks the quoted contracts of the pricer's option type by the ROI of buying them at their ask price and selling
+/// them at the prediction end. Exit prices come from the pricing model with the given end environment, with its
+/// volatility taken off the smile at each contract's strike if one is given.
+///
+/// Contracts without an ask, or that expire before the prediction ends, are skipped. Highest ROI comes first.
+pub fn rank_chain(
+    quotes: &[ChainQuote],
+    pricer: OptionType,
+    today: NaiveDate,
+    end_env: &Environment,
+    movement: &Movement,
+    smile: Option<&Sabr>,
+) -> Vec<RankedQuote> {
+    let mut ranked: Vec<RankedQuote> = quotes.iter()
+        .filter(|quote| quote.is_call == pricer.is_call() && quote.ask > 0.0)
+        .filter_map(|quote| {
+            let contract = Contract {
+                strike: quote.strike,
+                expiry: DayCount::Act365.year_fraction(today, quote.expiry),
+            };
+            if contract.expiry < movement.time {
+                return None;
+            }
+            let (end_env, end_con) = movement.apply(end_env.clone(), contract);
+            let end_env = match smile {
+                Some(smile) if end_con.expiry > 0.0 => smile.environment(&end_env, &end_con),
+                _ => end_env,
+            };
+            let exit = pricer.bsm_price_sell(&end_env, &end_con);
+            let entry = Decimal::from_f64(quote.ask)?;
+            let roi = (exit / entry).to_f64()?;
+            Some(RankedQuote { quote: quote.clone(), exit, roi })
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.roi.total_cmp(&a.roi));
+    return ranked;
+}
@@ -0,0 +1,86 @@
+use chrono::NaiveDate;
+use serde_json::Value;
+
+use super::{ChainFuture, ChainProvider, ChainQuote};
+
+const TRADIER_SANDBOX_URL: &str = "https://sandbox.tradier.com/v1/markets/options";
+
+/// Environment variable the Tradier API access token is read from
+const TOKEN_ENV_VAR: &str = "TRADIER_TOKEN";
+
+/// Maximum number of expiry dates pulled per chain, to keep the number of requests reasonable
+const MAX_EXPIRIES: usize = 8;
+
+/// Pulls option chains from the Tradier developer sandbox (delayed data)
+pub struct TradierSandbox {
+    token: String,
+}
+impl TradierSandbox {
+    /// Creates a provider using the access token held in the TRADIER_TOKEN environment variable
+    pub fn from_env() -> Result<Self, String> {
+        let token = std::env::var(TOKEN_ENV_VAR)
+            .map_err(|_| format!("Set {} to a Tradier sandbox access token", TOKEN_ENV_VAR))?;
+        return Ok(Self { token });
+    }
+}
+impl ChainProvider for TradierSandbox {
+    fn name(&self) -> &'static str {
+        "Tradier (sandbox)"
+    }
+
+    fn fetch_chain(&self, symbol: String) -> ChainFuture {
+        let token = self.token.clone();
+        Box::pin(async move {
+            let symbol = symbol.trim().to_uppercase();
+            let client = reqwest::Client::new();
+            let get = |url: String| {
+                client.get(url)
+                    .bearer_auth(&token)
+                    .header(reqwest::header::ACCEPT, "application/json")
+                    .send()
+            };
+
+            let expirations: Value = get(format!("{}/expirations?symbol={}", TRADIER_SANDBOX_URL, symbol))
+                .await.map_err(|e| e.to_string())?
+                .error_for_status().map_err(|e| e.to_string())?
+                .json().await.map_err(|e| e.to_string())?;
+            let dates: Vec<String> = one_or_many(&expirations["expirations"]["date"])
+                .filter_map(|date| date.as_str().map(String::from))
+                .take(MAX_EXPIRIES)
+                .collect();
+            if dates.is_empty() {
+                return Err(format!("No option expiries found for {}", symbol));
+            }
+
+            let mut quotes = Vec::new();
+            for date in dates {
+                let chain: Value = get(format!("{}/chains?symbol={}&expiration={}&greeks=true", TRADIER_SANDBOX_URL, symbol, date))
+                    .await.map_err(|e| e.to_string())?
+                    .error_for_status().map_err(|e| e.to_string())?
+                    .json().await.map_err(|e| e.to_string())?;
+                quotes.extend(one_or_many(&chain["options"]["option"]).filter_map(parse_option));
+            }
+            return Ok(quotes);
+        })
+    }
+}
+
+/// Tradier collapses single element arrays into a lone object. Iterates over either form.
+fn one_or_many(value: &Value) -> Box<dyn Iterator<Item = &Value> + '_> {
+    match value {
+        Value::Array(values) => Box::new(values.iter()),
+        Value::Null => Box::new(std::iter::empty()),
+        single => Box::new(std::iter::once(single)),
+    }
+}
+
+fn parse_option(option: &Value) -> Option<ChainQuote> {
+    return Some(ChainQuote {
+        is_call: option["option_type"].as_str()? == "call",
+        strike: option["strike"].as_f64()?,
+        expiry: NaiveDate::parse_from_str(option["expiration_date"].as_str()?, "%Y-%m-%d").ok()?,
+        bid: option["bid"].as_f64().unwrap_or(0.0),
+        ask: option["ask"].as_f64().unwrap_or(0.0),
+        iv: option["greeks"]["mid_iv"].as_f64(),
+    });
+}
@@ -0,0 +1,127 @@
+use std::fmt::Display;
+use plotters::chart::ChartContext;
+use plotters::coord::CoordTranslate;
+use plotters::coord::cartesian::Cartesian2d;
+use plotters::coord::ranged1d::{Ranged, ValueFormatter};
+use plotters::prelude::*;
+use plotters::style::RGBColor;
+
+/// Determines chart title text size. Axis titles are drawn 10 smaller.
+pub const CHART_TITLE_SIZE: u32 = 25;
+
+pub const CHART_FONT_NAME: &str = crate::FIRA_SANS_NAME;
+
+/// Colours, line widths and fill opacities shared by every chart.
+/// Built from the colours of the app theme, with either the standard or a colour-blind safe set of line colours.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChartStyle {
+    /// Colour of the chart background (only filled in exported images)
+    pub background: RGBColor,
+    /// Colour of the text, axes and crosshair
+    pub text: RGBColor,
+    /// Colour of the mesh grid lines
+    pub grid: RGBColor,
+    /// Colour of the main payoff line, and of the highest heatmap values
+    pub primary: RGBColor,
+    /// Colour of the benchmark line, and of the lowest heatmap values
+    pub benchmark: RGBColor,
+    /// Colour of the probability density line, and of the marked prediction
+    pub density: RGBColor,
+    /// Colour of regions that make a profit
+    pub profit: RGBColor,
+    /// Colour of regions that make a loss
+    pub loss: RGBColor,
+    /// Stroke width of plotted lines in pixels
+    pub line_width: u32,
+    /// Opacity of the area filled below plotted lines, from 0 to 1
+    pub fill_opacity: f64,
+}
+impl Default for ChartStyle {
+    fn default() -> Self {
+        Self {
+            background: RGBColor(255, 255, 255),
+            text: RGBColor(0, 0, 0),
+            grid: RGBColor(0, 0, 255),
+            primary: RGBColor(0, 175, 255),
+            benchmark: RGBColor(220, 20, 20),
+            density: RGBColor(150, 60, 200),
+            profit: RGBColor(20, 160, 60),
+            loss: RGBColor(220, 20, 20),
+            line_width: 2,
+            fill_opacity: 0.175,
+        }
+    }
+}
+impl ChartStyle {
+    /// Standard line colours on the background and text colours of a theme
+    pub fn from_palette(palette: iced::theme::Palette) -> Self {
+        return Self {
+            background: to_rgb(palette.background),
+            text: to_rgb(palette.text),
+            ..Default::default()
+        };
+    }
+
+    /// Line colours from the Okabe-Ito palette, which stay distinguishable with the common forms of colour
+    /// blindness, on the background and text colours of a theme
+    pub fn color_blind(palette: iced::theme::Palette) -> Self {
+        return Self {
+            primary: RGBColor(0, 114, 178),
+            benchmark: RGBColor(213, 94, 0),
+            density: RGBColor(204, 121, 167),
+            profit: RGBColor(0, 114, 178),
+            loss: RGBColor(230, 159, 0),
+            line_width: 3,
+            ..Self::from_palette(palette)
+        };
+    }
+
+    /// Colour of a value on a scale from the benchmark colour at 0, through the background colour at the benchmark,
+    /// to the primary colour at the highest value max. Used to colour heatmap cells and stress grid cells.
+    pub fn scale_color(&self, value: f64, benchmark: f64, max: f64) -> RGBColor {
+        fn lerp(from: RGBColor, to: RGBColor, t: f64) -> RGBColor {
+            let t = t.clamp(0.0, 1.0);
+            let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+            return RGBColor(channel(from.0, to.0), channel(from.1, to.1), channel(from.2, to.2));
+        }
+        if value <= benchmark {
+            return lerp(self.benchmark, self.background, value / benchmark);
+        }
+        return lerp(self.background, self.primary, (value - benchmark) / (max - benchmark));
+    }
+
+    /// Draws the grid lines and both axes of a chart, with 10 y-axis ticks labelled by y_formatter
+    pub fn draw_mesh<'a, DB: DrawingBackend + 'a, X, Y>(&self, chart: &mut ChartContext<'a, DB, Cartesian2d<X, Y>>, y_formatter: &dyn Fn(&f64) -> String)
+    where X: Ranged<ValueType = f64> + ValueFormatter<f64>, Y: Ranged<ValueType = f64> + ValueFormatter<f64> {
+        chart
+            .configure_mesh()
+            .label_style((CHART_FONT_NAME).into_font().color(&self.text))
+            .bold_line_style(self.grid.mix(0.1))
+            .light_line_style(self.grid.mix(0.05))
+            .axis_style(ShapeStyle::from(self.grid.mix(0.45)).stroke_width(1))
+            .y_labels(10)
+            .y_label_formatter(y_formatter)
+            .draw()
+            .expect("failed to draw chart mesh");
+    }
+
+    /// Draws the legend of every series drawn so far. Labels should be made with legend_label.
+    pub fn draw_legend<'a, DB: DrawingBackend + 'a, CT: CoordTranslate>(&self, chart: &mut ChartContext<'a, DB, CT>) {
+        chart.configure_series_labels()
+            .border_style(self.text)
+            .background_style(self.background.mix(0.8))
+            .label_font((CHART_FONT_NAME, 15).into_font().color(&self.text))
+            .draw()
+            .expect("failed to draw line labels");
+    }
+}
+
+/// Label of a series in a chart legend, followed by empty spaces to act as margin before the legend border
+pub fn legend_label(label: impl Display) -> String {
+    return format!("{}   ", label);
+}
+
+fn to_rgb(color: iced::Color) -> RGBColor {
+    let [r, g, b, _] = color.into_rgba8();
+    return RGBColor(r, g, b);
+}
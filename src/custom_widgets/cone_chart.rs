@@ -0,0 +1,182 @@
+use iced::Element;
+use iced::widget::{column, text};
+use iced::widget::canvas::{Cache, Frame, Geometry};
+use iced::Size;
+use iced::Center;
+use plotters_iced2::{Renderer};
+use plotters_iced2::{Chart, ChartWidget, DrawingBackend, ChartBuilder};
+
+use crate::custom_widgets::ChartStyle;
+use crate::custom_widgets::chart_style::{CHART_TITLE_SIZE, legend_label};
+use crate::custom_widgets::payoff_chart::format_tick;
+
+/// Determines number of datapoints computed for the chart
+const CHART_RESOLUTION: i32 = 201;
+
+/// Chart widget to draw the cone of stock prices the implied volatility expects over time, against the prediction.
+/// Shades the 1σ and 2σ moves either side of the median of the lognormal stock price, so how aggressive the
+/// prediction is can be seen at a glance.
+pub struct ConeChart {
+    cache: Cache,
+    /// Stock price now, the tip of the cone
+    spot: f64,
+    /// Drift of the stock price per year (the risk free rate less the dividend yield)
+    drift: f64,
+    /// Volatility per year the cone widens at
+    vol: f64,
+    /// Years from now the cone is drawn until
+    time: f64,
+    /// Predicted stock price at the end of the time frame, marked as a point
+    prediction: Option<f64>,
+    /// Chart title
+    title: String,
+    /// Colours and line styling the chart is drawn with
+    style: ChartStyle,
+}
+impl Default for ConeChart {
+    fn default() -> Self {
+        Self {
+            cache: Cache::new(),
+            spot: 5.0,
+            drift: 0.0,
+            vol: 0.2,
+            time: 1.0,
+            prediction: None,
+            title: String::from("Expected Move"),
+            style: ChartStyle::default(),
+        }
+    }
+}
+impl ConeChart {
+    pub fn view<'a, Message: 'a>(&'a self) -> Element<'a, Message> {
+        column![
+            text!("{}", self.title).size(CHART_TITLE_SIZE),
+            ChartWidget::new(self),
+            text("Years from now").size(CHART_TITLE_SIZE - 10),
+        ].align_x(Center)
+        .into()
+    }
+
+    /// Sets the stock price, drift and volatility the cone is drawn from
+    pub fn set_stock(&mut self, spot: f64, drift: f64, vol: f64) -> &mut Self {
+        self.spot = spot;
+        self.drift = drift;
+        self.vol = vol;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the years from now the cone is drawn until, which the prediction is made at
+    pub fn set_time(&mut self, time: f64) -> &mut Self {
+        self.time = time;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the predicted stock price at the end of the time frame
+    pub fn set_prediction(&mut self, stock: f64) -> &mut Self {
+        self.prediction = Some(stock);
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the colours and line styling the chart is drawn with
+    pub fn set_style(&mut self, style: ChartStyle) -> &mut Self {
+        self.style = style;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Stock price the given number of standard deviations of log returns from the median at the given time
+    fn price_at(&self, time: f64, sigmas: f64) -> f64 {
+        let median = (self.drift - 0.5 * self.vol.powi(2)) * time;
+        return self.spot * (median + sigmas * self.vol * time.sqrt()).exp();
+    }
+
+    /// Standard deviations of log returns the prediction is from the median at the end of the time frame.
+    /// None without a prediction, or if the cone has no width.
+    fn prediction_sigmas(&self) -> Option<f64> {
+        let prediction = self.prediction?;
+        let spread = self.vol * self.time.sqrt();
+        if spread.is_nan() || spread <= 0.0 {
+            return None;
+        }
+        return Some(((prediction / self.spot).ln() - (self.drift - 0.5 * self.vol.powi(2)) * self.time) / spread);
+    }
+}
+impl<Message> Chart<Message> for ConeChart {
+    type State = ();
+
+    #[inline]
+    fn draw<R: Renderer, F: Fn(&mut Frame)>(
+        &self,
+        renderer: &R,
+        bounds: Size,
+        draw_fn: F,
+    ) -> Geometry {
+        renderer.draw_cache(&self.cache, bounds, draw_fn)
+    }
+
+    fn build_chart<DB: DrawingBackend>(&self, _: &Self::State, mut chart: ChartBuilder<DB>) {
+        use plotters::prelude::*;
+        let ChartStyle { primary, density, line_width, fill_opacity, .. } = self.style;
+
+        // Nothing to draw over an empty time frame, but the axes are still shown
+        let end = self.time.max(f64::EPSILON);
+        let times: Vec<f64> = (0..CHART_RESOLUTION)
+            .map(|t| t as f64 * end / (CHART_RESOLUTION - 1) as f64)
+            .collect();
+        let line = |sigmas: f64| times.iter().map(move |&t| (t, self.price_at(t, sigmas)));
+
+        // Always show the prediction and the whole 2σ cone
+        let (mut y_min, mut y_max) = line(-2.0).chain(line(2.0)).map(|p| p.1).filter(|y| y.is_finite())
+            .fold((self.spot, self.spot), |(low, high), y| (low.min(y), high.max(y)));
+        if let Some(prediction) = self.prediction.filter(|p| p.is_finite()) {
+            (y_min, y_max) = (y_min.min(prediction), y_max.max(prediction));
+        }
+        let margin = (y_max - y_min).max(f64::EPSILON) * 0.05;
+        let mut chart = chart
+            .x_label_area_size(20)
+            .y_label_area_size(40)
+            .margin(10)
+            .build_cartesian_2d(0.0..end, (y_min - margin).max(0.0)..y_max + margin)
+            .expect("failed to build chart");
+
+        let y_range = chart.y_range();
+        let tick_gap = (y_range.end - y_range.start) / 10.0;
+        self.style.draw_mesh(&mut chart, &|y: &f64| format_tick(*y, tick_gap, false));
+
+        // Each band is the region between its lower and upper lines
+        for (sigmas, opacity, name) in [(2.0, fill_opacity, "2σ move (95%)"), (1.0, fill_opacity * 2.0, "1σ move (68%)")] {
+            let outline: Vec<(f64, f64)> = line(-sigmas).chain(line(sigmas).collect::<Vec<_>>().into_iter().rev()).collect();
+            chart.draw_series(std::iter::once(Polygon::new(outline, primary.mix(opacity).filled())))
+                .expect("failed to draw chart data")
+                .label(legend_label(name))
+                .legend(move |(x, y)| Rectangle::new([(x, y), (x + 20, y + 10)], primary.mix(opacity).filled()));
+        }
+        chart.draw_series(LineSeries::new(line(0.0), ShapeStyle::from(primary).stroke_width(line_width)))
+            .expect("failed to draw chart data")
+            .label(legend_label("Median"))
+            // y+5 is to lower the legend-line to be inline with the label
+            .legend(move |(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], primary));
+
+        if let Some(prediction) = self.prediction {
+            let label = match self.prediction_sigmas() {
+                Some(sigmas) => legend_label(format!("Prediction ({:.2}, {:+.2}σ)", prediction, sigmas)),
+                None => legend_label(format!("Prediction ({:.2})", prediction)),
+            };
+            chart.draw_series(DashedLineSeries::new([(0.0, self.spot), (end, prediction)], 5, 5, density.into()))
+                .expect("failed to draw chart data")
+                .label(label)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], density));
+            chart.draw_series(PointSeries::of_element(
+                std::iter::once((end, prediction)),
+                5,
+                ShapeStyle::from(&density).filled(),
+                &|coord, size, style| EmptyElement::at(coord) + Circle::new((0, 0), size, style),
+            )).expect("failed to draw chart data");
+        }
+
+        self.style.draw_legend(&mut chart);
+    }
+}
@@ -1,15 +1,51 @@
 use std::ops::RangeInclusive;
+use chrono::NaiveDate;
 use iced::alignment::Vertical;
-use iced::Element;
-use iced::Length;
-use iced::widget::{TextInput, column, container, row, slider, text_input, text};
+use iced::{Element, Length, Point, Rectangle, Renderer, Task, Theme, mouse};
+use iced::widget::canvas::{self, Canvas, Frame, Geometry, Path, Stroke};
+use iced::widget::{TextInput, button, checkbox, column, container, mouse_area, row, slider, text_input, text};
+use serde::{Deserialize, Serialize};
 
-use super::{NumberInput, NumberInputMessage};
+use super::{DatePicker, DatePickerMessage, NumberInput, NumberInputMessage, range_slider};
+use crate::calendar::DayCount;
+
+/// Number of steps the slider range is split into when no step is set
+const DEFAULT_STEPS: f64 = 200.0;
+
+/// Most tick marks drawn. Only every so many steps are marked if there would be more.
+const MAX_TICKS: usize = 50;
+
+/// Height of the slider when sized for touch screens, making it easier to grab
+const TOUCH_HEIGHT: f32 = 32.0;
+
+/// Width of the handle of iced's default slider style. Ticks are offset by half of it at either end.
+const HANDLE_WIDTH: f32 = 14.0;
 
 #[derive(Debug, Clone)]
 pub enum CustomSliderMessage {
     Slide(f64),
+    /// The slider handle was let go after dragging
+    Release,
     NumberInputMessage(usize, NumberInputMessage),
+    /// Shows or hides the tick marks at each step
+    ShowTicks(bool),
+    /// Returns the slider to its original value
+    Reset,
+    /// The range slider below the slider was dragged to new bounds
+    RangeSlide(RangeInclusive<f64>),
+    /// Moves the slider by the given number of steps (or 1/200ths of its range if it has no step).
+    /// Negative numbers move it down.
+    Nudge(f64),
+    /// Message of the date picker, shown if the slider's value is a time in years. See CustomSlider::set_dates.
+    Picker(DatePickerMessage),
+}
+
+/// Settings of a slider that are kept when it's saved, apart from its value and bounds
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SliderConfig {
+    /// See CustomSlider::get_step
+    pub step: Option<f64>,
+    pub show_ticks: bool,
 }
 
 pub struct CustomSlider {
@@ -19,9 +55,20 @@ pub struct CustomSlider {
     /// 
     /// 0: Set/display lower bound \
     /// 1: Set/display upper bound \
-    /// 2: Set/display number read out
-    number_inputs: [NumberInput; 3],
+    /// 2: Set/display number read out \
+    /// 3: Set/display step (empty for a smooth slider)
+    number_inputs: [NumberInput; 4],
     allowed_range: RangeInclusive<f64>,
+    /// Whether tick marks are drawn at each step
+    show_ticks: bool,
+    /// Value the slider was given by the calculation. Marked on the slider and returned to on reset.
+    original: Option<f64>,
+    /// Date the value counts years from and the day count converting dates to years.
+    /// Only set for sliders of times, which can then be picked as calendar dates.
+    dates: Option<(NaiveDate, DayCount)>,
+    picker: DatePicker,
+    /// Whether the slider is enlarged for touch screens
+    touch: bool,
 }
 impl Default for CustomSlider {
     fn default() -> Self {
@@ -30,6 +77,11 @@ impl Default for CustomSlider {
             value: Default::default(),
             number_inputs: Default::default(),
             allowed_range: f64::MIN..=f64::MAX,
+            show_ticks: false,
+            original: None,
+            dates: None,
+            picker: DatePicker::default(),
+            touch: false,
         }
     }
 }
@@ -38,9 +90,30 @@ impl CustomSlider {
         match message {
             CustomSliderMessage::Slide(new_val) => {
                 self.value = new_val;
+                // Snap to whole multiples of the step, rather than steps from the lower bound.
+                // A ctrl-click reset to the original value is left as is.
+                if let Some(step) = self.get_step() && Some(new_val) != self.original {
+                    let range = self.get_slider_range();
+                    self.value = ((new_val / step).round() * step).clamp(*range.start(), *range.end());
+                }
                 let value_str = self.value.to_string();
                 self.number_inputs[2].update(NumberInputMessage::Edit(value_str));
             }
+            CustomSliderMessage::Release => (),
+            // The -/+ buttons of the read out move the slider by its own step
+            CustomSliderMessage::NumberInputMessage(2, NumberInputMessage::Step(steps)) => {
+                self.update(CustomSliderMessage::Nudge(steps));
+            }
+            CustomSliderMessage::Nudge(steps) => {
+                let range = self.get_slider_range();
+                let step = self.get_step();
+                let mut value = self.value + steps * step.unwrap_or((range.end() - range.start()) / DEFAULT_STEPS);
+                // Fine nudges move between the snapped values
+                if let Some(step) = step && steps.fract() == 0.0 {
+                    value = (value / step).round() * step;
+                }
+                self.set_value(value.clamp(*range.start(), *range.end()));
+            }
             CustomSliderMessage::NumberInputMessage(i, msg) => {
                 self.number_inputs[i].update(msg);
                 self.update_input_ranges();
@@ -49,7 +122,102 @@ impl CustomSlider {
                     self.value = 0.0;
                 }
             }
+            CustomSliderMessage::ShowTicks(show) => {
+                self.show_ticks = show;
+            }
+            CustomSliderMessage::RangeSlide(range) => {
+                self.set_slider_range(range);
+                self.update_input_ranges();
+                let range = self.get_slider_range();
+                let clamped = self.value.clamp(*range.start(), *range.end());
+                if clamped != self.value {
+                    self.set_value(clamped);
+                }
+            }
+            CustomSliderMessage::Reset => {
+                if let Some(original) = self.original {
+                    self.set_value(original);
+                }
+            }
+            CustomSliderMessage::Picker(msg) => {
+                // The bounds are widened if the picked date falls outside them
+                if let DatePickerMessage::Pick(date) = msg && let Some((start, day_count)) = self.dates {
+                    let years = day_count.year_fraction(start, date).clamp(*self.allowed_range.start(), *self.allowed_range.end());
+                    let range = self.get_slider_range();
+                    if !range.contains(&years) {
+                        self.set_slider_range(range.start().min(years)..=range.end().max(years));
+                    }
+                    self.set_value(years);
+                }
+                self.picker.update(msg);
+            }
+        }
+    }
+
+    /// Step the slider snaps to. None if no (positive) step is set, in which case the slider moves smoothly.
+    pub fn get_step(&self) -> Option<f64> {
+        let step = self.number_inputs[3].get_value();
+        return (step > 0.0).then_some(step);
+    }
+
+    /// Sets the step the slider snaps to. None for a smooth slider.
+    pub fn set_step(&mut self, step: Option<f64>) -> &mut Self {
+        match step {
+            Some(step) => self.number_inputs[3].set_value(step),
+            None => self.number_inputs[3].update(NumberInputMessage::Edit(String::new())),
+        }
+        return self;
+    }
+
+    /// Settings to save the slider with
+    pub fn config(&self) -> SliderConfig {
+        return SliderConfig {
+            step: self.get_step(),
+            show_ticks: self.show_ticks,
+        };
+    }
+
+    /// Restores settings saved with config
+    pub fn apply_config(&mut self, config: &SliderConfig) -> &mut Self {
+        self.set_step(config.step);
+        self.show_ticks = config.show_ticks;
+        return self;
+    }
+
+    /// Values the range slider can set the bounds to: the current bounds widened by their span either side,
+    /// within the allowed range. Repeated drags can keep widening the bounds.
+    fn range_domain(&self) -> RangeInclusive<f64> {
+        let range = self.get_slider_range();
+        let span = match range.end() - range.start() {
+            span if span > 0.0 => span,
+            _ => range.end().abs().max(1.0),
+        };
+        return (range.start() - span).max(*self.allowed_range.start())..=(range.end() + span).min(*self.allowed_range.end());
+    }
+
+    /// Produces Nudge(steps) only if the read out of the slider has focus. Used to nudge the slider with the arrow keys.
+    pub fn nudge_if_focused(&self, steps: f64) -> Task<CustomSliderMessage> {
+        return self.number_inputs[2].step_if_focused(steps).map(move |_| CustomSliderMessage::Nudge(steps));
+    }
+
+    /// Fractions of the way along the slider of each tick mark, for multiples of the step within the range
+    fn tick_ratios(&self, range: RangeInclusive<f64>) -> Vec<f32> {
+        let (Some(step), (start, end)) = (self.get_step(), (*range.start(), *range.end())) else {
+            return Vec::new();
+        };
+        if end <= start {
+            return Vec::new();
         }
+        let first = (start / step).ceil() as i64;
+        let last = (end / step).floor() as i64;
+        let count = (last - first + 1).max(0) as usize;
+        let every = count.div_ceil(MAX_TICKS).max(1) as i64;
+        // Only every multiple of every is visited, so wide ranges of small steps stay cheap
+        let first = first.div_euclid(every) * every + if first.rem_euclid(every) == 0 { 0 } else { every };
+        return (first..=last)
+            .step_by(every as usize)
+            .map(|i| ((i as f64 * step - start) / (end - start)) as f32)
+            .collect();
     }
 
     fn update_input_ranges(&mut self) {
@@ -59,6 +227,7 @@ impl CustomSlider {
             lower = *self.allowed_range.start();
         }
         self.number_inputs[1].set_range(lower..=*self.allowed_range.end());
+        self.number_inputs[3].set_range(0.0..=f64::MAX);
     }
 
     pub fn get_value(&self) -> f64 {
@@ -70,18 +239,53 @@ impl CustomSlider {
         self
     }
 
+    /// Sets the date the value counts years from and the day count to convert with, for sliders of times.
+    /// The value can then be picked as a calendar date. None hides the date picker.
+    pub fn set_dates(&mut self, dates: Option<(NaiveDate, DayCount)>) -> &mut Self {
+        self.dates = dates;
+        return self;
+    }
+
+    /// Date the value falls on, if the slider is of a time. See set_dates.
+    fn value_date(&self) -> Option<NaiveDate> {
+        let (start, day_count) = self.dates?;
+        return day_count.date_after(start, self.value);
+    }
+
+    /// Sets the value the slider was given by the calculation, which it can be reset to
+    pub fn set_original(&mut self, value: f64) -> &mut Self {
+        self.original = Some(value);
+        return self;
+    }
+
     pub fn set_value(&mut self, value: f64) {
         self.value = value;
         self.number_inputs[2].set_value(value);
     }
 
     pub fn set_precision(mut self, precision: usize) -> Self {
-        self.number_inputs = self.number_inputs.map(|x| x.set_precision(precision));
+        self.change_precision(precision);
+        return self;
+    }
+
+    /// Same as set_precision, for a slider already in use. Takes effect from the next edit of its inputs.
+    pub fn change_precision(&mut self, precision: usize) -> &mut Self {
+        self.number_inputs = self.number_inputs.clone().map(|x| x.set_precision(precision));
         return self;
     }
 
+    /// Sets the range of values the slider can take. Takes effect immediately, pulling the value and
+    /// the slider bounds inside the new range if they fall outside it.
     pub fn set_allowed_range(&mut self, range: RangeInclusive<f64>) -> &mut Self {
         self.allowed_range = range;
+        self.update_input_ranges();
+        if self.number_inputs[0].value_outside_range() || self.number_inputs[1].value_outside_range() {
+            self.set_slider_range(self.get_slider_range());
+        }
+        let clamped = self.value.clamp(*self.allowed_range.start(), *self.allowed_range.end());
+        if clamped != self.value {
+            self.set_value(clamped);
+        }
         self
     }
 
@@ -101,6 +305,12 @@ impl CustomSlider {
         return start..=end;
     }
 
+    /// Sets whether the slider is enlarged to be easier to grab on touch screens
+    pub fn set_touch(&mut self, touch: bool) -> &mut Self {
+        self.touch = touch;
+        return self;
+    }
+
     pub fn set_slider_range(&mut self, range: RangeInclusive<f64>) {
         self.number_inputs[0].set_value(*range.start());
         self.number_inputs[1].set_value(*range.end());
@@ -108,7 +318,6 @@ impl CustomSlider {
 
     pub fn view(&self) -> Element<'_, CustomSliderMessage> {
 
-        use iced::Theme;
         // Formatting for the textinputs of the range boundaries for the sliders
         fn style_strategy(show_red: bool) -> impl Fn(&Theme, text_input::Status) -> text_input::Style {
             move |theme, status| {
@@ -124,8 +333,33 @@ impl CustomSlider {
         let lower = self.number_inputs[0].get_value();
         let upper = self.number_inputs[1].get_value().max(lower);
 
+        // Ctrl-clicking the slider also resets it
+        let mut slider = slider(lower..=upper, self.value, CustomSliderMessage::Slide)
+            .step(self.get_step().unwrap_or((upper - lower) / DEFAULT_STEPS))
+            .on_release(CustomSliderMessage::Release);
+        if let Some(original) = self.original {
+            slider = slider.default(original);
+        }
+        if self.touch {
+            slider = slider.height(TOUCH_HEIGHT);
+        }
+        let original_ratio = self.original
+            .filter(|original| upper > lower && (lower..=upper).contains(original))
+            .map(|original| ((original - lower) / (upper - lower)) as f32);
+        let marks = SliderMarks {
+            ticks: if self.show_ticks { self.tick_ratios(lower..=upper) } else { Vec::new() },
+            original: original_ratio,
+        };
+
         column![
-            text!("{}", self.title),
+            row![
+                mouse_area(text!("{}", self.title).width(Length::Fill))
+                    .on_double_click(CustomSliderMessage::Reset),
+                button(text("Reset").size(10))
+                    .padding([2, 5])
+                    .style(button::secondary)
+                    .on_press_maybe(self.original.filter(|original| *original != self.value).map(|_| CustomSliderMessage::Reset)),
+            ].align_y(Vertical::Center),
             row![
                 container(
                     self.number_inputs[0]
@@ -135,7 +369,14 @@ impl CustomSlider {
                         })
                         .map(|number_msg| CustomSliderMessage::NumberInputMessage(0, number_msg))).width(Length::FillPortion(1)
                 ),
-                container(slider(lower..=upper, self.value, CustomSliderMessage::Slide).step((upper - lower) / 200.0)).width(Length::FillPortion(6)),
+                container(column![
+                    slider,
+                    (self.show_ticks || marks.original.is_some()).then(|| Canvas::new(marks)
+                        .width(Length::Fill)
+                        .height(8)),
+                    // Alternative to typing the bounds either side
+                    range_slider(self.range_domain(), lower..=upper, CustomSliderMessage::RangeSlide),
+                ].spacing(2)).width(Length::FillPortion(6)),
                 container(
                     self.number_inputs[1]
                         .adjust_then_view(|o: TextInput<'_, NumberInputMessage>| {
@@ -145,7 +386,60 @@ impl CustomSlider {
                         .map(|number_msg| CustomSliderMessage::NumberInputMessage(1, number_msg))
                 ).width(Length::FillPortion(1)),
             ].align_y(Vertical::Center),
-            self.number_inputs[2].view().map(|number_msg| CustomSliderMessage::NumberInputMessage(2, number_msg)),
+            row![
+                container(self.number_inputs[2].view().map(|number_msg| CustomSliderMessage::NumberInputMessage(2, number_msg)))
+                    .width(Length::FillPortion(4)),
+                text("Step").size(10),
+                container(
+                    self.number_inputs[3]
+                        .adjust_then_view(|o: TextInput<'_, NumberInputMessage>| o.size(10))
+                        .map(|number_msg| CustomSliderMessage::NumberInputMessage(3, number_msg))
+                ).width(Length::FillPortion(1)),
+                checkbox(self.show_ticks)
+                    .label("Ticks")
+                    .text_size(10)
+                    .on_toggle(CustomSliderMessage::ShowTicks),
+            ].spacing(5)
+            .align_y(Vertical::Center),
+            self.dates.is_some().then(|| row![
+                text(self.value_date().map_or_else(String::new, |date| date.to_string())).size(10).width(Length::Fill),
+                self.picker.view_button(self.value_date()).map(CustomSliderMessage::Picker),
+            ].align_y(Vertical::Center)),
+            self.picker.view_calendar(self.value_date()).map(|calendar| calendar.map(CustomSliderMessage::Picker)),
         ].into()
     }
-}
\ No newline at end of file
+}
+
+/// Tick marks and the original value marker drawn below a slider
+struct SliderMarks {
+    /// Fractions of the way along the slider of each tick
+    ticks: Vec<f32>,
+    /// Fraction of the way along the slider of the original value
+    original: Option<f32>,
+}
+impl<Message> canvas::Program<Message> for SliderMarks {
+    type State = ();
+
+    fn draw(&self, _: &Self::State, renderer: &Renderer, theme: &Theme, bounds: Rectangle, _: mouse::Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        // Lines up with the centre of the handle at that fraction along
+        let x = |ratio: f32| HANDLE_WIDTH / 2.0 + (bounds.width - HANDLE_WIDTH) * ratio;
+        let tick = Stroke::default()
+            .with_width(1.0)
+            .with_color(theme.extended_palette().background.strong.color);
+        for &ratio in &self.ticks {
+            frame.stroke(&Path::line(Point::new(x(ratio), 0.0), Point::new(x(ratio), bounds.height * 0.6)), tick);
+        }
+        if let Some(ratio) = self.original {
+            // Small upwards triangle pointing at the original value
+            let marker = Path::new(|p| {
+                p.move_to(Point::new(x(ratio), 0.0));
+                p.line_to(Point::new(x(ratio) + 4.0, bounds.height));
+                p.line_to(Point::new(x(ratio) - 4.0, bounds.height));
+                p.close();
+            });
+            frame.fill(&marker, theme.palette().primary);
+        }
+        return vec![frame.into_geometry()];
+    }
+}
@@ -0,0 +1,76 @@
+use chrono::NaiveDate;
+use iced::Element;
+use iced::widget::{column, row, text_input};
+use iced::Theme;
+
+use super::{DatePicker, DatePickerMessage, NumberInput};
+
+/// Format dates are entered and displayed as
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, Clone)]
+pub enum DateInputMessage {
+    Edit(String),
+    Picker(DatePickerMessage),
+}
+
+/// A TextInput widget for entering calendar dates in YYYY-MM-DD form.
+/// Highlights itself in red while the text entered isn't a valid date.
+/// The date can also be picked from a calendar opened next to the TextInput.
+#[derive(Debug, Clone, Default)]
+pub struct DateInput {
+    /// The raw text in the textinput
+    value_str: String,
+    picker: DatePicker,
+}
+impl DateInput {
+    pub fn update(&mut self, message: DateInputMessage) {
+        match message {
+            DateInputMessage::Edit(new_val) => {
+                // Only digits and dashes can ever make up a valid date
+                if new_val.chars().all(|c| c.is_ascii_digit() || c == '-') {
+                    self.value_str = new_val;
+                }
+            }
+            DateInputMessage::Picker(msg) => {
+                if let DatePickerMessage::Pick(date) = msg {
+                    self.set_date(date);
+                }
+                self.picker.update(msg);
+            }
+        }
+    }
+
+    /// Retrieves the date entered. Returns None if the user input isn't a complete date.
+    pub fn get_date(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(&self.value_str, DATE_FORMAT).ok()
+    }
+
+    /// Retrieves the raw text entered into the TextInput
+    pub fn get_text(&self) -> &str {
+        return &self.value_str;
+    }
+
+    pub fn set_date(&mut self, date: NaiveDate) {
+        self.value_str = date.format(DATE_FORMAT).to_string();
+    }
+
+    /// Checks if the user input is non-empty but not a valid date
+    pub fn is_invalid(&self) -> bool {
+        !self.value_str.is_empty() && self.get_date().is_none()
+    }
+
+    pub fn view(&self) -> Element<'_, DateInputMessage, Theme> {
+        column![
+            row![
+                text_input("YYYY-MM-DD", &self.value_str)
+                    .on_input(DateInputMessage::Edit)
+                    .style(NumberInput::style_strategy(self.is_invalid())),
+                self.picker.view_button(self.get_date()).map(DateInputMessage::Picker),
+            ].spacing(5)
+            .align_y(iced::Center),
+            self.picker.view_calendar(self.get_date()).map(|calendar| calendar.map(DateInputMessage::Picker)),
+        ].spacing(5)
+        .into()
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::{Datelike, Months, NaiveDate};
+use iced::{Center, Element, Length};
+use iced::widget::{Column, Row, button, column, container, row, text};
+
+/// Text size of the day numbers and weekday names
+const DAY_TEXT_SIZE: u32 = 11;
+
+const WEEKDAY_NAMES: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+#[derive(Debug, Clone)]
+pub enum DatePickerMessage {
+    /// Opens the calendar on the month starting on the given date, or closes it if None
+    ShowMonth(Option<NaiveDate>),
+    /// A day of the calendar was clicked. Closes the calendar.
+    Pick(NaiveDate),
+}
+
+/// Custom widget for choosing a date from a month calendar opened with a button.
+/// The parent reads the chosen date off the Pick message.
+#[derive(Debug, Clone, Default)]
+pub struct DatePicker {
+    /// First day of the month shown. None while the calendar is closed.
+    month: Option<NaiveDate>,
+}
+impl DatePicker {
+    pub fn update(&mut self, message: DatePickerMessage) {
+        match message {
+            DatePickerMessage::ShowMonth(month) => {
+                self.month = month;
+            }
+            DatePickerMessage::Pick(_) => {
+                self.month = None;
+            }
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        return self.month.is_some();
+    }
+
+    /// Button opening the calendar on the month of the selected date (today's month if None), or closing it
+    pub fn view_button(&self, selected: Option<NaiveDate>) -> Element<'_, DatePickerMessage> {
+        let message = match self.month {
+            Some(_) => DatePickerMessage::ShowMonth(None),
+            None => DatePickerMessage::ShowMonth(selected.unwrap_or_else(crate::calendar::today).with_day(1)),
+        };
+        button(text(if self.is_open() { "Close" } else { "Calendar" }).size(10))
+            .padding([2, 5])
+            .style(button::secondary)
+            .on_press(message)
+            .into()
+    }
+
+    /// Calendar of the shown month with the selected date highlighted. None while closed.
+    pub fn view_calendar(&self, selected: Option<NaiveDate>) -> Option<Element<'_, DatePickerMessage>> {
+        let month = self.month?;
+        let show = |month: Option<NaiveDate>| month.map(|month| DatePickerMessage::ShowMonth(Some(month)));
+        let header = row![
+            button(text("<").size(DAY_TEXT_SIZE))
+                .style(button::text)
+                .on_press_maybe(show(month.checked_sub_months(Months::new(1)))),
+            text(month.format("%B %Y").to_string()).width(Length::Fill).align_x(Center),
+            button(text(">").size(DAY_TEXT_SIZE))
+                .style(button::text)
+                .on_press_maybe(show(month.checked_add_months(Months::new(1)))),
+        ].align_y(Center);
+
+        let weekdays = Row::from_iter(WEEKDAY_NAMES.iter()
+            .map(|name| text(*name).size(DAY_TEXT_SIZE).width(Length::Fill).align_x(Center).into()));
+
+        // Weeks start on Monday, with the days either side of the month left blank
+        let days: Vec<Option<NaiveDate>> = std::iter::repeat_n(None, month.weekday().num_days_from_monday() as usize)
+            .chain(month.iter_days().take_while(|day| day.month() == month.month()).map(Some))
+            .collect();
+        let weeks = Column::from_iter(days.chunks(7).map(|week| {
+            let mut cells = Row::from_iter(week.iter().map(|day| match day {
+                Some(day) => button(text(day.day()).size(DAY_TEXT_SIZE).width(Length::Fill).align_x(Center))
+                    .width(Length::Fill)
+                    .padding(2)
+                    .style(if Some(*day) == selected { button::primary } else { button::text })
+                    .on_press(DatePickerMessage::Pick(*day))
+                    .into(),
+                None => container("").width(Length::Fill).into(),
+            }));
+            // Pad out the last week so its days line up with the weekday names
+            for _ in week.len()..7 {
+                cells = cells.push(container("").width(Length::Fill));
+            }
+            cells.into()
+        }));
+
+        return Some(container(column![header, weekdays, weeks].spacing(2))
+            .padding(5)
+            .style(container::rounded_box)
+            .into());
+    }
+}
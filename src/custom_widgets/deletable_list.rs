@@ -1,7 +1,8 @@
+use std::ops::Range;
 use iced::Element;
 use iced::Length;
-use iced::widget::Column;
-use iced::widget::{container, text, hover, button};
+use iced::widget::{Column, Row};
+use iced::widget::{container, text, hover, button, row, stack};
 use iced::{Center, Right};
 use iced::Padding;
 
@@ -10,12 +11,22 @@ use iced::Padding;
 pub enum DeletableListMessage<T: Clone> {
     Delete(usize),
     Item(usize, T),
+    /// Puts the last deleted item back where it was
+    UndoDelete,
+    /// Deletes every item
+    Clear,
 }
 
 /// Custom widget for handling a list of items. Each item is deletable on the GUI with
 /// a "X" delete button that appears on top-right of the item upon mouse hover.
 /// 
 /// Each Item is also paired up with some given ID information for ease of tracking.
+/// 
+/// Items can optionally show extra controls next to the delete button via an overlay.
+///
+/// The last deleted item is held on to so the deletion can be undone.
+///
+/// On touch screens, where nothing can be hovered, the buttons can instead be always shown and enlarged.
 pub struct DeletableList<Id, Item, ItemMessage, Update, View> 
 where
     Id: Clone + PartialEq,
@@ -26,6 +37,11 @@ where
     pub data: Vec<(Id, Item)>,
     item_view: View,
     item_update: Update,
+    item_overlay: Option<for<'a> fn(&'a Item) -> Element<'a, ItemMessage>>,
+    /// Last deleted item with the index it was deleted from
+    deleted: Option<(usize, (Id, Item))>,
+    /// Whether the delete button is always shown at a size easy to tap
+    touch: bool,
 }
 
 impl<Id, Item, ItemMessage, Update, View> DeletableList<Id, Item, ItemMessage, Update, View> 
@@ -44,20 +60,55 @@ where
             data: Vec::new(),
             item_view: view,
             item_update: update,
+            item_overlay: None,
+            deleted: None,
+            touch: false,
         }
     }
 
+    /// Sets extra controls for each item that appear alongside the delete button upon mouse hover
+    pub fn set_overlay(&mut self, overlay: for<'a> fn(&'a Item) -> Element<'a, ItemMessage>) -> &mut Self {
+        self.item_overlay = Some(overlay);
+        return self;
+    }
+
+    /// Sets whether the delete button (and overlay) is always shown at a size easy to tap, instead of upon hover
+    pub fn set_touch(&mut self, touch: bool) -> &mut Self {
+        self.touch = touch;
+        return self;
+    }
+
     pub fn update(&mut self, message: DeletableListMessage<ItemMessage>) {
         match message {
             DeletableListMessage::Delete(i) => {
-                self.data.remove(i);
+                self.deleted = Some((i, self.data.remove(i)));
             }
             DeletableListMessage::Item(i, message) => {
                 (self.item_update)(&mut self.data[i].1, message);
             }
+            DeletableListMessage::UndoDelete => {
+                // Skipped if an item of the same ID has been added since
+                if let Some((i, (id, item))) = self.deleted.take() && self.scan_ID(&id).is_none() {
+                    self.data.insert(i.min(self.data.len()), (id, item));
+                }
+            }
+            DeletableListMessage::Clear => {
+                self.data.clear();
+                self.deleted = None;
+            }
         }
     }
 
+    /// ID of the last deleted item, if it can still be restored with UndoDelete
+    pub fn deleted(&self) -> Option<&Id> {
+        return self.deleted.as_ref().map(|(_, (id, _))| id);
+    }
+
+    /// Drops the last deleted item so it can no longer be restored
+    pub fn forget_deleted(&mut self) {
+        self.deleted = None;
+    }
+
     /// Checks if a given ID is already held within the list.
     /// 
     /// Returns Some(i) with i being the lowest index where the ID exists. Returns None
@@ -67,6 +118,22 @@ where
         return self.data.iter().position(|(item_id,_)| *id == *item_id);
     }
 
+    /// The ID and config of every item in order, to save the list with. The config of each item is produced
+    /// by the given function (e.g the settings of the item worth keeping).
+    pub fn to_configs<Config>(&self, config: impl Fn(&Item) -> Config) -> Vec<(Id, Config)> {
+        return self.data.iter().map(|(id, item)| (id.clone(), config(item))).collect();
+    }
+
+    /// Replaces every item with the given ones, such as those created from a saved list of configs.
+    /// Items with an ID already present are skipped.
+    pub fn replace_all(&mut self, items: impl IntoIterator<Item = (Id, Item)>) {
+        self.data.clear();
+        self.deleted = None;
+        for (id, item) in items {
+            self.unique_push(id, item);
+        }
+    }
+
     /// Appends the item to the bottom of the list if the given ID is not already present.
     pub fn unique_push(&mut self, id: Id, item: Item) {
         if self.scan_ID(&id).is_none() {
@@ -80,11 +147,55 @@ where
         (formatting)(
             Column::from_iter(self.data.iter()
                 .enumerate()
-                .map(|(i, (_, x))| hover(
-                        (self.item_view)(x).map(move |message| DeletableListMessage::Item(i, message)),
-                        container(button(text("X").size(10).align_x(Center)).width(15.0).height(15.0).padding(Padding::ZERO).on_press(DeletableListMessage::Delete(i))).width(Length::Fill).align_x(Right)
-                    )
-                )
+                .map(|(i, (_, x))| self.item_element(i, x))
         )).into()
     }
+
+    /// Lays the items out in a grid with the given number of columns, filled row by row.
+    /// Every row is given the same height.
+    ///
+    /// Only the rows within visible_rows are drawn. The rest are left empty at the same height so
+    /// the grid scrolls the same, without building the views of items that are off-screen.
+    pub fn view_grid(&self, columns: usize, row_height: f32, visible_rows: Range<usize>) -> Element<'_, DeletableListMessage<ItemMessage>> {
+        let items: Vec<(usize, &Item)> = self.data.iter().map(|(_, x)| x).enumerate().collect();
+        Column::from_iter(items.chunks(columns.max(1))
+            .enumerate()
+            .map(|(j, chunk)| {
+                if !visible_rows.contains(&j) {
+                    return container("").height(row_height).into();
+                }
+                let mut row = Row::from_iter(chunk.iter()
+                    .map(|&(i, x)| container(self.item_element(i, x)).width(Length::FillPortion(1)).into())
+                ).height(row_height);
+                // Pad out the last row so its items keep the same width
+                for _ in chunk.len()..columns {
+                    row = row.push(container("").width(Length::FillPortion(1)));
+                }
+                row.into()
+            })
+        ).into()
+    }
+
+    /// View of the single item at the given index, with its delete button and overlay
+    pub fn view_item(&self, i: usize) -> Element<'_, DeletableListMessage<ItemMessage>> {
+        return self.item_element(i, &self.data[i].1);
+    }
+
+    /// The item's view with the delete button (and overlay) shown on top upon mouse hover
+    fn item_element<'a>(&'a self, i: usize, x: &'a Item) -> Element<'a, DeletableListMessage<ItemMessage>> {
+        let (size, text_size) = if self.touch { (30.0, 16) } else { (15.0, 10) };
+        let item = (self.item_view)(x).map(move |message| DeletableListMessage::Item(i, message));
+        let controls = container(row![
+            self.item_overlay.map(|overlay| overlay(x).map(move |message| DeletableListMessage::Item(i, message))),
+            button(text("X").size(text_size).align_x(Center).align_y(Center))
+                .width(size)
+                .height(size)
+                .padding(Padding::ZERO)
+                .on_press(DeletableListMessage::Delete(i)),
+        ].spacing(5)).width(Length::Fill).align_x(Right);
+        if self.touch {
+            return stack![item, controls].into();
+        }
+        return hover(item, controls);
+    }
 }
@@ -0,0 +1,196 @@
+use std::ops::RangeInclusive;
+use iced::Element;
+use iced::widget::{column, text};
+use iced::widget::canvas::{Cache, Frame, Geometry};
+use iced::Size;
+use iced::Center;
+use plotters_iced2::{Renderer};
+use plotters_iced2::{Chart, ChartWidget, DrawingBackend, ChartBuilder};
+
+use crate::custom_widgets::ChartStyle;
+use crate::custom_widgets::chart_style::{CHART_FONT_NAME, CHART_TITLE_SIZE, legend_label};
+use crate::custom_widgets::payoff_chart::format_tick;
+
+/// Determines number of datapoints computed for the chart
+const CHART_RESOLUTION: i32 = 501;
+
+/// Chart widget to draw the classic payoff diagram of holding a single option to expiry.
+/// Shows the profit/loss against the stock price at expiry, net of the premium paid.
+pub struct ExpiryChart {
+    cache: Cache,
+    /// Range of stock prices at expiry covered by the x-axis
+    x_range: RangeInclusive<f64>,
+    /// True for a call option, false for a put
+    is_call: bool,
+    strike: f64,
+    /// Price paid for the option
+    premium: f64,
+    /// Factor the payoff and premium are multiplied by. (E.g the contract multiplier).
+    scale: f64,
+    /// Predicted stock price, marked with a vertical line
+    prediction: Option<f64>,
+    /// Chart title
+    title: String,
+    /// Colours and line styling the chart is drawn with
+    style: ChartStyle,
+}
+impl Default for ExpiryChart {
+    fn default() -> Self {
+        Self {
+            cache: Cache::new(),
+            x_range: 0.0f64..=10.0f64,
+            is_call: true,
+            strike: 5.0,
+            premium: 1.0,
+            scale: 1.0,
+            prediction: None,
+            title: String::from("Profit/Loss at Expiry"),
+            style: ChartStyle::default(),
+        }
+    }
+}
+impl ExpiryChart {
+    pub fn view<'a, Message: 'a>(&'a self) -> Element<'a, Message> {
+        column![
+            text!("{}", self.title).size(CHART_TITLE_SIZE),
+            ChartWidget::new(self),
+            text("Stock price at expiry").size(CHART_TITLE_SIZE - 10),
+        ].align_x(Center)
+        .into()
+    }
+
+    /// Sets the option drawn and the premium paid for it
+    pub fn set_option(&mut self, is_call: bool, strike: f64, premium: f64) -> &mut Self {
+        self.is_call = is_call;
+        self.strike = strike;
+        self.premium = premium;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the range of stock prices the chart will cover
+    pub fn set_xrange(&mut self, x_range: RangeInclusive<f64>) -> &mut Self {
+        self.x_range = x_range;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the factor the payoff and premium are multiplied by
+    pub fn set_scale(&mut self, scale: f64) -> &mut Self {
+        self.scale = scale;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the x-value of the predicted stock price line
+    pub fn set_prediction(&mut self, stock: f64) -> &mut Self {
+        self.prediction = Some(stock);
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the colours and line styling the chart is drawn with
+    pub fn set_style(&mut self, style: ChartStyle) -> &mut Self {
+        self.style = style;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Profit/loss of holding the option to expiry for the given stock price
+    fn profit(&self, stock: f64) -> f64 {
+        let intrinsic = if self.is_call {
+            (stock - self.strike).max(0.0)
+        } else {
+            (self.strike - stock).max(0.0)
+        };
+        return (intrinsic - self.premium) * self.scale;
+    }
+
+    /// Stock price at expiry where the option exactly pays back its premium
+    fn breakeven(&self) -> f64 {
+        if self.is_call {
+            return self.strike + self.premium;
+        }
+        return self.strike - self.premium;
+    }
+}
+impl<Message> Chart<Message> for ExpiryChart {
+    type State = ();
+
+    #[inline]
+    fn draw<R: Renderer, F: Fn(&mut Frame)>(
+        &self,
+        renderer: &R,
+        bounds: Size,
+        draw_fn: F,
+    ) -> Geometry {
+        renderer.draw_cache(&self.cache, bounds, draw_fn)
+    }
+
+    fn build_chart<DB: DrawingBackend>(&self, _: &Self::State, mut chart: ChartBuilder<DB>) {
+        use plotters::prelude::*;
+        let ChartStyle { text: text_color, profit, loss, density, line_width, fill_opacity, .. } = self.style;
+
+        let (start, end) = (*self.x_range.start(), *self.x_range.end());
+        // Include the strike so the kink of the payoff is drawn sharply
+        let mut xs: Vec<f64> = (0..CHART_RESOLUTION)
+            .map(|x| start + x as f64*((end-start)/((CHART_RESOLUTION-1) as f64)))
+            .collect();
+        if (start..end).contains(&self.strike) {
+            xs.push(self.strike);
+            xs.sort_by(f64::total_cmp);
+        }
+        let points: Vec<(f64, f64)> = xs.iter().map(|&x| (x, self.profit(x))).collect();
+
+        // Always show the greatest loss (the premium) and some profit
+        let y_max = points.iter().map(|p| p.1).fold(self.premium * self.scale, f64::max);
+        let y_min = -self.premium * self.scale * 1.2;
+        let mut chart = chart
+            .x_label_area_size(20)
+            .y_label_area_size(40)
+            .margin(10)
+            .build_cartesian_2d(start..end, y_min..y_max * 1.1)
+            .expect("failed to build chart");
+
+        let y_range = chart.y_range();
+        let tick_gap = (y_range.end - y_range.start) / 10.0;
+        self.style.draw_mesh(&mut chart, &|y: &f64| format_tick(*y, tick_gap, false));
+
+        // Shade the profit and loss regions either side of zero
+        chart.draw_series(AreaSeries::new(points.iter().map(|&(x, y)| (x, y.max(0.0))), 0.0, profit.mix(fill_opacity)))
+            .expect("failed to draw chart data");
+        chart.draw_series(AreaSeries::new(points.iter().map(|&(x, y)| (x, y.min(0.0))), 0.0, loss.mix(fill_opacity)))
+            .expect("failed to draw chart data");
+
+        chart.draw_series(LineSeries::new([(start, 0.0), (end, 0.0)], text_color.mix(0.6)))
+            .expect("failed to draw chart data");
+        chart.draw_series(LineSeries::new(points.iter().copied(), ShapeStyle::from(text_color).stroke_width(line_width)))
+            .expect("failed to draw chart data")
+            .label(legend_label(format!("Profit/Loss (premium {:.2})", self.premium * self.scale)))
+            // y+5 is to lower the legend-line to be inline with the label
+            .legend(move |(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], text_color));
+
+        if let Some(prediction) = self.prediction.filter(|p| (start..end).contains(p)) {
+            chart.draw_series(DashedLineSeries::new([(prediction, y_min), (prediction, y_max * 1.1)], 5, 5, density.into()))
+                .expect("failed to draw chart data")
+                .label(legend_label(format!("Prediction ({:.2})", prediction)))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], density));
+        }
+
+        let breakeven = self.breakeven();
+        if (start..end).contains(&breakeven) {
+            chart.draw_series(PointSeries::of_element(
+                std::iter::once((breakeven, 0.0)),
+                5,
+                ShapeStyle::from(&text_color).filled(),
+                &|coord, size, style| {
+                    EmptyElement::at(coord)
+                    + Circle::new((0, 0), size, style)
+                    + Text::new(format!("Breakeven {:.2}", coord.0), (8, 8), (CHART_FONT_NAME, 15).into_font().color(&text_color))
+                },
+            )).expect("failed to draw chart data");
+        }
+
+        self.style.draw_legend(&mut chart);
+    }
+}
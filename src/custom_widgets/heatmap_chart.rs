@@ -0,0 +1,209 @@
+use std::ops::RangeInclusive;
+use iced::Element;
+use iced::widget::{column, text};
+use iced::widget::canvas::{Cache, Frame, Geometry};
+use iced::Size;
+use iced::Center;
+use plotters_iced2::{Renderer};
+use plotters_iced2::{Chart, ChartWidget, DrawingBackend, ChartBuilder};
+use plotters::style::RGBColor;
+
+use crate::custom_widgets::ChartStyle;
+use crate::custom_widgets::chart_style::{CHART_FONT_NAME, CHART_TITLE_SIZE, legend_label};
+
+/// Determines number of cells along each axis of the heatmap
+const HEATMAP_RESOLUTION: usize = 60;
+
+/// Chart widget to draw a function of two variables as a grid of coloured cells.
+/// Cells are coloured from the benchmark colour (red by default) at 0, through the background colour at the benchmark,
+/// to the primary colour (blue by default) at the highest value.
+pub struct HeatmapChart {
+    cache: Cache,
+    /// x axis range of the grid
+    x_range: RangeInclusive<f64>,
+    /// y axis range of the grid
+    y_range: RangeInclusive<f64>,
+    /// Function value at the centre of each cell. Rows of constant y, ordered from the lowest y.
+    values: Vec<Vec<f64>>,
+    /// Value drawn in the background colour. For an ROI heatmap this would be 1.
+    benchmark: f64,
+    /// Point to highlight on the grid
+    marker: Option<(f64, f64)>,
+    /// Chart title
+    title: String,
+    /// x-axis title
+    title_x: String,
+    /// y-axis title
+    title_y: String,
+    /// Colours the chart is drawn with
+    style: ChartStyle,
+}
+impl Default for HeatmapChart {
+    fn default() -> Self {
+        Self {
+            cache: Cache::new(),
+            x_range: 0.0f64..=10.0f64,
+            y_range: 0.0f64..=10.0f64,
+            values: Vec::new(),
+            benchmark: 1.0,
+            marker: None,
+            title: String::from("Title"),
+            title_x: String::from("X-Axis Title"),
+            title_y: String::from("Y-Axis Title"),
+            style: ChartStyle::default(),
+        }
+    }
+}
+impl HeatmapChart {
+    pub fn new(chart_title: String, x_axis_title: String, y_axis_title: String) -> Self {
+        return Self {
+            title: chart_title,
+            title_x: x_axis_title,
+            title_y: y_axis_title,
+            ..Default::default()
+        }
+    }
+
+    pub fn view<'a, Message: 'a>(&'a self) -> Element<'a, Message> {
+        column![
+            text!("{}", self.title).size(CHART_TITLE_SIZE),
+            ChartWidget::new(self),
+            text!("{}", self.title_x).size(CHART_TITLE_SIZE - 10),
+        ].align_x(Center)
+        .into()
+    }
+
+    /// Sets the ranges of the x and y axes the grid covers. Takes effect on the next set_rows.
+    pub fn set_ranges(&mut self, x_range: RangeInclusive<f64>, y_range: RangeInclusive<f64>) -> &mut Self {
+        self.x_range = x_range;
+        self.y_range = y_range;
+        return self;
+    }
+
+    /// Evaluates the function over every cell of the grid, a row at a time so it can be evaluated in batches.
+    /// Given the x values of a row and its y value, the function gives the value of each cell in the row.
+    /// Rows are evaluated in parallel on desktop.
+    pub fn set_rows(&mut self, func: impl Fn(&[f64], f64) -> Vec<f64> + Sync) -> &mut Self {
+        let xs = linspace(&self.x_range);
+        let row = |y: f64| func(&xs, y);
+        let ys = linspace(&self.y_range);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use rayon::prelude::*;
+            self.values = ys.into_par_iter().map(row).collect();
+        }
+        // No threads on the web build
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.values = ys.into_iter().map(row).collect();
+        }
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the point highlighted on the grid
+    pub fn set_marker(&mut self, x: f64, y: f64) -> &mut Self {
+        self.marker = Some((x, y));
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the colours and line styling the chart is drawn with
+    pub fn set_style(&mut self, style: ChartStyle) -> &mut Self {
+        self.style = style;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Colour of a cell with the given value, out of a grid whose highest value is max
+    fn cell_color(&self, value: f64, max: f64) -> RGBColor {
+        return self.style.scale_color(value, self.benchmark, max);
+    }
+}
+
+/// Centres of the cells evenly splitting the range
+fn linspace(range: &RangeInclusive<f64>) -> Vec<f64> {
+    let start = *range.start();
+    let step = (range.end() - start) / HEATMAP_RESOLUTION as f64;
+    return (0..HEATMAP_RESOLUTION)
+        .map(|i| start + (i as f64 + 0.5) * step)
+        .collect();
+}
+
+impl<Message> Chart<Message> for HeatmapChart {
+    type State = ();
+
+    #[inline]
+    fn draw<R: Renderer, F: Fn(&mut Frame)>(
+        &self,
+        renderer: &R,
+        bounds: Size,
+        draw_fn: F,
+    ) -> Geometry {
+        renderer.draw_cache(&self.cache, bounds, draw_fn)
+    }
+
+    fn build_chart<DB: DrawingBackend>(&self, _: &Self::State, mut chart: ChartBuilder<DB>) {
+        use plotters::prelude::*;
+        let text_color = self.style.text;
+
+        let (x_start, x_end) = (*self.x_range.start(), *self.x_range.end());
+        let (y_start, y_end) = (*self.y_range.start(), *self.y_range.end());
+        let mut chart = chart
+            .x_label_area_size(20)
+            .y_label_area_size(40)
+            .margin(10)
+            .build_cartesian_2d(x_start..x_end, y_start..y_end)
+            .expect("failed to build chart");
+
+        chart
+            .configure_mesh()
+            .disable_mesh()
+            .label_style((CHART_FONT_NAME).into_font().color(&text_color))
+            .axis_style(ShapeStyle::from(text_color.mix(0.45)).stroke_width(1))
+            .y_desc(&self.title_y)
+            .axis_desc_style((CHART_FONT_NAME, 15).into_font().color(&text_color))
+            .draw()
+            .expect("failed to draw chart mesh");
+
+        let max = self.values.iter().flatten()
+            .copied()
+            .filter(|v| v.is_finite())
+            .fold(self.benchmark, f64::max);
+        let (x_step, y_step) = ((x_end - x_start) / HEATMAP_RESOLUTION as f64, (y_end - y_start) / HEATMAP_RESOLUTION as f64);
+        // Cells without a finite value are left empty
+        chart.draw_series(
+            self.values.iter().enumerate().flat_map(|(j, row)| {
+                row.iter().enumerate()
+                    .filter(|(_, value)| value.is_finite())
+                    .map(move |(i, &value)| {
+                        let (x, y) = (x_start + i as f64 * x_step, y_start + j as f64 * y_step);
+                        Rectangle::new([(x, y), (x + x_step, y + y_step)], self.cell_color(value, max).filled())
+                    })
+            })
+        ).expect("failed to draw chart data");
+
+        if let Some(marker) = self.marker {
+            chart.draw_series(PointSeries::of_element(
+                std::iter::once(marker),
+                5,
+                ShapeStyle::from(&text_color).filled(),
+                &|coord, size, style| {
+                    EmptyElement::at(coord)
+                    + Circle::new((0, 0), size, style)
+                    + Text::new(format!("({:.3}, {:.3})", coord.0, coord.1), (8, 8), (CHART_FONT_NAME, 15).into_font().color(&text_color))
+                },
+            )).expect("failed to draw chart data");
+        }
+
+        // Legend of the colour scale
+        for (value, color) in [(0.0, self.style.benchmark), (self.benchmark, self.style.background), (max, self.style.primary)] {
+            chart.draw_series(std::iter::empty::<Rectangle<(f64, f64)>>())
+                .expect("failed to draw chart data")
+                .label(legend_label(format!("{:.2}", value)))
+                .legend(move |(x, y)| Rectangle::new([(x, y), (x + 20, y + 10)], color.filled()));
+        }
+        self.style.draw_legend(&mut chart);
+    }
+}
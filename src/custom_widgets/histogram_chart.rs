@@ -0,0 +1,159 @@
+use iced::Element;
+use iced::widget::{column, text};
+use iced::widget::canvas::{Cache, Frame, Geometry};
+use iced::Size;
+use iced::Center;
+use plotters_iced2::{Renderer};
+use plotters_iced2::{Chart, ChartWidget, DrawingBackend, ChartBuilder};
+
+use crate::custom_widgets::ChartStyle;
+use crate::custom_widgets::chart_style::{CHART_TITLE_SIZE, legend_label};
+
+/// Determines number of bars the outcomes are binned into
+const HISTOGRAM_BINS: usize = 40;
+
+/// Chart widget to draw the distribution of an option's profit/loss at the end of simulated stock price paths.
+/// Bars are coloured by whether they make a profit or a loss, with the mean, median and 5th/95th percentiles marked.
+pub struct HistogramChart {
+    cache: Cache,
+    /// Profit/loss at the end of each path, lowest first
+    outcomes: Vec<f64>,
+    /// Mean of the outcomes
+    mean: f64,
+    /// 5th, 25th, 50th, 75th and 95th percentiles of the outcomes
+    percentiles: [f64; 5],
+    /// Factor the outcomes are multiplied by. (E.g the contract multiplier).
+    scale: f64,
+    /// Chart title
+    title: String,
+    /// Colours and line styling the chart is drawn with
+    style: ChartStyle,
+}
+impl Default for HistogramChart {
+    fn default() -> Self {
+        Self {
+            cache: Cache::new(),
+            outcomes: Vec::new(),
+            mean: 0.0,
+            percentiles: [0.0; 5],
+            scale: 1.0,
+            title: String::from("Profit/Loss Distribution"),
+            style: ChartStyle::default(),
+        }
+    }
+}
+impl HistogramChart {
+    pub fn view<'a, Message: 'a>(&'a self) -> Element<'a, Message> {
+        column![
+            text!("{}", self.title).size(CHART_TITLE_SIZE),
+            ChartWidget::new(self),
+            text("Profit/Loss at the prediction end").size(CHART_TITLE_SIZE - 10),
+        ].align_x(Center)
+        .into()
+    }
+
+    /// Sets the outcomes binned, sorted lowest first, with their mean and percentiles
+    pub fn set_outcomes(&mut self, outcomes: Vec<f64>, mean: f64, percentiles: [f64; 5]) -> &mut Self {
+        self.outcomes = outcomes;
+        self.mean = mean;
+        self.percentiles = percentiles;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the factor the outcomes are multiplied by
+    pub fn set_scale(&mut self, scale: f64) -> &mut Self {
+        self.scale = scale;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the colours and line styling the chart is drawn with
+    pub fn set_style(&mut self, style: ChartStyle) -> &mut Self {
+        self.style = style;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Lower edge of each bin with the percentage of outcomes in it, and the width of a bin
+    fn bins(&self) -> (Vec<(f64, f64)>, f64) {
+        let (low, high) = match (self.outcomes.first(), self.outcomes.last()) {
+            (Some(&low), Some(&high)) => (low * self.scale, high * self.scale),
+            _ => return (Vec::new(), 1.0),
+        };
+        // Outcomes all the same still get a bar of some width
+        let width = ((high - low) / HISTOGRAM_BINS as f64).max(f64::EPSILON.max(high.abs() * 1e-6));
+        let mut counts = [0usize; HISTOGRAM_BINS];
+        for outcome in self.outcomes.iter() {
+            let bin = ((outcome * self.scale - low) / width) as usize;
+            counts[bin.min(HISTOGRAM_BINS - 1)] += 1;
+        }
+        let total = self.outcomes.len() as f64;
+        let bins = counts.iter().enumerate()
+            .map(|(i, &count)| (low + i as f64 * width, 100.0 * count as f64 / total))
+            .collect();
+        return (bins, width);
+    }
+}
+impl<Message> Chart<Message> for HistogramChart {
+    type State = ();
+
+    #[inline]
+    fn draw<R: Renderer, F: Fn(&mut Frame)>(
+        &self,
+        renderer: &R,
+        bounds: Size,
+        draw_fn: F,
+    ) -> Geometry {
+        renderer.draw_cache(&self.cache, bounds, draw_fn)
+    }
+
+    fn build_chart<DB: DrawingBackend>(&self, _: &Self::State, mut chart: ChartBuilder<DB>) {
+        use plotters::prelude::*;
+        let ChartStyle { text: text_color, primary, benchmark, profit, loss, line_width, fill_opacity, .. } = self.style;
+
+        let (bins, width) = self.bins();
+        // Nothing to draw without outcomes, but the axes are still shown
+        let start = bins.first().map_or(0.0, |b| b.0);
+        let end = bins.last().map_or(1.0, |b| b.0 + width);
+        let y_max = bins.iter().map(|b| b.1).fold(1.0, f64::max) * 1.1;
+        let mut chart = chart
+            .x_label_area_size(20)
+            .y_label_area_size(40)
+            .margin(10)
+            .build_cartesian_2d(start..end, 0.0..y_max)
+            .expect("failed to build chart");
+
+        self.style.draw_mesh(&mut chart, &|y: &f64| format!("{:.0}%", y));
+
+        // Bars are coloured by which side of breakeven their middle is
+        let opacity = (fill_opacity * 4.0).min(1.0);
+        chart.draw_series(bins.iter().map(|&(x, share)| {
+            let color = if x + width / 2.0 < 0.0 { loss } else { profit };
+            Rectangle::new([(x, 0.0), (x + width, share)], color.mix(opacity).filled())
+        })).expect("failed to draw chart data")
+            .label(legend_label(format!("Share of {} paths", self.outcomes.len())))
+            .legend(move |(x, y)| Rectangle::new([(x, y), (x + 20, y + 10)], profit.mix(opacity).filled()));
+
+        let markers = [
+            (self.mean, benchmark, "Mean"),
+            (self.percentiles[2], primary, "Median"),
+            (self.percentiles[0], text_color, "5th percentile"),
+            (self.percentiles[4], text_color, "95th percentile"),
+        ];
+        for (value, color, name) in markers {
+            let value = value * self.scale;
+            if !value.is_finite() {
+                continue;
+            }
+            let style = ShapeStyle::from(color).stroke_width(line_width);
+            chart.draw_series(DashedLineSeries::new([(value, 0.0), (value, y_max)], 5, 5, style))
+                .expect("failed to draw chart data")
+                .label(legend_label(format!("{} ({:.2})", name, value)))
+                // y+5 is to lower the legend-line to be inline with the label
+                .legend(move |(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], color));
+        }
+
+        self.style.draw_legend(&mut chart);
+    }
+}
@@ -1,13 +1,51 @@
-/// Module for all custom Iced widgets to be used for the project
+//! Module for all custom Iced widgets to be used for the project
 
 pub mod number_input;
 pub use number_input::{NumberInput, NumberInputMessage};
 
+pub mod chart_style;
+pub use chart_style::ChartStyle;
+
 pub mod payoff_chart;
-pub use payoff_chart::{PayoffChart, PayoffChartMessage};
+pub use payoff_chart::{ChartConfig, ChartFunc, PayoffChart, PayoffChartMessage};
+
+pub mod heatmap_chart;
+pub use heatmap_chart::HeatmapChart;
+
+pub mod expiry_chart;
+pub use expiry_chart::ExpiryChart;
+
+pub mod stress_grid;
+pub use stress_grid::StressGrid;
+
+pub mod simulation_chart;
+pub use simulation_chart::SimulationChart;
+
+pub mod cone_chart;
+pub use cone_chart::ConeChart;
+
+pub mod histogram_chart;
+pub use histogram_chart::HistogramChart;
+
+pub mod range_slider;
+pub use range_slider::range_slider;
+
+pub mod splitter;
+pub use splitter::splitter;
 
 pub mod custom_slider;
-pub use custom_slider::{CustomSlider, CustomSliderMessage};
+pub use custom_slider::{CustomSlider, CustomSliderMessage, SliderConfig};
 
 pub mod deletable_list;
-pub use deletable_list::{DeletableList, DeletableListMessage};
\ No newline at end of file
+pub use deletable_list::{DeletableList, DeletableListMessage};
+pub mod date_picker;
+pub use date_picker::{DatePicker, DatePickerMessage};
+
+pub mod date_input;
+pub use date_input::{DateInput, DateInputMessage};
+
+pub mod modal;
+pub use modal::modal;
+
+pub mod search_pick;
+pub use search_pick::SearchPick;
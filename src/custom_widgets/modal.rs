@@ -0,0 +1,24 @@
+use iced::{Color, Element};
+use iced::widget::{center, container, mouse_area, opaque, stack};
+
+/// Shows content as a dialog on top of the base element. The base is dimmed and cannot be
+/// interacted with while the dialog is open. Clicking outside the dialog produces on_blur.
+pub fn modal<'a, Message: Clone + 'a>(
+    base: impl Into<Element<'a, Message>>,
+    content: impl Into<Element<'a, Message>>,
+    on_blur: Message,
+) -> Element<'a, Message> {
+    stack![
+        base.into(),
+        opaque(
+            mouse_area(center(opaque(content)).style(|_theme| {
+                container::Style {
+                    background: Some(Color { a: 0.6, ..Color::BLACK }.into()),
+                    ..container::Style::default()
+                }
+            }))
+            .on_press(on_blur)
+        )
+    ]
+    .into()
+}
@@ -1,30 +1,48 @@
 use std::ops::RangeInclusive;
-use iced::Color;
-use iced::Element;
+use iced::{Color, Element, Task};
 use iced::widget::text_input::Catalog;
-use iced::widget::{TextInput, text_input};
+use iced::widget::{Id, TextInput, button, operation, row, text, text_input};
 use iced::Theme;
 
 #[derive(Debug, Clone)]
 pub enum NumberInputMessage {
     Edit(String),
+    /// Text pasted into the input (the whole text of the input after pasting)
+    Paste(String),
+    /// Moves the value by the given number of steps. Negative numbers step down.
+    Step(f64),
 }
 
-/// A TextInput widget than ensures only valid numbers/floats can be entered into it
+/// Characters removed from pasted text
+const CURRENCY_SYMBOLS: &str = "$€£¥₹";
+
+/// A TextInput widget than ensures only valid numbers/floats can be entered into it.
+/// The number may end with one of the unit suffixes configured for the input (e.g "30d" or "25%"),
+/// and may have thousands separators (e.g "1,234.5").
 #[derive(Debug, Clone)]
 pub struct NumberInput {
     /// The number/float in the textinput
     value_str: String,
     /// Determines the amount of numbers after the decimal point that can be entered
     dp_precision: Option<usize>,
-    allowed_range: RangeInclusive<f64>
+    allowed_range: RangeInclusive<f64>,
+    /// Amount the +/- buttons and arrow keys move the value by
+    step: f64,
+    /// Identifies the TextInput to check whether it has focus
+    id: Id,
+    /// Suffixes the number can end with, each with the factor converting a number in that unit into the
+    /// unit of this input. E.g ("%", 0.01) for an input of decimals.
+    suffixes: Vec<(&'static str, f64)>,
 }
 impl Default for NumberInput {
     fn default() -> Self {
         Self {
             value_str: Default::default(),
             dp_precision: None,
-            allowed_range: f64::MIN..=f64::MAX
+            allowed_range: f64::MIN..=f64::MAX,
+            step: 1.0,
+            id: Id::unique(),
+            suffixes: Vec::new(),
         }
     }
 }
@@ -32,13 +50,70 @@ impl NumberInput {
     pub fn update(&mut self, message: NumberInputMessage) {
         match message {
             NumberInputMessage::Edit(new_val) => {
-                // Make sure the textbox can be empty or have a lone negative sign
-                if new_val.is_empty() || new_val == "-" || new_val.parse::<f64>().is_ok() {
+                // Make sure the textbox can be empty or have a lone negative sign, before any suffix
+                let (number, _) = self.split_suffix(&new_val);
+                if number.is_empty() || number == "-" || parse_number(number).is_some() {
                     self.value_str = new_val;
                     self.apply_precision();
                 }
             }
+            NumberInputMessage::Paste(pasted) => {
+                // Copied prices often come with a currency symbol or surrounding whitespace (e.g "$1,234.50 ")
+                let sanitized: String = pasted.chars()
+                    .filter(|c| !c.is_whitespace() && !CURRENCY_SYMBOLS.contains(*c))
+                    .collect();
+                self.update(NumberInputMessage::Edit(sanitized));
+            }
+            NumberInputMessage::Step(steps) => {
+                // An incomplete input steps from the bottom of the allowed range (or 0 if it has no bottom)
+                let value = self.parse().unwrap_or(self.allowed_range.start().max(0.0));
+                // Rounding removes floating point noise like 0.30000000000000004
+                let decimals = self.dp_precision.unwrap_or(10).min(15) as i32;
+                let new_value = ((value + steps * self.step) * 10f64.powi(decimals)).round() / 10f64.powi(decimals);
+                self.set_value(new_value.clamp(*self.allowed_range.start(), *self.allowed_range.end()));
+            }
+        }
+    }
+
+    /// Sets the amount the +/- buttons and arrow keys move the value by. 1 by default.
+    pub fn set_step(&mut self, step: f64) -> &mut Self {
+        self.step = step;
+        return self;
+    }
+
+    /// Sets the unit suffixes the number can end with, each with the factor converting a number in that unit
+    /// into the unit of this input
+    pub fn set_suffixes(&mut self, suffixes: Vec<(&'static str, f64)>) -> &mut Self {
+        self.suffixes = suffixes;
+        return self;
+    }
+
+    /// Splits text into the number and the factor of the unit suffix it ends with (1 without a suffix).
+    /// Suffixes are matched ignoring case, and may be separated from the number by spaces.
+    fn split_suffix<'a>(&self, text: &'a str) -> (&'a str, f64) {
+        for &(suffix, factor) in &self.suffixes {
+            if let Some(number) = text.len().checked_sub(suffix.len())
+                .and_then(|i| text.get(i..).filter(|end| end.eq_ignore_ascii_case(suffix)).map(|_| &text[..i])) {
+                return (number.trim_end(), factor);
+            }
         }
+        return (text, 1.0);
+    }
+
+    /// Number entered converted out of any unit suffix. None if user input isn't a complete number.
+    fn parse(&self) -> Option<f64> {
+        let (number, factor) = self.split_suffix(&self.value_str);
+        return parse_number(number).map(|value| value * factor);
+    }
+
+    /// Produces Step(steps) only if the TextInput has focus. Used to step the value with the arrow keys.
+    pub fn step_if_focused(&self, steps: f64) -> Task<NumberInputMessage> {
+        return operation::is_focused(self.id.clone()).then(move |focused| {
+            if focused {
+                return Task::done(NumberInputMessage::Step(steps));
+            }
+            return Task::none();
+        });
     }
 
     /// Sets the max dp precision of this NumberInput
@@ -55,33 +130,41 @@ impl NumberInput {
     fn apply_precision(&mut self) {
         if let Some(precision) = self.dp_precision {
             let over = self.get_precision().saturating_sub(precision);
-            self.value_str.truncate(self.value_str.len() - over);
+            // Any suffix is kept after the truncated number
+            let number_end = self.split_suffix(&self.value_str).0.len();
+            self.value_str.replace_range(number_end - over..number_end, "");
         }
     }
 
     /// Checks if user inputted value is outside the allowed range. An incomplete user input is considered inside.
     pub fn value_outside_range(&self) -> bool {
-        !self.allowed_range.contains(&self.value_str.parse::<f64>().unwrap_or(*self.allowed_range.start()))
+        !self.allowed_range.contains(&self.parse().unwrap_or(*self.allowed_range.start()))
     }
 
-    /// Retrieves the value entered into the TextInput. Returns NAN if user input isn't a complete number.
+    /// Retrieves the value entered into the TextInput, converted out of any unit suffix. Returns NAN if user input isn't a complete number.
     /// 
     /// Any non NAN values are clamped according to allowed range specified. (By default its f64::MIN..=f64::MAX)
     pub fn get_value(&self) -> f64 {
-        self.value_str.parse::<f64>().unwrap_or(f64::NAN)
+        self.parse().unwrap_or(f64::NAN)
             .clamp(*self.allowed_range.start(), *self.allowed_range.end())
     }
 
+    /// Retrieves the raw text entered into the TextInput
+    pub fn get_text(&self) -> &str {
+        return &self.value_str;
+    }
+
+    /// Sets the value of the input, shown with thousands separators
     pub fn set_value(&mut self, value: f64) {
-        self.value_str = value.to_string();
+        self.value_str = group_thousands(value.to_string());
         self.apply_precision();
     }
 
     /// Calculates the "implied" precision of the number.
     /// 
-    /// E.g "1." is 0 precision, "1.0" is 1 precision, "1.50" is 2 precision, etc
+    /// E.g "1." is 0 precision, "1.0" is 1 precision, "1.50" is 2 precision, "1.5y" is 1 precision, etc
     pub fn get_precision(&self) -> usize {
-        return self.value_str.split_once(".")
+        return self.split_suffix(&self.value_str).0.split_once(".")
             .unwrap_or(("", ""))
             .1.len();
     }
@@ -96,16 +179,62 @@ impl NumberInput {
         }
     }
 
+    /// The TextInput with -/+ buttons beside it to step the value
     pub fn view(&self) -> Element<'_, NumberInputMessage, Theme> {
-        text_input("", &self.value_str)
-            .on_input(NumberInputMessage::Edit)
-            .style(Self::style_strategy(self.value_outside_range()))
-            .into()
+        let step_button = |label, steps| button(text(label).size(12))
+            .padding([5, 8])
+            .style(button::secondary)
+            .on_press(NumberInputMessage::Step(steps));
+        row![
+            text_input("", &self.value_str)
+                .id(self.id.clone())
+                .on_input(NumberInputMessage::Edit)
+                .on_paste(NumberInputMessage::Paste)
+                .style(Self::style_strategy(self.value_outside_range())),
+            step_button("-", -1.0),
+            step_button("+", 1.0),
+        ].spacing(2)
+        .align_y(iced::alignment::Vertical::Center)
+        .into()
     }
 
     pub fn adjust_then_view<'a>(&self, adjust: impl Fn(TextInput<'a, NumberInputMessage>) -> TextInput<'a, NumberInputMessage>) -> Element<'a, NumberInputMessage> {
         adjust(text_input("", &self.value_str)
+            .id(self.id.clone())
             .on_input(NumberInputMessage::Edit)
+            .on_paste(NumberInputMessage::Paste)
         ).into()
     }
-}
\ No newline at end of file
+}
+
+/// Parses a number that may have thousands separators (commas) before the decimal point.
+/// Separators aren't checked to be 3 digits apart, so partly typed numbers like "1,23" are accepted.
+fn parse_number(number: &str) -> Option<f64> {
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if fraction.contains(',') || whole.trim_start_matches('-').starts_with(',') || whole.contains(",,") {
+        return None;
+    }
+    return number.replace(',', "").parse::<f64>().ok();
+}
+
+/// Inserts thousands separators into the whole part of a number. E.g "-1234567.5" to "-1,234,567.5".
+fn group_thousands(number: String) -> String {
+    let (sign, unsigned) = number.split_at(number.starts_with('-') as usize);
+    let (whole, fraction) = unsigned.split_once('.').map_or((unsigned, None), |(w, f)| (w, Some(f)));
+    // Leave anything that isn't plain digits (e.g "inf" or "NaN") alone
+    if whole.len() <= 3 || !whole.chars().all(|c| c.is_ascii_digit()) {
+        return number;
+    }
+    let mut grouped = String::from(sign);
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    return grouped;
+}
@@ -1,30 +1,88 @@
+use std::cell::{Cell, Ref, RefCell};
 use std::iter;
 use std::ops::RangeInclusive;
+use std::sync::Arc;
 use iced::Element;
-use iced::widget::{column, text};
+use iced::widget::{button, column, row, text};
 use iced::widget::canvas::{Cache, Frame, Geometry};
-use iced::Size;
+use iced::{Rectangle, Size};
+use iced::event::Status;
+use iced::mouse::Cursor;
 use plotters_iced2::{Renderer};
 use plotters_iced2::{Chart, ChartWidget, DrawingBackend, ChartBuilder};
+use plotters::chart::ChartContext;
+use plotters::coord::cartesian::Cartesian2d;
+use plotters::coord::ranged1d::{Ranged, ValueFormatter};
+use plotters::coord::types::RangedCoordf64;
 use iced::Center;
+use iced::Padding;
+
+use serde::{Deserialize, Serialize};
+
+use crate::export::FileKind;
+use crate::settings::ChartResolution;
+use crate::custom_widgets::ChartStyle;
+use crate::custom_widgets::chart_style::{CHART_FONT_NAME, CHART_TITLE_SIZE, legend_label};
 
 #[derive(Debug, Clone, Copy)]
-pub enum PayoffChartMessage{}
+pub enum PayoffChartMessage {
+    /// Request for the chart to be exported as an image or its data as CSV
+    Export(FileKind),
+    /// The point hovered by the cursor changed so the chart needs redrawing
+    HoverMoved,
+    /// Switches the y-axis between a linear and log scale
+    ToggleLogScale,
+    /// The plotting area was clicked at the given x value
+    Clicked(f64),
+    /// Steps the chart's own resolution to the next choice, or back to the default
+    CycleResolution,
+    /// Switches between showing the chart on its own across the whole panel and alongside the others
+    ToggleMaximize,
+    /// Freezes the y-axis at its current scale, or lets it follow the plotted values again if frozen
+    ToggleYLock,
+    /// Keeps the current payoff line drawn as a dashed ghost for comparison, or clears the ghost if there is one
+    ToggleFreeze,
+}
+
+/// Single variable function drawn on a chart. Shared and thread safe so its points can be computed in parallel
+/// and in the background.
+pub type ChartFunc = Arc<dyn Fn(f64) -> f64 + Send + Sync>;
+
+/// Sampled (x, payoff function) points and the generation they were sampled for
+type GenerationSamples = (u64, Vec<(f64, f64)>);
+
+/// Number of datapoints computed for charts while in draft mode
+const DRAFT_RESOLUTION: usize = 101;
 
-/// Determines number of datapoints computed for all charts
-const CHART_RESOLUTION: i32 = 501;
+/// Most times the gap between two datapoints is halved to follow a sharp bend in the payoff function
+const MAX_REFINEMENT_DEPTH: u32 = 6;
 
-/// Determines chart title text size
-const CHART_TITLE_SIZE: u32 = 25;
+/// Distance (as a fraction of the plotted height) the payoff function can stray from a straight line
+/// between two datapoints before more datapoints are added between them
+const REFINEMENT_TOLERANCE: f64 = 1e-3;
 
-const CHART_FONT_NAME: &str = crate::FIRA_SANS_NAME;
+/// Lowest value shown on a log scale y-axis, as a fraction of the highest
+const LOG_SCALE_RANGE: f64 = 1e-4;
+
+/// Pixel size of charts exported as images
+const EXPORT_SIZE: (u32, u32) = (1200, 800);
+
+/// Settings of a chart that are kept when it's saved, apart from what it plots
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChartConfig {
+    pub log_scale: bool,
+    /// Resolution chosen for the chart specifically
+    pub resolution: Option<ChartResolution>,
+    /// See PayoffChart::y_lock
+    pub y_lock: Option<(f64, f64, f64)>,
+}
 
 /// Chart widget to handle drawing a single payoff chart.
 /// Support drawing an ROI graph or a nominal return graph.
 pub struct PayoffChart {
     cache: Cache,
     /// Main payoff function to plot
-    func: Box<dyn Fn(f64) -> f64>,
+    func: ChartFunc,
     /// The height of the "benchmark" line. For an ROI graph this would be 1.
     benchmark: f64,
     /// x axis range of the graph
@@ -33,29 +91,117 @@ pub struct PayoffChart {
     y_range: RangeInclusive<f64>,
     /// x value of where to place vertical crosshair line
     x_vert: Option<f64>,
+    /// Labelled x values marked with dashed vertical lines. (E.g the strike and breakevens).
+    markers: Vec<(String, f64)>,
+    /// Probability density of the x value, drawn against a secondary y-axis
+    density: Option<ChartFunc>,
+    /// Points of the payoff line when it was frozen, drawn dashed for comparison
+    ghost: Option<Vec<(f64, f64)>>,
+    /// y-axis bounds kept fixed instead of following the plotted values. See y_bounds.
+    y_lock: Option<(f64, f64, f64)>,
     /// Chart title
     title: String,
     /// x-axis title
     title_x: String,
     /// Lines series labels
-    labels: [String; 2]
+    labels: [String; 2],
+    /// True if the y-axis is drawn with a log scale
+    log_scale: bool,
+    /// True if y-axis values are ratios labelled as percentages. (E.g an ROI of 1.5 as 150%).
+    percent_axis: bool,
+    /// True if the chart is shown on its own across the whole panel
+    maximized: bool,
+    /// Number of datapoints computed unless the chart has a resolution of its own
+    default_resolution: usize,
+    /// Resolution chosen for this chart specifically
+    resolution: Option<ChartResolution>,
+    /// True if the chart is drawn at a reduced resolution to keep up with rapid changes
+    draft: bool,
+    /// Colours and line styling the chart is drawn with
+    style: ChartStyle,
+    /// Decimal places of the x and y values labelled on the chart
+    decimals: (usize, usize),
+    /// Pixel (x, y) ranges of the plotting area when last drawn on screen. Used to find the hovered x value.
+    plot_area: Cell<Option<(std::ops::Range<i32>, std::ops::Range<i32>)>>,
+    /// Sampled (x, payoff function) points and the generation they were sampled for, kept between redraws
+    samples: RefCell<Option<GenerationSamples>>,
+    /// Counts changes to the function, x-axis range and resolution. Samples of an older generation are out of date.
+    generation: u64,
+    /// Generation being sampled in the background. The out of date samples are drawn until it's done.
+    sampling: Option<u64>,
 }
 impl Default for PayoffChart {
     fn default() -> Self {
         Self {
             cache: Cache::new(),
-            func: Box::new(|x| x),
+            func: Arc::new(|x| x),
             benchmark: 1.0,
             x_range: 0.0f64..=10.0f64,
             y_range: 0.0f64..=10.0f64,
             x_vert: None,
+            markers: Vec::new(),
+            density: None,
+            ghost: None,
+            y_lock: None,
             title: String::from("Title"),
             title_x: String::from("X-Axis Title"),
-            labels: [String::from("Line 1"), String::from("Line 2")]
+            labels: [String::from("Line 1"), String::from("Line 2")],
+            log_scale: false,
+            percent_axis: false,
+            maximized: false,
+            default_resolution: 501,
+            resolution: None,
+            draft: false,
+            style: ChartStyle::default(),
+            decimals: (3, 2),
+            plot_area: Cell::new(None),
+            samples: RefCell::new(None),
+            generation: 0,
+            sampling: None,
         }
     }
 }
 impl PayoffChart {
+    pub fn update(&mut self, message: PayoffChartMessage) {
+        match message {
+            PayoffChartMessage::HoverMoved => self.cache.clear(),
+            PayoffChartMessage::ToggleLogScale => {
+                self.log_scale = !self.log_scale;
+                self.cache.clear();
+            }
+            PayoffChartMessage::CycleResolution => {
+                let choices = ChartResolution::everything();
+                self.resolution = match self.resolution.and_then(|r| choices.iter().position(|c| *c == r)) {
+                    None => Some(choices[0]),
+                    Some(i) => choices.get(i + 1).copied(),
+                };
+                self.outdate_samples();
+                self.cache.clear();
+            }
+            PayoffChartMessage::ToggleYLock => {
+                self.y_lock = match self.y_lock {
+                    Some(_) => None,
+                    None => Some(self.y_bounds(&self.plotted_values())),
+                };
+                self.cache.clear();
+            }
+            PayoffChartMessage::ToggleFreeze => {
+                self.ghost = match self.ghost {
+                    Some(_) => None,
+                    None => Some(self.samples().clone()),
+                };
+                self.cache.clear();
+            }
+            PayoffChartMessage::ToggleMaximize => {
+                self.set_maximized(!self.maximized);
+            }
+            // Handled by the application as saving is asynchronous
+            PayoffChartMessage::Export(_) => (),
+            // Handled by the application as it changes the scenario
+            PayoffChartMessage::Clicked(_) => (),
+        }
+    }
+
     pub fn view(&self) -> Element<'_, PayoffChartMessage> {
         column![
             text!("{}", self.title).size(CHART_TITLE_SIZE),
@@ -65,6 +211,37 @@ impl PayoffChart {
         .into()
     }
 
+    /// Buttons shown on top of the chart upon mouse hover
+    pub fn overlay(&self) -> Element<'_, PayoffChartMessage> {
+        fn small_button<'a>(label: impl text::IntoFragment<'a>, message: PayoffChartMessage) -> Element<'a, PayoffChartMessage> {
+            button(text(label).size(10).align_x(Center))
+                .height(15.0)
+                .padding(Padding::ZERO.left(3).right(3))
+                .on_press(message)
+                .into()
+        }
+        // Rasterising text relies on system fonts which aren't available on the web build
+        #[cfg(not(target_arch = "wasm32"))]
+        let png: Option<Element<'_, PayoffChartMessage>> = Some(small_button("PNG", PayoffChartMessage::Export(FileKind::Png)));
+        #[cfg(target_arch = "wasm32")]
+        let png: Option<Element<'_, PayoffChartMessage>> = None;
+
+        row![
+            small_button(if self.maximized { "Restore" } else { "Maximize" }, PayoffChartMessage::ToggleMaximize),
+            small_button(if self.ghost.is_some() { "Unfreeze" } else { "Freeze" }, PayoffChartMessage::ToggleFreeze),
+            small_button(if self.y_lock.is_some() { "Unlock y" } else { "Lock y" }, PayoffChartMessage::ToggleYLock),
+            small_button(if self.log_scale { "Linear" } else { "Log" }, PayoffChartMessage::ToggleLogScale),
+            small_button(match self.resolution {
+                Some(resolution) => format!("{} pts", resolution.0),
+                None => String::from("Default pts"),
+            }, PayoffChartMessage::CycleResolution),
+            png,
+            small_button("SVG", PayoffChartMessage::Export(FileKind::Svg)),
+            small_button("CSV", PayoffChartMessage::Export(FileKind::Csv)),
+        ].spacing(5)
+        .into()
+    }
+
     /// Create chart for showing ROI (return on investment)
     pub fn new_roi_chart(chart_title: String, x_axis_title: String) -> Self {
         return Self {
@@ -72,6 +249,7 @@ impl PayoffChart {
             title_x: x_axis_title,
             benchmark: 1.0,
             labels: [String::from("Exit ROI"), String::from("Entry ROI")],
+            percent_axis: true,
             ..Default::default()
         }
     }
@@ -95,7 +273,11 @@ impl PayoffChart {
 
     /// Sets the range of x-axis values the chart will cover
     pub fn set_xrange(&mut self, x_range: RangeInclusive<f64>) -> &mut Self {
+        if self.x_range == x_range {
+            return self;
+        }
         self.x_range = x_range;
+        self.outdate_samples();
         self.cache.clear();
         return self;
     }
@@ -108,8 +290,9 @@ impl PayoffChart {
     }
 
     /// Sets the payoff function the chart will draw
-    pub fn set_func(&mut self, func: Box<dyn Fn(f64) -> f64>) -> &mut Self {
+    pub fn set_func(&mut self, func: ChartFunc) -> &mut Self {
         self.func = func;
+        self.outdate_samples();
         self.cache.clear();
         return self;
     }
@@ -120,88 +303,346 @@ impl PayoffChart {
         self.cache.clear();
         return self;
     }
-}
-impl Chart<PayoffChartMessage> for PayoffChart {
-    type State = ();
 
-    #[inline]
-    fn draw<R: Renderer, F: Fn(&mut Frame)>(
-        &self,
-        renderer: &R,
-        bounds: Size,
-        draw_fn: F,
-    ) -> Geometry {
-        renderer.draw_cache(&self.cache, bounds, draw_fn)
+    /// Sets the labelled x values marked on the chart
+    pub fn set_markers(&mut self, markers: Vec<(String, f64)>) -> &mut Self {
+        self.markers = markers;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the probability density drawn against the secondary y-axis, or None to hide it
+    pub fn set_density(&mut self, density: Option<ChartFunc>) -> &mut Self {
+        self.density = density;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the colours and line styling the chart is drawn with
+    pub fn set_style(&mut self, style: ChartStyle) -> &mut Self {
+        self.style = style;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the decimal places of the x and y values labelled on the chart
+    pub fn set_decimals(&mut self, x: usize, y: usize) -> &mut Self {
+        self.decimals = (x, y);
+        self.cache.clear();
+        return self;
     }
 
-    fn build_chart<DB: DrawingBackend>(&self, _: &Self::State, mut chart: ChartBuilder<DB>) {
+    /// Sets whether the chart is shown on its own across the whole panel
+    pub fn set_maximized(&mut self, maximized: bool) -> &mut Self {
+        self.maximized = maximized;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the number of datapoints computed when the chart has no resolution of its own
+    pub fn set_default_resolution(&mut self, resolution: usize) -> &mut Self {
+        self.default_resolution = resolution;
+        self.outdate_samples();
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets whether the chart is drawn at a reduced resolution, such as while a slider is dragged
+    pub fn set_draft(&mut self, draft: bool) -> &mut Self {
+        if self.draft == draft {
+            return self;
+        }
+        self.draft = draft;
+        self.outdate_samples();
+        self.cache.clear();
+        return self;
+    }
+
+    /// Number of datapoints currently computed for the chart
+    fn resolution(&self) -> usize {
+        let resolution = self.resolution.map_or(self.default_resolution, |r| r.0);
+        if self.draft {
+            return resolution.min(DRAFT_RESOLUTION);
+        }
+        return resolution;
+    }
+
+    /// Settings to save the chart with
+    pub fn config(&self) -> ChartConfig {
+        return ChartConfig {
+            log_scale: self.log_scale,
+            resolution: self.resolution,
+            y_lock: self.y_lock,
+        };
+    }
+
+    /// Restores settings saved with config
+    pub fn apply_config(&mut self, config: &ChartConfig) -> &mut Self {
+        self.log_scale = config.log_scale;
+        self.resolution = config.resolution;
+        self.outdate_samples();
+        self.y_lock = config.y_lock;
+        self.cache.clear();
+        return self;
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        return self.maximized;
+    }
+
+    pub fn get_title(&self) -> &str {
+        return &self.title;
+    }
+
+    /// Draws the chart alongside its titles onto the given drawing area
+    fn draw_titled<DB: DrawingBackend>(&self, root: plotters::drawing::DrawingArea<DB, plotters::coord::Shift>) {
+        use plotters::prelude::*;
+
+        root.fill(&self.style.background).expect("failed to fill chart background");
+        let (_, height) = root.dim_in_pixel();
+        let (upper, lower) = root.split_vertically(height.saturating_sub(30));
+        lower.titled(&self.title_x, (CHART_FONT_NAME, CHART_TITLE_SIZE - 10).into_font().color(&self.style.text))
+            .expect("failed to draw x-axis title");
+        let mut builder = ChartBuilder::on(&upper);
+        builder.caption(&self.title, (CHART_FONT_NAME, CHART_TITLE_SIZE).into_font().color(&self.style.text));
+        // Exports are drawn without the hover marker and shouldn't replace the on screen plotting area
+        let plot_area = self.plot_area.take();
+        self.build_chart(&None, builder);
+        self.plot_area.set(plot_area);
+        root.present().expect("failed to present chart");
+    }
+
+    /// Renders the chart as a PNG image
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
         use plotters::prelude::*;
-        const BLUE_LINE_COLOR: RGBColor = RGBColor(0, 175, 255);
-        const RED_LINE_COLOR: RGBColor = RGBColor(220, 20, 20);
-        const BLACK_LINE_COLOR: RGBColor = RGBColor(0, 0, 0);
-
-        let start = *self.x_range.start();
-        let end = *self.x_range.end();
-        let x_linspace: Vec<f64> = (0..CHART_RESOLUTION).into_iter()
-            .map(|x| start + x as f64*((end-start)/((CHART_RESOLUTION-1) as f64)) )
+
+        let (width, height) = EXPORT_SIZE;
+        let mut buffer = vec![0u8; (width * height * 3) as usize];
+        self.draw_titled(BitMapBackend::with_buffer(&mut buffer, EXPORT_SIZE).into_drawing_area());
+
+        let image = image::RgbImage::from_raw(width, height, buffer).ok_or("Chart buffer has the wrong size")?;
+        let mut png = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut png, image::ImageOutputFormat::Png).map_err(|e| e.to_string())?;
+        return Ok(png.into_inner());
+    }
+
+    /// Renders the chart as an SVG image
+    pub fn to_svg(&self) -> String {
+        use plotters::prelude::*;
+
+        let mut svg = String::new();
+        self.draw_titled(SVGBackend::with_string(&mut svg, EXPORT_SIZE).into_drawing_area());
+        return svg;
+    }
+
+    /// Points of the payoff function from sample_points. Only computed again once out of date, so redraws from
+    /// resizing or hovering don't evaluate the function. While sampling in the background, the out of date points are
+    /// returned instead.
+    fn samples(&self) -> Ref<'_, Vec<(f64, f64)>> {
+        let stored = self.samples.borrow().as_ref().map(|(generation, _)| *generation);
+        if stored.is_none() || (stored != Some(self.generation) && self.sampling.is_none()) {
+            let samples = sample_points(&self.func, &self.x_range, self.resolution());
+            *self.samples.borrow_mut() = Some((self.generation, samples));
+        }
+        return Ref::map(self.samples.borrow(), |samples| &samples.as_ref().expect("samples were just computed").1);
+    }
+
+    /// Marks the sampled points out of date, for when the function, x-axis range or resolution change
+    fn outdate_samples(&mut self) {
+        self.generation += 1;
+    }
+
+    /// Samples the payoff function in the background if the points are out of date, returning the generation sampled and
+    /// the points for set_samples. The out of date points are drawn in the meantime. None if the points are up to date
+    /// or already being sampled.
+    pub fn sample_in_background(&mut self) -> Option<impl Future<Output = (u64, Vec<(f64, f64)>)> + Send + 'static> {
+        let stored = self.samples.get_mut().as_ref().map(|(generation, _)| *generation);
+        if stored == Some(self.generation) || self.sampling.is_some() {
+            return None;
+        }
+        self.sampling = Some(self.generation);
+        let (func, x_range, resolution, generation) = (self.func.clone(), self.x_range.clone(), self.resolution(), self.generation);
+        return Some(async move { (generation, sample_points(&func, &x_range, resolution)) });
+    }
+
+    /// Stops waiting on points sampled in the background, such as ones dropped while the chart was deleted.
+    /// Out of date points are then sampled when next drawn.
+    pub fn stop_sampling(&mut self) -> &mut Self {
+        self.sampling = None;
+        return self;
+    }
+
+    /// Stores points sampled in the background for a generation, unless newer points are already stored
+    pub fn set_samples(&mut self, generation: u64, samples: Vec<(f64, f64)>) -> &mut Self {
+        if self.sampling == Some(generation) {
+            self.sampling = None;
+        }
+        if self.samples.get_mut().as_ref().is_none_or(|(stored, _)| *stored < generation) {
+            *self.samples.get_mut() = Some((generation, samples));
+            self.cache.clear();
+        }
+        return self;
+    }
+
+    /// Column headers and rows of the sampled points of the chart. Columns are the x value, the
+    /// payoff function value and the benchmark height.
+    pub fn to_table(&self) -> ([&str; 3], Vec<[f64; 3]>) {
+        let headers = [self.title_x.as_str(), self.labels[0].as_str(), self.labels[1].as_str()];
+        let rows = self.samples().iter()
+            .map(|&(x, y)| [x, y, self.benchmark])
             .collect();
+        return (headers, rows);
+    }
+
+    /// Writes the sampled points of the chart as CSV. See to_table for the columns.
+    pub fn to_csv(&self) -> String {
+        use crate::export::csv_field;
+
+        let (headers, rows) = self.to_table();
+        let mut out = headers.map(csv_field).join(",") + "\n";
+        for [x, y, benchmark] in rows {
+            out += &format!("{},{},{}\n", x, y, benchmark);
+        }
+        return out;
+    }
 
-        // Ensure y range of the graph is atleast self.y_range (or wider if needed)
-        let func_max = x_linspace.iter()
-            .map(|&x| (self.func)(x))
+    /// Values of the payoff line, and the frozen line if any, within the x-axis range
+    fn plotted_values(&self) -> Vec<f64> {
+        let mut values: Vec<f64> = self.samples().iter().map(|p| p.1).collect();
+        if let Some(ghost) = &self.ghost {
+            values.extend(ghost.iter().filter(|(x, _)| self.x_range.contains(x)).map(|p| p.1));
+        }
+        return values;
+    }
+
+    /// (start, end, log floor) of the y-axis needed to show the given values. The axis covers atleast self.y_range
+    /// and only extends below 0 for negative values. Log scales can't reach 0, so instead start at the log floor,
+    /// the smallest positive value plotted.
+    fn y_bounds(&self, values: &[f64]) -> (f64, f64, f64) {
+        let func_max = values.iter()
+            .copied()
             .reduce(f64::max)
             .unwrap_or(0.0);
-        let mut y_range = 0.0..=func_max;
-        y_range = *y_range.start()..=y_range.end().max(*self.y_range.end());
+        let y_end = func_max.max(*self.y_range.end());
+        let func_min = values.iter()
+            .copied()
+            .reduce(f64::min)
+            .unwrap_or(0.0);
+        let y_start = func_min.min(*self.y_range.start()).min(0.0);
 
-        let x_range_exclusive = *self.x_range.start()..*self.x_range.end();
-        let y_range_exclusive = *y_range.start()..*y_range.end();
-        let mut chart = chart
-            .x_label_area_size(20)
-            .y_label_area_size(40)
-            .margin(10)
-            .build_cartesian_2d(x_range_exclusive, y_range_exclusive)
-            .expect("failed to build chart");
+        let lowest = y_end.max(f64::MIN_POSITIVE) * LOG_SCALE_RANGE;
+        let floor = values.iter()
+            .copied()
+            .filter(|y| *y > 0.0)
+            .reduce(f64::min)
+            .unwrap_or(lowest)
+            .max(lowest)
+            // Keep the benchmark line in view
+            .min(self.benchmark.max(lowest));
+        return (y_start, y_end, floor);
+    }
 
-        // General chart formatting
-        chart
-            .configure_mesh()
-            .label_style((CHART_FONT_NAME).into_font())
-            .bold_line_style(plotters::style::colors::BLUE.mix(0.1))
-            .light_line_style(plotters::style::colors::BLUE.mix(0.05))
-            .axis_style(ShapeStyle::from(plotters::style::colors::BLUE.mix(0.45)).stroke_width(1))
+    /// Draws everything within the chart onto its axes, whose y-axis starts at bottom.
+    /// The probability density (if any) is drawn against a secondary y-axis on the right.
+    fn draw_contents<'a, DB: DrawingBackend + 'a, Y>(&self, mut chart: ChartContext<'a, DB, Cartesian2d<RangedCoordf64, Y>>, state: &Option<f64>, bottom: f64)
+    where Y: Ranged<ValueType = f64> + ValueFormatter<f64> {
+        use plotters::prelude::*;
+        let (text_color, density_color) = (self.style.text, self.style.density);
+
+        let Some(density) = &self.density else {
+            self.draw_primary(&mut chart, state, bottom);
+            self.style.draw_legend(&mut chart);
+            return;
+        };
+        let densities: Vec<(f64, f64)> = par_map(&self.samples(), |&(x, _)| (x, density(x)))
+            .into_iter()
+            .filter(|(_, d)| d.is_finite())
+            .collect();
+        let density_max = densities.iter().map(|p| p.1).fold(f64::MIN_POSITIVE, f64::max);
+        // The secondary axis takes over the right label area, so must be set before the primary mesh is drawn
+        let mut chart = chart.set_secondary_coord(*self.x_range.start()..*self.x_range.end(), 0.0..density_max * 1.1);
+        self.draw_primary(&mut chart, state, bottom);
+        chart.configure_secondary_axes()
+            .label_style((CHART_FONT_NAME).into_font().color(&text_color))
+            .axis_style(ShapeStyle::from(density_color.mix(0.45)).stroke_width(1))
             .y_labels(10)
-            .y_label_formatter(&|y: &f64| format!("{:.1}", y))
+            .y_label_formatter(&|y: &f64| format_tick(*y, density_max * 1.1 / 10.0, false))
             .draw()
             .expect("failed to draw chart mesh");
+        chart.draw_secondary_series(LineSeries::new(densities, ShapeStyle::from(density_color).stroke_width(self.style.line_width)))
+            .expect("failed to draw chart data")
+            .label(legend_label("Probability density"))
+            // y+5 is to lower the legend-line to be inline with the label
+            .legend(move |(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], density_color));
+        self.style.draw_legend(&mut chart);
+    }
+
+    /// Draws the payoff function, benchmark, markers and crosshair onto axes whose y-axis starts at bottom.
+    /// Areas are filled from 0, or from the bottom on a log scale where 0 can't be reached.
+    fn draw_primary<'a, DB: DrawingBackend + 'a, Y>(&self, chart: &mut ChartContext<'a, DB, Cartesian2d<RangedCoordf64, Y>>, state: &Option<f64>, bottom: f64)
+    where Y: Ranged<ValueType = f64> + ValueFormatter<f64> {
+        use plotters::prelude::*;
+        let ChartStyle { text: text_color, primary: line_color, benchmark: benchmark_color, line_width, fill_opacity, .. } = self.style;
+        let (x_dp, y_dp) = self.decimals;
+
+        self.plot_area.set(Some(chart.plotting_area().get_pixel_range()));
+        let samples = self.samples();
+        let baseline = bottom.max(0.0);
+        // Log scale ticks span many magnitudes so are formatted to their own size
+        let y_range = chart.y_range();
+        let tick_gap = (y_range.end - y_range.start) / 10.0;
+        let y_formatter = |y: &f64| format_tick(*y, if self.log_scale { y.abs() } else { tick_gap }, self.percent_axis);
+
+        // General chart formatting
+        self.style.draw_mesh(chart, &y_formatter);
+
+        // Zero line to separate gains from losses
+        if bottom < 0.0 {
+            let (start, end) = (*self.x_range.start(), *self.x_range.end());
+            chart.draw_series(LineSeries::new([(start, 0.0), (end, 0.0)], text_color.mix(0.6)))
+                .expect("failed to draw chart data");
+        }
 
         // Draw the function given at self.func
         chart.draw_series(
                 AreaSeries::new(
-                    x_linspace.iter().map(|&x| (x, (self.func)(x))),
-                    0.0,
-                    BLUE_LINE_COLOR.mix(0.175),
+                    samples.iter().map(|&(x, y)| (x, y.max(bottom))),
+                    baseline,
+                    line_color.mix(fill_opacity),
                 )
-                .border_style(ShapeStyle::from(BLUE_LINE_COLOR).stroke_width(2)),
+                .border_style(ShapeStyle::from(line_color).stroke_width(line_width)),
             ).expect("failed to draw chart data")
-            // Empty spaces to act as margin
-            .label(format!("{}   ", self.labels[0].to_owned()))
+            .label(legend_label(&self.labels[0]))
             // y+5 is to lower the legend-line to be inline with the label
-            .legend(|(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], BLUE_LINE_COLOR));
+            .legend(move |(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], line_color));
+
+        // Draw the frozen payoff line (if any) to compare against
+        if let Some(ghost) = &self.ghost {
+            chart.draw_series(
+                DashedLineSeries::new(
+                    ghost.iter().filter(|(x, _)| self.x_range.contains(x)).map(|&(x, y)| (x, y.max(bottom))),
+                    6,
+                    4,
+                    ShapeStyle::from(line_color.mix(0.6)).stroke_width(line_width),
+                )
+            ).expect("failed to draw chart data")
+            .label(legend_label("Frozen"))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], line_color.mix(0.6)));
+        }
 
         // Draw profit benchmark line
         chart.draw_series(
                 AreaSeries::new(
-                    x_linspace.iter().map(|&x| (x, self.benchmark)),
-                    0.0,
-                    RED_LINE_COLOR.mix(0.175),
+                    samples.iter().map(|&(x, _)| (x, self.benchmark.max(bottom))),
+                    baseline,
+                    benchmark_color.mix(fill_opacity),
                 )
-                .border_style(ShapeStyle::from(RED_LINE_COLOR).stroke_width(2)),
+                .border_style(ShapeStyle::from(benchmark_color).stroke_width(line_width)),
             ).expect("failed to draw chart data")
-            // Empty spaces to act as margin
-            .label(format!("{}   ", self.labels[1].to_owned()))
+            .label(legend_label(&self.labels[1]))
             // y+5 is to lower the legend-line to be inline with the label
-            .legend(|(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], RED_LINE_COLOR));
+            .legend(move |(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], benchmark_color));
         
         // Invisible filler line.
         // Only being so we can display the height of the benchmark line in the legend.
@@ -209,45 +650,211 @@ impl Chart<PayoffChartMessage> for PayoffChart {
         // drawing the legend border.
         chart.draw_series(
                 AreaSeries::new(
-                    x_linspace.iter().map(|&x| (x, x)),
-                    0.0,
-                    BLACK_LINE_COLOR.mix(0.0)
+                    samples.iter().map(|&(x, _)| (x, x.max(bottom))),
+                    baseline,
+                    text_color.mix(0.0)
                 )
-                .border_style(ShapeStyle::from(RED_LINE_COLOR).stroke_width(0))
+                .border_style(ShapeStyle::from(text_color.mix(0.0)).stroke_width(0))
             )
             .expect("failed to draw chart data")
-            .label(format!("({:.2})", self.benchmark));
+            .label(format!("({:.*})", y_dp, self.benchmark));
+
+        // Draw labelled markers within the x range
+        let top = chart.y_range().end;
+        for (i, (label, x)) in self.markers.iter().filter(|(_, x)| self.x_range.contains(x)).enumerate() {
+            chart.draw_series(DashedLineSeries::new([(*x, bottom), (*x, top)], 5, 5, text_color.mix(0.6).into()))
+                .expect("failed to draw chart data");
+            // Labels are staggered downwards so nearby markers stay readable
+            chart.draw_series(iter::once(
+                EmptyElement::at((*x, top))
+                + Text::new(format!("{} {:.*}", label, x_dp, x), (4, 4 + 15 * i as i32), (CHART_FONT_NAME, 13).into_font().color(&text_color))
+            )).expect("failed to draw chart data");
+        }
 
         // Draw vertical crosshair line (if valid)
-        if let Some(x_vert) = self.x_vert {
+        if let Some(x_vert) = self.x_vert && !(self.func)(x_vert).is_nan() {
             let val = (self.func)(x_vert);
-            if val.is_nan() {
-                return;
-            }
             chart.draw_series(
                 LineSeries::new(
-                    [(x_vert, 0.0), (x_vert, f64::MAX)].iter().map(|&x| x),
-                    BLACK_LINE_COLOR
+                    [(x_vert, bottom), (x_vert, f64::MAX)].iter().copied(),
+                    text_color
                 )
             ).expect("failed to draw chart data");
-            // Highlight where vertical line intersects main function
+            // Highlight where vertical line intersects main function. Values below the bottom (of a log scale or a
+            // locked range) are drawn on it, but labelled with their own value.
             chart.draw_series(PointSeries::of_element(
-                iter::once((x_vert, val)),
+                iter::once((x_vert, val.max(bottom))),
                 5,
-                ShapeStyle::from(&RED).filled(),
+                ShapeStyle::from(&benchmark_color).filled(),
                 &|coord, size, style| {
                     EmptyElement::at(coord)
                     + Circle::new((0, 0), size, style)
-                    + Text::new(format!("({:.3}, {:.2})", coord.0, coord.1), (8, 8), (CHART_FONT_NAME, 15))
+                    + Text::new(format!("({:.*}, {:.*})", x_dp, coord.0, y_dp, val), (8, 8), (CHART_FONT_NAME, 15).into_font().color(&text_color))
                 },
             )).expect("failed to draw chart data");
         }
 
-        // Draw line legends
-        chart.configure_series_labels()
-            .border_style(BLACK)
-            .label_font((CHART_FONT_NAME, 15))
-            .draw()
-            .expect("failed to draw line labels");
+        // Mark the point of the function under the cursor
+        if let Some(hover_x) = *state {
+            let val = (self.func)(hover_x);
+            if !val.is_nan() {
+                chart.draw_series(
+                    LineSeries::new(
+                        [(hover_x, bottom), (hover_x, val.max(bottom))].iter().copied(),
+                        text_color.mix(0.4)
+                    )
+                ).expect("failed to draw chart data");
+                chart.draw_series(PointSeries::of_element(
+                    iter::once((hover_x, val.max(bottom))),
+                    4,
+                    ShapeStyle::from(&line_color).filled(),
+                    &|coord, size, style| {
+                        EmptyElement::at(coord)
+                        + Circle::new((0, 0), size, style)
+                        + Text::new(format!("({:.*}, {:.*})", x_dp, coord.0, y_dp, val), (8, -20), (CHART_FONT_NAME, 15).into_font().color(&text_color))
+                    },
+                )).expect("failed to draw chart data");
+            }
+        }
+    }
+}
+/// Formats an axis tick value. Decimal places follow the gap between ticks, large values are shortened with
+/// K/M suffixes and percent axes show ratios as percentages.
+pub fn format_tick(value: f64, tick_gap: f64, percent: bool) -> String {
+    let (mut value, mut tick_gap) = (value, tick_gap);
+    if percent {
+        (value, tick_gap) = (value * 100.0, tick_gap * 100.0);
+    }
+    let mut suffix = String::new();
+    for (size, letter) in [(1e6, "M"), (1e3, "K")] {
+        if value.abs() >= size {
+            (value, tick_gap) = (value / size, tick_gap / size);
+            suffix.push_str(letter);
+            break;
+        }
+    }
+    if percent {
+        suffix.push('%');
+    }
+    // Enough decimal places to tell neighbouring ticks apart
+    let decimals = if tick_gap > 0.0 && tick_gap.is_finite() { (-tick_gap.log10().floor()).clamp(0.0, 6.0) as usize } else { 1 };
+    return format!("{:.*}{}", decimals, value, suffix);
+}
+
+/// Points of the function across the x-axis range. These start evenly spaced, with extra points added
+/// wherever the function bends sharply (e.g near the strike or where the ROI floors to 0).
+fn sample_points(func: &ChartFunc, x_range: &RangeInclusive<f64>, resolution: usize) -> Vec<(f64, f64)> {
+    let (start, end) = (*x_range.start(), *x_range.end());
+    let xs: Vec<f64> = (0..resolution)
+        .map(|x| start + x as f64*((end-start)/((resolution-1) as f64)) )
+        .collect();
+    let even = par_map(&xs, |&x| (x, func(x)));
+
+    let (low, high) = even.iter()
+        .map(|p| p.1)
+        .filter(|y| y.is_finite())
+        .fold((f64::MAX, f64::MIN), |(low, high), y| (low.min(y), high.max(y)));
+    let tolerance = (high - low).max(f64::MIN_POSITIVE) * REFINEMENT_TOLERANCE;
+
+    // Each gap between the even points is refined separately
+    let gaps: Vec<&[(f64, f64)]> = even.windows(2).collect();
+    let refined = par_map(&gaps, |pair| {
+        let mut out = Vec::new();
+        refine(func, pair[0], pair[1], tolerance, MAX_REFINEMENT_DEPTH, &mut out);
+        out.push(pair[1]);
+        out
+    });
+    return even.first().copied().into_iter().chain(refined.into_iter().flatten()).collect();
+}
+
+/// Adds points between the two datapoints (exclusive) until the function is within tolerance
+/// of a straight line between neighbouring datapoints
+fn refine(func: &ChartFunc, (x0, y0): (f64, f64), (x1, y1): (f64, f64), tolerance: f64, depth: u32, out: &mut Vec<(f64, f64)>) {
+    if depth == 0 {
+        return;
+    }
+    let mid = (x0 + x1) / 2.0;
+    let y_mid = func(mid);
+    // Undefined values have nothing to follow
+    if !(y0.is_finite() && y1.is_finite() && y_mid.is_finite()) || (y_mid - (y0 + y1) / 2.0).abs() <= tolerance {
+        return;
+    }
+    refine(func, (x0, y0), (mid, y_mid), tolerance, depth - 1, out);
+    out.push((mid, y_mid));
+    refine(func, (mid, y_mid), (x1, y1), tolerance, depth - 1, out);
+}
+
+/// Maps every item, in parallel on desktop
+fn par_map<T: Sync, U: Send>(items: &[T], f: impl Fn(&T) -> U + Sync + Send) -> Vec<U> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use rayon::prelude::*;
+        return items.par_iter().map(f).collect();
+    }
+    // No threads on the web build
+    #[cfg(target_arch = "wasm32")]
+    {
+        return items.iter().map(f).collect();
+    }
+}
+
+impl Chart<PayoffChartMessage> for PayoffChart {
+    /// x value under the cursor, if hovering over the plotting area
+    type State = Option<f64>;
+
+    #[inline]
+    fn draw<R: Renderer, F: Fn(&mut Frame)>(
+        &self,
+        renderer: &R,
+        bounds: Size,
+        draw_fn: F,
+    ) -> Geometry {
+        renderer.draw_cache(&self.cache, bounds, draw_fn)
+    }
+
+    fn update(&self, state: &mut Self::State, event: &iced::widget::canvas::Event, bounds: Rectangle, cursor: Cursor) -> (Status, Option<PayoffChartMessage>) {
+        use iced::mouse;
+
+        let clicked = matches!(event, iced::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)));
+        if !clicked && !matches!(event, iced::Event::Mouse(mouse::Event::CursorMoved { .. } | mouse::Event::CursorLeft)) {
+            return (Status::Ignored, None);
+        }
+        let area = self.plot_area.take();
+        self.plot_area.set(area.clone());
+        let hovered_x = cursor.position_in(bounds)
+            .zip(area)
+            .filter(|(pos, (xs, ys))| xs.contains(&(pos.x as i32)) && ys.contains(&(pos.y as i32)))
+            .map(|(pos, (xs, _))| {
+                let (start, end) = (*self.x_range.start(), *self.x_range.end());
+                start + (end - start) * (pos.x as f64 - xs.start as f64) / (xs.end - xs.start).max(1) as f64
+            });
+        if clicked {
+            // Left ignored so the export buttons on top of the chart still receive the click
+            return (Status::Ignored, hovered_x.map(PayoffChartMessage::Clicked));
+        }
+        if hovered_x == *state {
+            return (Status::Ignored, None);
+        }
+        *state = hovered_x;
+        // Left ignored so the export buttons on top of the chart still receive the event
+        return (Status::Ignored, Some(PayoffChartMessage::HoverMoved));
+    }
+
+    fn build_chart<DB: DrawingBackend>(&self, state: &Self::State, mut chart: ChartBuilder<DB>) {
+        use plotters::prelude::*;
+
+        // Ensure y range of the graph is atleast self.y_range (or wider if needed), unless locked
+        let (y_start, y_end, floor) = self.y_lock.unwrap_or_else(|| self.y_bounds(&self.plotted_values()));
+
+        let x_range_exclusive = *self.x_range.start()..*self.x_range.end();
+        chart
+            .x_label_area_size(20)
+            .y_label_area_size(40)
+            .right_y_label_area_size(if self.density.is_some() { 45 } else { 0 })
+            .margin(10);
+        match self.log_scale {
+            false => self.draw_contents(chart.build_cartesian_2d(x_range_exclusive, y_start..y_end).expect("failed to build chart"), state, y_start),
+            true => self.draw_contents(chart.build_cartesian_2d(x_range_exclusive, (floor..y_end).log_scale()).expect("failed to build chart"), state, floor),
+        }
     }
 }
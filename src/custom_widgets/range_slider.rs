@@ -0,0 +1,129 @@
+use std::ops::RangeInclusive;
+use iced::{Element, Length, Point, Rectangle, Renderer, Theme, mouse};
+use iced::widget::canvas::{self, Action, Canvas, Event, Frame, Geometry, Path, Stroke};
+
+/// Radius of the handles
+const HANDLE_RADIUS: f32 = 6.0;
+
+/// Height of the widget
+const RANGE_SLIDER_HEIGHT: f32 = 16.0;
+
+/// Number of steps the domain is split into when rounding dragged values
+const DRAG_STEPS: f64 = 200.0;
+
+/// A slider with a handle for each end of a range of values picked out of the domain.
+/// The domain is held fixed while a handle is dragged, so the handles don't jump if it changes with the range.
+pub fn range_slider<'a, Message: 'a>(
+    domain: RangeInclusive<f64>,
+    range: RangeInclusive<f64>,
+    on_change: impl Fn(RangeInclusive<f64>) -> Message + 'a,
+) -> Element<'a, Message> {
+    Canvas::new(RangeSlider { domain, range, on_change: Box::new(on_change) })
+        .width(Length::Fill)
+        .height(RANGE_SLIDER_HEIGHT)
+        .into()
+}
+
+struct RangeSlider<'a, Message> {
+    domain: RangeInclusive<f64>,
+    range: RangeInclusive<f64>,
+    on_change: Box<dyn Fn(RangeInclusive<f64>) -> Message + 'a>,
+}
+
+#[derive(Default)]
+struct DragState {
+    /// Handle being dragged (0 for the lower, 1 for the upper) and the domain when the drag started
+    dragging: Option<(usize, RangeInclusive<f64>)>,
+}
+
+impl<Message> RangeSlider<'_, Message> {
+    /// x position within the bounds of a value in the domain
+    fn x_of(value: f64, domain: &RangeInclusive<f64>, bounds: Rectangle) -> f32 {
+        let span = domain.end() - domain.start();
+        let ratio = if span > 0.0 { ((value - domain.start()) / span).clamp(0.0, 1.0) } else { 0.0 };
+        return HANDLE_RADIUS + (bounds.width - 2.0 * HANDLE_RADIUS) * ratio as f32;
+    }
+
+    /// Value in the domain at an x position within the bounds, rounded to a 200th of the domain
+    fn value_at(x: f32, domain: &RangeInclusive<f64>, bounds: Rectangle) -> f64 {
+        let ratio = ((x - HANDLE_RADIUS) / (bounds.width - 2.0 * HANDLE_RADIUS)).clamp(0.0, 1.0) as f64;
+        let span = domain.end() - domain.start();
+        let value = domain.start() + span * ratio;
+        if span <= 0.0 {
+            return value;
+        }
+        // Round to a power of 10 so the bounds read cleanly
+        let step = 10f64.powf((span / DRAG_STEPS).log10().floor());
+        return ((value / step).round() * step).clamp(*domain.start(), *domain.end());
+    }
+
+    /// Moves the handle to an x position
+    fn drag(&self, handle: usize, x: f32, domain: &RangeInclusive<f64>, bounds: Rectangle) -> Action<Message> {
+        let value = Self::value_at(x, domain, bounds);
+        let range = if handle == 0 {
+            value.min(*self.range.end())..=*self.range.end()
+        } else {
+            *self.range.start()..=value.max(*self.range.start())
+        };
+        return Action::publish((self.on_change)(range)).and_capture();
+    }
+}
+
+impl<Message> canvas::Program<Message> for RangeSlider<'_, Message> {
+    type State = DragState;
+
+    fn update(&self, state: &mut Self::State, event: &Event, bounds: Rectangle, cursor: mouse::Cursor) -> Option<Action<Message>> {
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let position = cursor.position_in(bounds)?;
+                // Grab whichever handle is nearer. The upper one if they overlap and the press is to their right.
+                let lower = Self::x_of(*self.range.start(), &self.domain, bounds);
+                let upper = Self::x_of(*self.range.end(), &self.domain, bounds);
+                let handle = if (position.x - lower).abs() < (position.x - upper).abs()
+                    || (lower == upper && position.x < lower) { 0 } else { 1 };
+                state.dragging = Some((handle, self.domain.clone()));
+                return Some(self.drag(handle, position.x, &self.domain, bounds));
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let (handle, domain) = state.dragging.clone()?;
+                return Some(self.drag(handle, position.x - bounds.x, &domain, bounds));
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                state.dragging.take()?;
+                return Some(Action::capture());
+            }
+            _ => return None,
+        }
+    }
+
+    fn draw(&self, state: &Self::State, renderer: &Renderer, theme: &Theme, bounds: Rectangle, _: mouse::Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let domain = state.dragging.as_ref().map_or(&self.domain, |(_, domain)| domain);
+        let (lower, upper) = (Self::x_of(*self.range.start(), domain, bounds), Self::x_of(*self.range.end(), domain, bounds));
+        let y = bounds.height / 2.0;
+        let palette = theme.extended_palette();
+
+        frame.stroke(
+            &Path::line(Point::new(HANDLE_RADIUS, y), Point::new(bounds.width - HANDLE_RADIUS, y)),
+            Stroke::default().with_width(2.0).with_color(palette.background.strong.color),
+        );
+        frame.stroke(
+            &Path::line(Point::new(lower, y), Point::new(upper, y)),
+            Stroke::default().with_width(4.0).with_color(palette.primary.base.color),
+        );
+        for x in [lower, upper] {
+            frame.fill(&Path::circle(Point::new(x, y), HANDLE_RADIUS), palette.primary.base.color);
+        }
+        return vec![frame.into_geometry()];
+    }
+
+    fn mouse_interaction(&self, state: &Self::State, bounds: Rectangle, cursor: mouse::Cursor) -> mouse::Interaction {
+        if state.dragging.is_some() {
+            return mouse::Interaction::Grabbing;
+        }
+        if cursor.is_over(bounds) {
+            return mouse::Interaction::Grab;
+        }
+        return mouse::Interaction::default();
+    }
+}
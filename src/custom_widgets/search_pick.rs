@@ -0,0 +1,30 @@
+use std::fmt::Display;
+
+use iced::widget::combo_box::{self, ComboBox};
+
+/// Dropdown whose options can be filtered by typing into it, for lists that grow too long to scroll through.
+/// Unlike a pick_list the typed search has to be kept between views, so the options live in here.
+/// The parent keeps the selection and reads a choice off the message given to view.
+pub struct SearchPick<T> {
+    state: combo_box::State<T>,
+}
+impl<T: Display + Clone + 'static> SearchPick<T> {
+    pub fn new(options: Vec<T>) -> Self {
+        return Self { state: combo_box::State::new(options) };
+    }
+
+    pub fn options(&self) -> &[T] {
+        return self.state.options();
+    }
+
+    /// Replaces the options. Clears any typed search.
+    pub fn set_options(&mut self, options: Vec<T>) -> &mut Self {
+        self.state = combo_box::State::new(options);
+        return self;
+    }
+
+    /// Dropdown showing the selected option, with the placeholder shown while nothing is selected or typed
+    pub fn view<'a, Message: Clone + 'a>(&'a self, placeholder: &str, selected: Option<&T>, on_select: impl Fn(T) -> Message + 'static) -> ComboBox<'a, T, Message> {
+        return ComboBox::new(&self.state, placeholder, selected, on_select);
+    }
+}
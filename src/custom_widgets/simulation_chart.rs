@@ -0,0 +1,137 @@
+use iced::Element;
+use iced::widget::{column, text};
+use iced::widget::canvas::{Cache, Frame, Geometry};
+use iced::Size;
+use iced::Center;
+use plotters_iced2::{Renderer};
+use plotters_iced2::{Chart, ChartWidget, DrawingBackend, ChartBuilder};
+
+use crate::custom_widgets::ChartStyle;
+use crate::custom_widgets::chart_style::{CHART_TITLE_SIZE, legend_label};
+use crate::custom_widgets::payoff_chart::format_tick;
+
+/// Chart widget to draw the spread of an option's value over time across simulated stock price paths.
+/// Shades the 5th-95th and 25th-75th percentile bands around the median, against the premium paid.
+pub struct SimulationChart {
+    cache: Cache,
+    /// Time of each step, with the 5th, 25th, 50th, 75th and 95th percentile values at it
+    bands: Vec<(f64, [f64; 5])>,
+    /// Price paid for the option, marked with a horizontal line
+    premium: f64,
+    /// Factor the values and premium are multiplied by. (E.g the contract multiplier).
+    scale: f64,
+    /// Number of paths the bands were computed from
+    paths: usize,
+    /// Chart title
+    title: String,
+    /// Colours and line styling the chart is drawn with
+    style: ChartStyle,
+}
+impl Default for SimulationChart {
+    fn default() -> Self {
+        Self {
+            cache: Cache::new(),
+            bands: Vec::new(),
+            premium: 1.0,
+            scale: 1.0,
+            paths: 0,
+            title: String::from("Simulated Option Value"),
+            style: ChartStyle::default(),
+        }
+    }
+}
+impl SimulationChart {
+    pub fn view<'a, Message: 'a>(&'a self) -> Element<'a, Message> {
+        column![
+            text!("{}", self.title).size(CHART_TITLE_SIZE),
+            ChartWidget::new(self),
+            text("Years from now").size(CHART_TITLE_SIZE - 10),
+        ].align_x(Center)
+        .into()
+    }
+
+    /// Sets the percentile bands drawn, computed from the given number of paths
+    pub fn set_bands(&mut self, bands: Vec<(f64, [f64; 5])>, paths: usize) -> &mut Self {
+        self.bands = bands;
+        self.paths = paths;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the price paid for the option
+    pub fn set_premium(&mut self, premium: f64) -> &mut Self {
+        self.premium = premium;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the factor the values and premium are multiplied by
+    pub fn set_scale(&mut self, scale: f64) -> &mut Self {
+        self.scale = scale;
+        self.cache.clear();
+        return self;
+    }
+
+    /// Sets the colours and line styling the chart is drawn with
+    pub fn set_style(&mut self, style: ChartStyle) -> &mut Self {
+        self.style = style;
+        self.cache.clear();
+        return self;
+    }
+}
+impl<Message> Chart<Message> for SimulationChart {
+    type State = ();
+
+    #[inline]
+    fn draw<R: Renderer, F: Fn(&mut Frame)>(
+        &self,
+        renderer: &R,
+        bounds: Size,
+        draw_fn: F,
+    ) -> Geometry {
+        renderer.draw_cache(&self.cache, bounds, draw_fn)
+    }
+
+    fn build_chart<DB: DrawingBackend>(&self, _: &Self::State, mut chart: ChartBuilder<DB>) {
+        use plotters::prelude::*;
+        let ChartStyle { text: text_color, primary, line_width, fill_opacity, .. } = self.style;
+
+        let band = |i: usize| self.bands.iter().map(move |(t, values)| (*t, values[i] * self.scale));
+        let start = self.bands.first().map_or(0.0, |b| b.0);
+        // Nothing to draw over an empty time frame, but the axes are still shown
+        let end = self.bands.last().map_or(1.0, |b| b.0).max(start + f64::EPSILON);
+        let y_max = band(4).map(|p| p.1).filter(|y| y.is_finite()).fold(self.premium * self.scale, f64::max);
+        let mut chart = chart
+            .x_label_area_size(20)
+            .y_label_area_size(40)
+            .margin(10)
+            .build_cartesian_2d(start..end, 0.0..y_max * 1.1)
+            .expect("failed to build chart");
+
+        let y_range = chart.y_range();
+        let tick_gap = (y_range.end - y_range.start) / 10.0;
+        self.style.draw_mesh(&mut chart, &|y: &f64| format_tick(*y, tick_gap, false));
+
+        // Each band is the region between its lower and upper percentile lines
+        for ((lower, upper), opacity, name) in [((0, 4), fill_opacity, "5th-95th"), ((1, 3), fill_opacity * 2.0, "25th-75th")] {
+            let outline: Vec<(f64, f64)> = band(lower).chain(band(upper).rev()).collect();
+            chart.draw_series(std::iter::once(Polygon::new(outline, primary.mix(opacity).filled())))
+                .expect("failed to draw chart data")
+                .label(legend_label(format!("{} percentile", name)))
+                .legend(move |(x, y)| Rectangle::new([(x, y), (x + 20, y + 10)], primary.mix(opacity).filled()));
+        }
+        chart.draw_series(LineSeries::new(band(2), ShapeStyle::from(primary).stroke_width(line_width)))
+            .expect("failed to draw chart data")
+            .label(legend_label(format!("Median of {} paths", self.paths)))
+            // y+5 is to lower the legend-line to be inline with the label
+            .legend(move |(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], primary));
+
+        let premium = self.premium * self.scale;
+        chart.draw_series(DashedLineSeries::new([(start, premium), (end, premium)], 5, 5, text_color.into()))
+            .expect("failed to draw chart data")
+            .label(legend_label(format!("Premium ({:.2})", premium)))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y+5), (x + 20, y+5)], text_color));
+
+        self.style.draw_legend(&mut chart);
+    }
+}
@@ -0,0 +1,79 @@
+use iced::{Element, Length, Point, Rectangle, Renderer, Theme, mouse};
+use iced::widget::canvas::{self, Action, Canvas, Event, Frame, Geometry, Path, Stroke};
+
+/// Width of the area the splitter can be grabbed by
+const SPLITTER_WIDTH: f32 = 8.0;
+
+/// Width of the line drawn down the middle of the splitter
+const LINE_WIDTH: f32 = 2.0;
+
+/// A vertical line between two panels that can be dragged sideways to resize them.
+/// Each movement of a drag gives the horizontal distance moved since the last, and on_release is given once
+/// the drag ends (e.g to save the new size).
+pub fn splitter<'a, Message: Clone + 'a>(
+    on_drag: impl Fn(f32) -> Message + 'a,
+    on_release: Message,
+) -> Element<'a, Message> {
+    Canvas::new(Splitter { on_drag: Box::new(on_drag), on_release })
+        .width(SPLITTER_WIDTH)
+        .height(Length::Fill)
+        .into()
+}
+
+struct Splitter<'a, Message> {
+    on_drag: Box<dyn Fn(f32) -> Message + 'a>,
+    on_release: Message,
+}
+
+#[derive(Default)]
+struct DragState {
+    /// Cursor x position at the last movement of the drag. None while not dragging.
+    last_x: Option<f32>,
+}
+
+impl<Message: Clone> canvas::Program<Message> for Splitter<'_, Message> {
+    type State = DragState;
+
+    fn update(&self, state: &mut Self::State, event: &Event, bounds: Rectangle, cursor: mouse::Cursor) -> Option<Action<Message>> {
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let position = cursor.position_over(bounds)?;
+                state.last_x = Some(position.x);
+                return Some(Action::capture());
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let last_x = state.last_x.replace(position.x)?;
+                return Some(Action::publish((self.on_drag)(position.x - last_x)).and_capture());
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                state.last_x.take()?;
+                return Some(Action::publish(self.on_release.clone()).and_capture());
+            }
+            _ => return None,
+        }
+    }
+
+    fn draw(&self, state: &Self::State, renderer: &Renderer, theme: &Theme, bounds: Rectangle, cursor: mouse::Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let palette = theme.extended_palette();
+        // Highlighted while it can be or is being dragged
+        let color = if state.last_x.is_some() || cursor.is_over(bounds) {
+            palette.primary.base.color
+        } else {
+            palette.background.strong.color
+        };
+        let x = bounds.width / 2.0;
+        frame.stroke(
+            &Path::line(Point::new(x, 0.0), Point::new(x, bounds.height)),
+            Stroke::default().with_width(LINE_WIDTH).with_color(color),
+        );
+        return vec![frame.into_geometry()];
+    }
+
+    fn mouse_interaction(&self, state: &Self::State, bounds: Rectangle, cursor: mouse::Cursor) -> mouse::Interaction {
+        if state.last_x.is_some() || cursor.is_over(bounds) {
+            return mouse::Interaction::ResizingHorizontally;
+        }
+        return mouse::Interaction::default();
+    }
+}
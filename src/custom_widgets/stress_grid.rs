@@ -0,0 +1,87 @@
+use iced::{Center, Color, Element, Length};
+use iced::widget::{Column, Row, column, container, text};
+
+use crate::custom_widgets::ChartStyle;
+
+/// Relative shocks to the stock price along the columns of the grid
+pub const SPOT_SHOCKS: [f64; 7] = [-0.2, -0.1, -0.05, 0.0, 0.05, 0.1, 0.2];
+
+/// Relative shocks to the volatility down the rows of the grid
+pub const VOL_SHOCKS: [f64; 5] = [-0.25, -0.1, 0.0, 0.1, 0.25];
+
+/// Determines title text size
+const TITLE_SIZE: u32 = 25;
+
+/// Determines text size of the cells
+const CELL_TEXT_SIZE: u32 = 12;
+
+/// Table of the value and ROI of a contract over a matrix of stock price and volatility shocks.
+/// Cells are coloured by ROI on the same scale as the heatmap, red below breakeven and blue above.
+#[derive(Default)]
+pub struct StressGrid {
+    /// (value, ROI) of each cell. Rows follow VOL_SHOCKS and columns SPOT_SHOCKS.
+    values: Vec<Vec<(f64, f64)>>,
+    /// Colours the cells are drawn with
+    style: ChartStyle,
+    /// Decimal places of the values and ROIs shown
+    decimals: (usize, usize),
+}
+impl StressGrid {
+    /// Evaluates the grid a row at a time. Given the spot shocks and the vol shock of a row, the function gives
+    /// the (value, ROI) of each cell in the row.
+    pub fn set_rows(&mut self, func: impl Fn(&[f64], f64) -> Vec<(f64, f64)>) -> &mut Self {
+        self.values = VOL_SHOCKS.iter().map(|&vol_shock| func(&SPOT_SHOCKS, vol_shock)).collect();
+        return self;
+    }
+
+    /// Sets the colours the cells are drawn with
+    pub fn set_style(&mut self, style: ChartStyle) -> &mut Self {
+        self.style = style;
+        return self;
+    }
+
+    /// Sets the decimal places of the values and ROIs shown
+    pub fn set_decimals(&mut self, value: usize, roi: usize) -> &mut Self {
+        self.decimals = (value, roi);
+        return self;
+    }
+
+    pub fn view<'a, Message: 'a>(&'a self) -> Element<'a, Message> {
+        let percent = |shock: f64| format!("{:+.0}%", shock * 100.0);
+        let cell = |content: String| container(text(content).size(CELL_TEXT_SIZE).align_x(Center))
+            .width(Length::FillPortion(1))
+            .align_x(Center)
+            .padding(3);
+
+        let max = self.values.iter().flatten()
+            .map(|&(_, roi)| roi)
+            .filter(|roi| roi.is_finite())
+            .fold(1.0, f64::max);
+        let header = Row::from_iter(std::iter::once(cell(String::from("Vol \\ Spot")).into())
+            .chain(SPOT_SHOCKS.iter().map(|&shock| cell(percent(shock)).into())));
+        let rows = self.values.iter().zip(VOL_SHOCKS).map(|(row, vol_shock)| {
+            Row::from_iter(std::iter::once(cell(percent(vol_shock)).into())
+                .chain(row.iter().map(|&(value, roi)| {
+                    let color = self.style.scale_color(roi, 1.0, max);
+                    let (background, text_color) = (Color::from_rgb8(color.0, color.1, color.2), self.style.text);
+                    cell(format!("{:.*}\n{:.*}", self.decimals.0, value, self.decimals.1, roi))
+                        .style(move |_| container::Style {
+                            background: Some(background.into()),
+                            text_color: Some(Color::from_rgb8(text_color.0, text_color.1, text_color.2)),
+                            ..Default::default()
+                        })
+                        .into()
+                })))
+                .spacing(2)
+                .into()
+        });
+
+        column![
+            text("Stress Test").size(TITLE_SIZE),
+            text("Value and ROI at the prediction end with the stock price and volatility shocked").size(TITLE_SIZE - 10),
+            Column::from_iter(std::iter::once(header.spacing(2).into()).chain(rows)).spacing(2),
+        ].spacing(5)
+        .align_x(Center)
+        .into()
+    }
+}
@@ -0,0 +1,34 @@
+//! Details about how the last calculation went, shown in the status bar.
+
+use chrono::{DateTime, Local};
+
+#[derive(Debug, Clone)]
+pub struct Diagnostics {
+    /// When the calculation was run
+    pub calculated_at: DateTime<Local>,
+    /// How long the calculation took in milliseconds
+    pub duration_ms: i64,
+    /// Name of the pricing model the contracts were priced by
+    pub model: &'static str,
+    /// Gradient ascent steps taken until convergence
+    pub iterations: usize,
+    pub converged: bool,
+    /// Caveats about the answer that are worth pointing out
+    pub warnings: Vec<String>,
+}
+impl Diagnostics {
+    /// Single line description of the calculation
+    pub fn summary(&self) -> String {
+        let convergence = match self.converged {
+            true => format!("converged in {} iterations", self.iterations),
+            false => format!("not converged after {} iterations", self.iterations),
+        };
+        return format!(
+            "Calculated at {} in {} ms | {} | Optimiser {}",
+            self.calculated_at.format("%H:%M:%S"),
+            self.duration_ms,
+            self.model,
+            convergence,
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Driving the web build from the page it's embedded in (e.g an iframe in a blog or dashboard) through
+//! `postMessage`. Messages are JSON, either as objects or strings, told apart by their `type`:
+//!  - `{"type": "which_option.setInputs", "inputs": {"stock": 100, "vol": 0.3}}` fills in the parameter inputs
+//!    given. Any of `stock`, `vol`, `risk_free`, `div_yield`, `prediction_stock` and `prediction_time` can be given,
+//!    in decimals and years whatever units the inputs are entered in.
+//!  - `{"type": "which_option.calculate"}` calculates the answer, then replies with the result.
+//!  - `{"type": "which_option.getResult"}` replies with the result.
+//!
+//! Replies are posted to the parent window as `{"type": "which_option.result", ...}`, see EmbedResult, and only
+//! delivered if the parent window is still at the origin of the request.
+//!
+//! Building with `WHICH_OPTION_EMBED_ORIGINS` set to a comma separated list of origins (e.g
+//! `https://example.com,https://blog.example.com`) ignores requests from any other origin. Without it, requests
+//! from any origin are accepted.
+
+use iced::futures::Stream;
+use iced::futures::channel::mpsc;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum EmbedRequest {
+    #[serde(rename = "which_option.setInputs")]
+    SetInputs { inputs: EmbedInputs },
+    #[serde(rename = "which_option.calculate")]
+    Calculate,
+    #[serde(rename = "which_option.getResult")]
+    GetResult,
+}
+
+/// Values of the parameter inputs in decimals and years. Inputs not given are left as they are.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct EmbedInputs {
+    pub stock: Option<f64>,
+    pub vol: Option<f64>,
+    pub risk_free: Option<f64>,
+    pub div_yield: Option<f64>,
+    pub prediction_stock: Option<f64>,
+    pub prediction_time: Option<f64>,
+}
+impl EmbedInputs {
+    /// Values in the order of the six parameter inputs
+    pub fn values(&self) -> [Option<f64>; 6] {
+        return [self.stock, self.vol, self.risk_free, self.div_yield, self.prediction_stock, self.prediction_time];
+    }
+}
+
+/// The calculated answer, as replied to the embedding page
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "type", rename = "which_option.result")]
+pub struct EmbedResult {
+    pub calculated: bool,
+    /// True if the inputs have changed since the answer was calculated (e.g the calculation failed on invalid inputs)
+    pub stale: bool,
+    pub is_call: bool,
+    pub strike: f64,
+    pub expiry: f64,
+    pub buy_price: f64,
+    pub sell_price: f64,
+    pub roi: f64,
+}
+
+/// Origins requests are accepted from, separated by commas. None to accept any.
+const ALLOWED_ORIGINS: Option<&str> = option_env!("WHICH_OPTION_EMBED_ORIGINS");
+
+/// Whether requests from the given origin are accepted. Opaque origins (e.g sandboxed frames) never are, as there's
+/// no origin to reply to.
+fn origin_allowed(origin: &str) -> bool {
+    if origin.is_empty() || origin == "null" {
+        return false;
+    }
+    return ALLOWED_ORIGINS.is_none_or(|allowed| allowed.split(',').any(|allowed| allowed.trim() == origin));
+}
+
+/// Requests posted to the page's window, with the origin of the page that posted each.
+/// Listens from the first poll for as long as the page is open.
+pub fn requests() -> impl Stream<Item = (String, EmbedRequest)> {
+    let (sender, receiver) = mpsc::unbounded();
+    if let Some(window) = web_sys::window() {
+        let listener = Closure::<dyn FnMut(web_sys::MessageEvent)>::new(move |event: web_sys::MessageEvent| {
+            let origin = event.origin();
+            if !origin_allowed(&origin) {
+                return;
+            }
+            // Messages meant for anything else on the page are ignored
+            if let Some(request) = parse_request(&event.data()) {
+                let _ = sender.unbounded_send((origin, request));
+            }
+        });
+        let _ = window.add_event_listener_with_callback("message", listener.as_ref().unchecked_ref());
+        listener.forget();
+    }
+    return receiver;
+}
+
+fn parse_request(data: &wasm_bindgen::JsValue) -> Option<EmbedRequest> {
+    let json = match data.as_string() {
+        Some(json) => json,
+        None => js_sys::JSON::stringify(data).ok()?.as_string()?,
+    };
+    return serde_json::from_str(&json).ok();
+}
+
+/// Posts the result to the window embedding the page, or the page's own window if it isn't embedded.
+/// Only delivered if that window is at the given origin, the one the request came from.
+pub fn post_result(result: &EmbedResult, origin: &str) -> Result<(), String> {
+    let window = web_sys::window().ok_or("No browser window")?;
+    let target = window.parent().ok().flatten().unwrap_or(window);
+    let json = serde_json::to_string(result).map_err(|e| e.to_string())?;
+    let message = js_sys::JSON::parse(&json).map_err(|e| format!("{:?}", e))?;
+    return target.post_message(&message, origin).map_err(|e| format!("{:?}", e));
+}
@@ -0,0 +1,60 @@
+//! Employee stock options, granted as part of compensation, valued by the Hull-White (2004) lattice. Unlike a traded
+//! call they can't be exercised until they vest, are forfeited if the employee leaves before then, and tend to be
+//! exercised early (once the stock reaches a multiple of the strike, or the employee leaves after vesting).
+
+use crate::blackscholes::Environment;
+
+/// Time steps of the binomial lattice
+const LATTICE_STEPS: usize = 500;
+
+/// Terms of an employee stock option grant, which is a call on the company's stock
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmployeeOption {
+    pub strike: f64,
+    /// Years until the option expires
+    pub maturity: f64,
+    /// Years until the option vests and can be exercised
+    pub vesting: f64,
+    /// Multiple of the strike the stock price is expected to reach before the employee exercises (e.g 2 for double)
+    pub exercise_multiple: f64,
+    /// Rate per year employees leave at. Before vesting the option is forfeited, after it an option in the money is
+    /// exercised and one out of the money lapses.
+    pub exit_rate: f64,
+}
+impl EmployeeOption {
+    /// Value of the option at grant in the given environment. 0 if it expires before it vests.
+    pub fn value(&self, env: &Environment) -> f64 {
+        let intrinsic = |stock: f64| (stock - self.strike).max(0.0);
+        // Expires before it vests, so it can never be exercised
+        if self.maturity < self.vesting {
+            return 0.0;
+        }
+        if self.maturity <= 0.0 {
+            return intrinsic(env.stock);
+        }
+        let dt = self.maturity / LATTICE_STEPS as f64;
+        let up = (env.vol * dt.sqrt()).exp().max(1.0 + f64::EPSILON);
+        let down = 1.0 / up;
+        let p = (((env.risk_free - env.div_yield) * dt).exp() - down) / (up - down);
+        let discount = (-env.risk_free * dt).exp();
+        let exits = 1.0 - (-self.exit_rate * dt).exp();
+        let stock_at = |step: usize, ups: usize| env.stock * up.powi(ups as i32) * down.powi((step - ups) as i32);
+
+        let mut values: Vec<f64> = (0..=LATTICE_STEPS).map(|ups| intrinsic(stock_at(LATTICE_STEPS, ups))).collect();
+        for step in (0..LATTICE_STEPS).rev() {
+            let vested = step as f64 * dt >= self.vesting;
+            for ups in 0..=step {
+                let stock = stock_at(step, ups);
+                let held = discount * (p * values[ups + 1] + (1.0 - p) * values[ups]);
+                values[ups] = if !vested {
+                    (1.0 - exits) * held
+                } else if stock >= self.exercise_multiple * self.strike {
+                    intrinsic(stock)
+                } else {
+                    (1.0 - exits) * held + exits * intrinsic(stock)
+                };
+            }
+        }
+        return values[0];
+    }
+}
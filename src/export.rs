@@ -0,0 +1,108 @@
+//! Moving files in and out of the application.
+//! Desktop builds prompt with file dialogs, web builds trigger a browser download when saving.
+
+/// Kind of file being saved. Determines the file filter in the save dialog and the
+/// MIME type of the browser download.
+#[derive(Debug, Clone, Copy)]
+pub enum FileKind {
+    Csv,
+    Html,
+    Json,
+    /// Only produced on desktop builds
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    Png,
+    Svg,
+    Xlsx,
+}
+impl FileKind {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Html => "html",
+            Self::Json => "json",
+            Self::Png => "png",
+            Self::Svg => "svg",
+            Self::Xlsx => "xlsx",
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Html => "text/html",
+            Self::Json => "application/json",
+            Self::Png => "image/png",
+            Self::Svg => "image/svg+xml",
+            Self::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+}
+
+/// Saves the given bytes to a user chosen location.
+///
+/// Returns Ok(false) if the user cancelled the save, Ok(true) if the file was written.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn save_file(default_name: String, kind: FileKind, contents: Vec<u8>) -> Result<bool, String> {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .set_file_name(format!("{}.{}", default_name, kind.extension()))
+        .add_filter(kind.extension(), &[kind.extension()])
+        .save_file()
+        .await
+    else {
+        return Ok(false);
+    };
+    handle.write(&contents).await.map_err(|e| e.to_string())?;
+    return Ok(true);
+}
+
+/// Saves the given bytes by triggering a browser download.
+///
+/// Always returns Ok(true) on success as the browser handles any cancellation.
+#[cfg(target_arch = "wasm32")]
+pub async fn save_file(default_name: String, kind: FileKind, contents: Vec<u8>) -> Result<bool, String> {
+    use wasm_bindgen::JsCast;
+
+    let js_err = |e: wasm_bindgen::JsValue| format!("{:?}", e);
+    let window = web_sys::window().ok_or("No browser window")?;
+    let document = window.document().ok_or("No browser document")?;
+
+    let bytes = js_sys::Uint8Array::from(contents.as_slice());
+    let parts = js_sys::Array::of1(&bytes);
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(kind.mime_type());
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options).map_err(js_err)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(js_err)?;
+
+    let anchor: web_sys::HtmlAnchorElement = document.create_element("a")
+        .map_err(js_err)?
+        .dyn_into()
+        .map_err(|_| "Failed to create download link")?;
+    anchor.set_href(&url);
+    anchor.set_download(&format!("{}.{}", default_name, kind.extension()));
+    anchor.click();
+    web_sys::Url::revoke_object_url(&url).map_err(js_err)?;
+    return Ok(true);
+}
+
+/// Reads the contents of a user chosen file.
+///
+/// Returns Ok(None) if the user cancelled the selection.
+pub async fn open_file(kind: FileKind) -> Result<Option<Vec<u8>>, String> {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .add_filter(kind.extension(), &[kind.extension()])
+        .pick_file()
+        .await
+    else {
+        return Ok(None);
+    };
+    return Ok(Some(handle.read().await));
+}
+
+/// Escapes a single CSV field, quoting it if it contains separators or quotes
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+    return field.to_string();
+}
@@ -0,0 +1,44 @@
+//! C ABI over the pricing core so the exact same implementation can be called from Python, Excel, etc.
+//! Only compiled with the `ffi` feature. The matching declarations are in `include/which_option.h`.
+//!
+//! Structs are passed by value and share the layout of their Rust counterparts (four/two doubles).
+
+use crate::blackscholes::{BlackScholes, BlackScholesROI, Call, Contract, Environment, Movement, Put};
+
+/// Black-Scholes price of a call (is_call true) or put option
+#[unsafe(no_mangle)]
+pub extern "C" fn which_option_bsm_price(is_call: bool, env: Environment, contract: Contract) -> f64 {
+    if is_call {
+        return Call::bsm_price(&env, &contract);
+    }
+    return Put::bsm_price(&env, &contract);
+}
+
+/// ROI from purchasing the option in start_env then selling at the end of the movement in end_env
+#[unsafe(no_mangle)]
+pub extern "C" fn which_option_roi(
+    is_call: bool,
+    start_env: Environment,
+    end_env: Environment,
+    contract: Contract,
+    movement: Movement,
+) -> f64 {
+    if is_call {
+        return Call::roi(&start_env, &end_env, &contract, &movement);
+    }
+    return Put::roi(&start_env, &end_env, &contract, &movement);
+}
+
+/// Contract that maximises ROI for the movement
+#[unsafe(no_mangle)]
+pub extern "C" fn which_option_find_best_contract(
+    is_call: bool,
+    start_env: Environment,
+    end_env: Environment,
+    movement: Movement,
+) -> Contract {
+    if is_call {
+        return Call::find_best_contract(&start_env, &end_env, &movement);
+    }
+    return Put::find_best_contract(&start_env, &end_env, &movement);
+}
@@ -0,0 +1,39 @@
+//! Named sets of open sliders and charts, saved separately from scenario data so a preferred
+//! analysis workspace can be restored for any scenario.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Adjustables, PayoffYAxis};
+use crate::storage::Storage;
+
+/// Storage key the layouts are saved under
+const LAYOUTS_KEY: &str = "layouts";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Layout {
+    pub name: String,
+    /// Variables of the open sliders in order
+    pub sliders: Vec<Adjustables>,
+    /// Axes of the open charts in order
+    pub charts: Vec<(PayoffYAxis, Adjustables)>,
+}
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Layouts previously saved
+pub fn load() -> Vec<Layout> {
+    crate::storage::platform_storage().load(LAYOUTS_KEY)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Saves the layouts, replacing those previously saved
+pub fn store(layouts: &[Layout]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(layouts).map_err(|e| e.to_string())?;
+    return crate::storage::platform_storage().store(LAYOUTS_KEY, &json);
+}
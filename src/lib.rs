@@ -0,0 +1,14 @@
+//! Pricing core of the calculator, usable without the GUI.
+//!
+//! With the `ffi` feature, the core is also exposed to other languages through a C ABI. See the ffi module.
+
+pub mod american;
+pub mod blackscholes;
+pub mod eso;
+pub mod montecarlo;
+pub mod sabr;
+pub mod variance_swap;
+pub mod warrant;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
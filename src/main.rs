@@ -1,29 +1,185 @@
-use std::ops::RangeInclusive;
+use std::ops::{Range, RangeInclusive};
+use std::sync::Arc;
 
-mod blackscholes;
-use blackscholes::{
-    Environment, Contract, Movement,
-    BlackScholesRounded, BlackScholesROI, BlackScholesROIRounded,
-    Call, Put,
-};
+use which_option::american;
+use which_option::blackscholes;
+use which_option::eso::EmployeeOption;
+use which_option::warrant::Warrant;
+use blackscholes::{Environment, Contract, Movement, Greeks, OptionType, TailRisk, VolEvent};
+use which_option::montecarlo::{self, LsmEstimate, PnlStats, Rebalance};
+use which_option::variance_swap;
+
+mod analytics;
+use analytics::{PriceHistory, VolEstimates};
+
+mod calendar;
+use calendar::DayCount;
+
+mod diagnostics;
+use diagnostics::Diagnostics;
+
+mod export;
+use export::{FileKind, csv_field};
+
+mod layouts;
+use layouts::Layout;
+
+mod presets;
+use presets::Preset;
+
+mod rates;
+use rates::YieldCurve;
+
+mod report;
+use report::Report;
+
+mod scenario;
+use scenario::{Calculation, Scenario};
+
+mod settings;
+use settings::{ChartColumns, ChartPalette, ChartResolution, NominalScale, Precision, PremiumQuote, RangeSpan, Settings, UiScale};
+
+mod storage;
+use storage::Storage;
+
+mod ticker_defaults;
+use ticker_defaults::TickerDefaults;
+
+mod underlying;
+use underlying::Underlying;
+
+mod units;
+use units::{TimeUnit, VolUnit};
+
+#[cfg(target_arch = "wasm32")]
+mod share;
+
+#[cfg(target_arch = "wasm32")]
+mod embed;
+
+/// Storage key the session is autosaved under
+const AUTOSAVE_KEY: &str = "autosave";
+
+/// Maximum number of undoable steps remembered
+const UNDO_LIMIT: usize = 100;
+
+/// Time without input changes after which live mode recalculates
+const LIVE_DEBOUNCE: Duration = Duration::from_millis(400);
+
+/// Width to height ratio of each payoff chart in the chart grid
+const CHART_ASPECT_RATIO: f32 = 1.6;
+
+/// Rows of charts beyond either edge of the chart panel that are still drawn, so they're ready as they scroll in
+const CHART_OVERSCAN_ROWS: usize = 1;
+
+/// Length of the time decay animation from the prediction start to expiry
+const DECAY_ANIMATION_LENGTH: Duration = Duration::from_secs(6);
+/// Time between frames of the time decay animation
+const DECAY_FRAME: Duration = Duration::from_millis(50);
+
+/// How long the option to undo deleting a slider or chart is offered for
+const UNDO_DELETE_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Numbers of stock price paths the simulation chart can be drawn from
+const SIMULATION_PATH_CHOICES: [usize; 3] = [100, 1000, 10000];
+/// Time steps each simulated path is split into
+const SIMULATION_STEPS: usize = 60;
+/// Seed of the simulated paths. Fixed so the bands only move when the inputs do.
+const SIMULATION_SEED: u64 = 2024;
+
+/// Number of stock price paths the delta hedge is simulated over
+const HEDGE_PATHS: usize = 1000;
+
+/// Numbers of stock price paths the Longstaff-Schwartz valuation can be simulated over
+const LSM_PATH_CHOICES: [usize; 3] = [1000, 10000, 50000];
+/// Degrees of the polynomial the Longstaff-Schwartz valuation can regress the value of holding on with
+const LSM_DEGREE_CHOICES: [usize; 5] = [1, 2, 3, 4, 5];
+
+/// Labels of the employee stock option input boxes. Warrants share the first two.
+const ESO_NAMES: [&str; 5] = [
+    "Strike",
+    "Years to expiry",
+    "Years to vest",
+    "Exercise multiple of strike",
+    "Employee exit rate per year",
+];
+/// Values the employee stock option inputs after the strike start at. Typical terms of a grant.
+const ESO_DEFAULTS: [f64; 4] = [10.0, 3.0, 2.0, 0.05];
+/// Labels of the warrant input boxes after the strike and expiry
+const WARRANT_NAMES: [&str; 2] = ["Shares outstanding", "Warrants issued"];
+
+/// Kinds of option a company writes on its own stock, valued in their own dialog
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum CompanyOption {
+    #[default]
+    Employee,
+    Warrant,
+}
+impl std::fmt::Display for CompanyOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Employee => "Employee stock option",
+            Self::Warrant => "Warrant",
+        })
+    }
+}
+impl CompanyOption {
+    const COUNT: usize = 2;
+
+    fn everything() -> [Self; Self::COUNT] {
+        [Self::Employee, Self::Warrant]
+    }
+}
+
+/// Confidence levels the value at risk and expected shortfall are shown at
+const RISK_CONFIDENCES: [f64; 2] = [0.95, 0.99];
+
+#[cfg(feature = "online")]
+mod quotes;
+
+#[cfg(all(feature = "broker", target_arch = "wasm32"))]
+compile_error!("The broker feature is only supported on desktop builds");
+#[cfg(feature = "broker")]
+mod chain;
+#[cfg(feature = "broker")]
+use chain::ChainProvider;
+
+#[cfg(all(feature = "serve", target_arch = "wasm32"))]
+compile_error!("The serve feature is only supported on desktop builds");
+#[cfg(feature = "serve")]
+mod serve;
 
 mod custom_widgets;
 use custom_widgets::{
     NumberInput, NumberInputMessage, 
-    CustomSlider, CustomSliderMessage, 
+    DateInput, DateInputMessage,
+    CustomSlider, CustomSliderMessage, SliderConfig,
     DeletableList, DeletableListMessage,
-    PayoffChart, PayoffChartMessage,
+    PayoffChart, PayoffChartMessage, ChartConfig, ChartFunc,
+    HeatmapChart,
+    ExpiryChart,
+    StressGrid,
+    SimulationChart,
+    ConeChart,
+    HistogramChart,
+    modal,
+    SearchPick,
+    splitter,
 };
 
 use iced::Alignment::Center;
-use iced::window::Settings;
-use iced::{Element, Font, Left, Length, Subscription, Task, font};
-use iced::widget::{Column, button, column, container, operation, pick_list, responsive, row, rule, scrollable, text, tooltip};
+use iced::window;
+use iced::time::{Duration, Instant};
+use iced::{Element, Font, Left, Length, Size, Subscription, Task, Theme, font};
+use iced::widget::{Column, button, checkbox, column, container, operation, pick_list, radio, responsive, row, rule, scrollable, text, text_editor, tooltip};
 
 use rust_decimal::prelude::{ToPrimitive};
 
-const FIRA_SANS_REGULAR_PATH: &[u8] = include_bytes!("fonts\\Fira Sans Regular.ttf");
-const FIRA_SANS_BOLD_PATH: &[u8] = include_bytes!("fonts\\Fira Sans Bold.ttf");
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+const FIRA_SANS_REGULAR_PATH: &[u8] = include_bytes!("fonts/Fira Sans Regular.ttf");
+const FIRA_SANS_BOLD_PATH: &[u8] = include_bytes!("fonts/Fira Sans Bold.ttf");
 pub const FIRA_SANS_NAME: &str = "Fira Sans";
 const FIRA_SANS: Font = Font {
     family: font::Family::Name(FIRA_SANS_NAME),
@@ -38,10 +194,40 @@ const FIRA_SANS_BOLD: Font = Font {
     style: font::Style::Normal,
 };
 
-/// Limits the number of decimal points the calculator will output and the amount for inputs
-const MAX_DP: usize = 3;
+/// Text of a section header
+fn header1_text(s: &str) -> iced::widget::Text<'_> {
+    const HEADER1_SIZE: u32 = 20;
+    text(s)
+        .size(HEADER1_SIZE)
+        .font(FIRA_SANS_BOLD)
+}
+
+/// Width of the window (in logical pixels, after the interface size is applied) below which the panels are
+/// stacked and sized for touch screens, e.g on phones
+const COMPACT_WIDTH: f32 = 700.0;
+
+/// Widths the input panel can be resized between, in logical pixels
+const PANEL_WIDTH_RANGE: RangeInclusive<f32> = 250.0..=700.0;
+
+/// Labels of the six parameter input boxes
+const PARAM_NAMES: [&str; 6] = [
+    "Stock price",
+    "Volatility",
+    "Risk free rate",
+    "Dividend yield",
+    "Prediction stock price",
+    "Prediction end duration",
+];
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+/// Amount each parameter input is stepped by with its -/+ buttons and the arrow keys.
+/// The volatility and duration steps follow the unit they're entered in instead.
+const PARAM_STEPS: [f64; 6] = [1.0, 0.01, 0.001, 0.001, 1.0, 0.1];
+
+/// Lowest value each parameter input accepts. Rates and yields can go as low as -100%, since negative policy rates
+/// and the borrow cost of hard-to-borrow stocks (a negative dividend adjustment) are real.
+const PARAM_LOWEST: [f64; 6] = [0.0, 0.0, -1.0, -1.0, 0.0, 0.0];
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 enum Adjustables {
     Strike,
     Expiry,
@@ -70,9 +256,34 @@ impl Adjustables {
         Self::EndTime,
         Self::EndVol]
     }
+
+    /// Lowest value a slider of this variable can take. Prices, times and volatilities can't be negative.
+    /// Rates and yields, which can be (see PARAM_LOWEST), aren't adjusted by sliders.
+    fn lowest(&self) -> f64 {
+        match self {
+            Self::Strike | Self::Expiry | Self::EndPrice | Self::EndTime | Self::EndVol => 0.0,
+        }
+    }
+
+    /// Step a slider of this variable snaps to when first opened. Strikes are listed in $0.50 increments,
+    /// while the other variables move smoothly.
+    fn default_step(&self) -> Option<f64> {
+        match self {
+            Self::Strike => Some(0.5),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+/// Pairs of variables (lower, upper) where lower can never exceed upper. Enforced on the sliders as either moves.
+const CONSTRAINTS: [(Adjustables, Adjustables); 1] = [
+    // The prediction has to end before the contract expires
+    (Adjustables::EndTime, Adjustables::Expiry),
+];
+
+// Variant names are stored in saved settings and scenarios
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 enum PayoffYAxis {
     ROI,
     Nominal
@@ -103,21 +314,178 @@ pub fn main() -> iced::Result {
     #[cfg(not(target_arch = "wasm32"))]
     tracing_subscriber::fmt::init();
 
-    let mut window_setting = Settings::default();
-    window_setting.size = (1000.0, 850.0).into();
-    window_setting.min_size = Some((900.0, 400.0).into());
+    // Run headless as a pricing API instead of opening the GUI
+    #[cfg(feature = "serve")]
+    if let Some(address) = serve::requested_address() {
+        if let Err(e) = serve::run(&address) {
+            eprintln!("Failed to serve on {}: {}", address, e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let window_setting = window::Settings {
+        size: (1000.0, 850.0).into(),
+        min_size: Some((900.0, 400.0).into()),
+        ..Default::default()
+    };
 
-    iced::application(OptionCalculator::default, OptionCalculator::update, OptionCalculator::view)
+    iced::application(OptionCalculator::restore_session, OptionCalculator::update, OptionCalculator::view)
         .title("Which Option")
         .antialiasing(true)
         .font(FIRA_SANS_REGULAR_PATH)
         .font(FIRA_SANS_BOLD_PATH)
         .default_font(FIRA_SANS)
         .subscription(OptionCalculator::subscription)
+        .theme(OptionCalculator::theme)
+        // Clamped in case the settings were edited by hand
+        .scale_factor(|calculator| calculator.settings.scale.0.clamp(0.5, 4.0))
         .window(window_setting)
         .run()
 }
 
+/// (true if using Call contract, contract, purchase price, selling price, ROI) of a solved contract alongside
+/// diagnostics of the calculation
+type Solution = ((bool, Contract, f64, f64, f64), Diagnostics);
+
+/// Finds the contract maximising ROI for the predicted movement, out of both calls and puts.
+///
+/// Returns (true if using Call contract, contract, purchase price, selling price, ROI) of the answer
+/// alongside diagnostics of the calculation.
+#[cfg(feature = "serve")]
+fn solve(start_env: &Environment, end_env: &Environment, movement: &Movement) -> Solution {
+    // The environment is given directly, so it's priced as a stock
+    let [call, put] = solve_candidates(Underlying::Stock, start_env, end_env, movement);
+    if put.0.4 > call.0.4 {
+        return put;
+    }
+    return call;
+}
+
+/// Finds the best call and the best put for the predicted movement, in that order. See solve_contract.
+fn solve_candidates(underlying: Underlying, start_env: &Environment, end_env: &Environment, movement: &Movement) -> [Solution; 2] {
+    return [
+        solve_contract(true, underlying, start_env, end_env, movement),
+        solve_contract(false, underlying, start_env, end_env, movement),
+    ];
+}
+
+/// Finds the call (or put) contract maximising ROI for the predicted movement, on the underlying the environments
+/// were made for.
+///
+/// Returns (true if using Call contract, contract, purchase price, selling price, ROI) of the answer
+/// alongside diagnostics of the calculation.
+fn solve_contract(is_call: bool, underlying: Underlying, start_env: &Environment, end_env: &Environment, movement: &Movement) -> Solution {
+    let started_at = chrono::Local::now();
+    let pricer = OptionType::from_is_call(is_call);
+    let (contract, stats) = pricer.find_best_contract_with_stats(start_env, end_env, movement);
+    let (buy_price, sell_price) = pricer.buy_sell_prices_practical(start_env, end_env, &contract, movement);
+    let (entry, exit) = pricer.buy_sell_prices(start_env, end_env, &contract, movement);
+    let roi = sell_price / buy_price;
+
+    let mut warnings = Vec::new();
+    if exit <= blackscholes::ROI_FLOOR_THRESHOLD {
+        warnings.push(String::from("Exit price floored to 0"));
+    }
+    if entry < 0.01 {
+        warnings.push(String::from("Entry price rounded up to the minimum of 0.01"));
+    }
+    if !stats.converged {
+        warnings.push(format!("Optimiser did not converge (ROI gradient {:.2e})", stats.gradient));
+    }
+    if contract.strike <= 0.0 {
+        warnings.push(String::from("Optimal strike is not positive"));
+    }
+    let finished_at = chrono::Local::now();
+    let diagnostics = Diagnostics {
+        calculated_at: finished_at,
+        duration_ms: (finished_at - started_at).num_milliseconds(),
+        model: underlying.model_name(),
+        iterations: stats.iterations,
+        converged: stats.converged,
+        warnings,
+    };
+
+    return ((
+        is_call,
+        contract,
+        buy_price.to_f64().unwrap_or(0.0),
+        sell_price.to_f64().unwrap_or(0.0),
+        roi.to_f64().unwrap_or(0.0),
+    ), diagnostics);
+}
+
+/// Environment the contract is sold in at the end of the movement, once any volatility event has passed.
+/// See VolEvent::exit_env.
+fn exit_env(end_env: &Environment, movement: &Movement, event: Option<VolEvent>) -> Environment {
+    return event.map_or_else(|| end_env.clone(), |event| event.exit_env(end_env, movement));
+}
+
+/// Single variable function of a scenario that encapsulates a (practical) blackscholes calculation with 1 variable free.
+/// Only the variable being swept is replaced on each evaluation, leaving the rest of the scenario borrowed.
+///
+/// Practical meaning that prices within calculations are rounded to 2 d.p in the appropriate direction for buying/selling.
+struct Parameterisation {
+    pricer: OptionType,
+    out: PayoffYAxis,
+    var: Adjustables,
+    start_env: Environment,
+    end_env: Environment,
+    contract: Contract,
+    movement: Movement,
+    /// Volatility event applied to the end environment if it has passed by the movement end
+    event: Option<VolEvent>,
+}
+impl Parameterisation {
+    /// Value of the scenario with the free variable set to x
+    fn value(&self, x: f64) -> f64 {
+        let (start_env, end_env, contract, movement) = (&self.start_env, &self.end_env, &self.contract, &self.movement);
+        match self.var {
+            Adjustables::Strike => return self.output(start_env, end_env, &Contract { strike: x, ..*contract }, movement),
+            Adjustables::Expiry => return self.output(start_env, end_env, &Contract { expiry: x, ..*contract }, movement),
+            Adjustables::EndPrice => return self.output(start_env, end_env, contract, &Movement { stock: x, ..*movement }),
+            Adjustables::EndTime => return self.output(start_env, end_env, contract, &Movement { time: x, ..*movement }),
+            Adjustables::EndVol => return self.output(start_env, &Environment { vol: x, ..*end_env }, contract, movement),
+        }
+    }
+
+    /// Single variable function of the scenario to be given to the payoff graphs to be plotted
+    fn into_func(self) -> ChartFunc {
+        return Arc::new(move |x| self.value(x));
+    }
+
+    /// ROI or nominal exit price of the given scenario
+    fn output(&self, start_env: &Environment, end_env: &Environment, contract: &Contract, movement: &Movement) -> f64 {
+        use rust_decimal::prelude::ToPrimitive;
+        let end_env = &exit_env(end_env, movement, self.event);
+        match self.out {
+            PayoffYAxis::ROI => {
+                return self.pricer.roi_practical(start_env, end_env, contract, movement);
+            }
+            PayoffYAxis::Nominal => {
+                let (_, exit) = self.pricer.buy_sell_prices_practical(start_env, end_env, contract, movement);
+                return exit.to_f64().unwrap_or(0.0);
+            }
+        }
+    }
+}
+
+/// Payoff charts, each keyed by the axes it plots
+type ChartList = DeletableList<
+    (PayoffYAxis, Adjustables),
+    PayoffChart,
+    PayoffChartMessage,
+    fn(&mut PayoffChart, PayoffChartMessage),
+    fn(&PayoffChart) -> Element<'_, PayoffChartMessage>>;
+
+/// Sliders, each keyed by the variable it adjusts
+type SliderList = DeletableList<
+    Adjustables,
+    CustomSlider,
+    CustomSliderMessage,
+    fn(&mut CustomSlider, CustomSliderMessage),
+    fn(&CustomSlider) -> Element<'_, CustomSliderMessage>>;
+
 // #[derive(Default)]
 struct OptionCalculator {
     /// Members descriptions (in order):
@@ -127,36 +495,176 @@ struct OptionCalculator {
     ///  - f64: Selling price of the contract
     ///  - f64: ROI of buying then selling the contract
     answers: (bool, Contract, f64, f64, f64),
+    /// Input boxes for the strike and expiry of the answer, so any contract can be evaluated in its place
+    answer_inputs: [NumberInput; 2],
     /// Input boxes for the starting environment
     param: [NumberInput; 6],
+    /// Kind of asset the options are on, deciding which variant of Black-Scholes prices them
+    underlying: Underlying,
+    /// True if a volatility event (e.g earnings) is expected, entered into the event inputs
+    use_event: bool,
+    /// Input boxes for the time until the volatility event and the volatility after it.
+    /// Entered in the same units as the prediction end duration and volatility.
+    event_inputs: [NumberInput; 2],
+    /// Unit the volatility input is entered in
+    vol_unit: VolUnit,
+    /// Unit the prediction end duration input is entered in
+    time_unit: TimeUnit,
+    /// True if the prediction end is entered as a calendar date rather than a year fraction
+    use_dates: bool,
+    /// Input box for the prediction end date
+    horizon_date: DateInput,
+    /// Free text notes on the reasoning behind the prediction, saved with the scenario
+    notes: text_editor::Content,
+    /// Convention used to convert dates into year fractions
+    day_count: DayCount,
+    /// Date the last calculation was valued from. None if the last calculation didn't use dates.
+    valuation_date: Option<NaiveDate>,
+    /// Preferences persisted between sessions
+    settings: Settings,
+    /// Whether the settings dialog is open
+    settings_open: bool,
+    /// Whether the dialog valuing employee stock options and warrants is open
+    company_option_open: bool,
+    /// Kind of option valued in the dialog
+    company_option: CompanyOption,
+    /// Input boxes for the terms of an employee stock option. See ESO_NAMES.
+    eso_inputs: [NumberInput; 5],
+    /// Input boxes for the shares of a warrant issue. See WARRANT_NAMES.
+    warrant_inputs: [NumberInput; 2],
+    /// Value of the employee stock option or warrant entered, then of a traded call on the same terms. None unless
+    /// the inputs and the current environment are validly filled in.
+    company_option_value: Option<(f64, f64)>,
+    /// Size of the window, before the interface size is applied
+    window_size: Size,
+    /// Whether the bottom sheet of chart controls is open in the compact layout
+    chart_sheet_open: bool,
+    /// Every theme that can be chosen
+    themes: Vec<Theme>,
+    theme: Theme,
+    /// Volatility estimates from imported price history. The import dialog is shown while Some.
+    vol_import: Option<VolEstimates>,
+    /// Built-in and user saved presets of the six parameter inputs
+    presets: SearchPick<Preset>,
+    preset_select: Option<Preset>,
+    /// Name to save the current inputs as a preset under
+    preset_name: String,
+    /// Saved current environment inputs of tickers, searchable by ticker
+    ticker_defaults: SearchPick<TickerDefaults>,
+    ticker_defaults_select: Option<TickerDefaults>,
+    /// Ticker to save the current environment inputs under
+    ticker_defaults_name: String,
+    /// Saved sets of open sliders and charts
+    layouts: Vec<Layout>,
+    layout_select: Option<Layout>,
+    /// Name to save the open sliders and charts as a layout under
+    layout_name: String,
+    /// Result of the last risk free rate lookup
+    rate_status: String,
+    /// Number of shares per contract. Saved into settings when valid.
+    multiplier: NumberInput,
+    /// Time of the last edit not yet recalculated in live mode
+    live_pending: Option<Instant>,
+    /// Time decay animation currently playing
+    decay: Option<DecayAnimation>,
+    /// When the latest slider or chart was deleted, while the "Undo delete" toast is shown
+    delete_toast: Option<Instant>,
+    /// Part of the chart panel last scrolled to. None until the panel first overflows.
+    chart_viewport: Option<scrollable::Viewport>,
+    /// Charts that skipped being configured while off-screen, configured once scrolled into view
+    stale_charts: Vec<(PayoffYAxis, Adjustables)>,
+    /// Snapshots of the state before each undoable edit, most recent last
+    undo_history: Vec<Scenario>,
+    /// Snapshots of undone states, most recently undone last
+    redo_history: Vec<Scenario>,
+    /// Kind of the last undoable edit. Repeated edits of a single input or slider are undone together.
+    last_edit: Option<EditGroup>,
+    /// Details of the last calculation. None until Calculate is pressed.
+    diagnostics: Option<Diagnostics>,
+    /// Best contract of the other option type to the answer. Same layout as answers.
+    alternative: Option<(bool, Contract, f64, f64, f64)>,
+    /// Details of the calculation of the alternative
+    alternative_diagnostics: Option<Diagnostics>,
+    /// True once Calculate has successfully been pressed
+    calculated: bool,
+    /// Progress/result of the last file save/load
+    file_status: String,
+    /// Ticker to fetch live market data for
+    #[cfg(feature = "online")]
+    ticker: String,
+    /// Progress/result of the last quote fetch
+    #[cfg(feature = "online")]
+    quote_status: String,
+    /// Every contract within the last option chain pulled
+    #[cfg(feature = "broker")]
+    chain_quotes: Vec<chain::ChainQuote>,
+    /// Contracts of the option chain ordered by ROI under the last calculation
+    #[cfg(feature = "broker")]
+    chain_ranked: Vec<chain::RankedQuote>,
+    /// Progress/result of the last option chain pull
+    #[cfg(feature = "broker")]
+    chain_status: String,
+    /// SABR smile fit to the last option chain pulled
+    #[cfg(feature = "broker")]
+    chain_smile: Option<chain::SmileFit>,
+    /// Whether the chain's exit prices take their volatility off the fitted smile rather than the flat volatility
+    #[cfg(feature = "broker")]
+    use_smile: bool,
     /// Environment variables extracted from user numeric input
     start_env: Environment,
     /// Price movement extracted from user numeric input. Can
     /// later be adjusted by the user with numeric sliders.
     movement: Movement,
+    /// Price movement the answer was calculated for, without slider overrides
+    prediction: Movement,
     /// Environment variables for what happens at the prediction end date.
     /// Can later be adjusted by the user with numeric sliders.
     end_env: Environment,
+    /// Volatility event of the last calculation, which replaces the end environment's volatility once passed
+    vol_event: Option<VolEvent>,
     /// The "optimal" contract calculated given a starting environment and prediction.
     /// Can later be adjusted by user with numeric sliders
     contract: Contract,
     /// Payoff charts to visualise returns against a single variable changing
-    charts: DeletableList<
-        (PayoffYAxis, Adjustables),
-        PayoffChart, 
-        PayoffChartMessage, 
-        fn(&mut PayoffChart, PayoffChartMessage), 
-        fn(&PayoffChart) -> Element<'_, PayoffChartMessage>>,
+    charts: ChartList,
+    /// ROI over every strike and expiry. None unless opened.
+    heatmap: Option<HeatmapChart>,
+    /// Profit/loss of holding the contract to expiry. None unless opened.
+    expiry_chart: Option<ExpiryChart>,
+    /// Cone of stock prices the volatility expects over the prediction time frame. None unless opened.
+    cone_chart: Option<ConeChart>,
+    /// Value and ROI of the contract over shocks to the stock price and volatility. None unless opened.
+    stress_grid: Option<StressGrid>,
+    /// Percentile bands of the contract's value over simulated stock price paths. None unless opened.
+    simulation_chart: Option<SimulationChart>,
+    /// Distribution of the contract's profit/loss at the end of simulated stock price paths. None unless opened.
+    pnl_histogram: Option<HistogramChart>,
+    /// Number of paths the simulation chart and profit/loss histogram are drawn from. One of SIMULATION_PATH_CHOICES.
+    simulation_paths: usize,
+    /// How often the simulated delta hedge is rebalanced. None unless the delta hedge is opened.
+    hedge_rebalance: Option<Rebalance>,
+    /// Profit/loss per share of holding the contract over simulated paths, delta hedged then unhedged.
+    /// None until computed for an opened delta hedge.
+    hedge_stats: Option<(PnlStats, PnlStats)>,
+    /// Number of paths the Longstaff-Schwartz valuation is simulated over. One of LSM_PATH_CHOICES.
+    lsm_paths: usize,
+    /// Degree of the Longstaff-Schwartz regression. One of LSM_DEGREE_CHOICES.
+    lsm_degree: usize,
+    /// Number of the latest Longstaff-Schwartz valuation started, so progress of earlier ones is ignored
+    lsm_run: u64,
+    /// Fraction of the running Longstaff-Schwartz valuation done. None unless running.
+    lsm_progress: Option<f64>,
+    /// Last Longstaff-Schwartz estimate of the answer's American price, with the contract and environment it was for
+    lsm_estimate: Option<(Contract, Environment, LsmEstimate)>,
     /// Sliders to quickly vary variables of the scenario for the payoff calculation
-    sliders: DeletableList<
-        Adjustables,
-        CustomSlider, 
-        CustomSliderMessage, 
-        fn(&mut CustomSlider, CustomSliderMessage), 
-        fn(&CustomSlider) -> Element<'_, CustomSliderMessage>>,
+    sliders: SliderList,
     slider_add_select: Option<Adjustables>,
     chart_y_select: Option<PayoffYAxis>,
     chart_x_select: Option<Adjustables>,
+    /// Searchable pickers of the variable of a new slider, and the axes of a new chart
+    slider_add_pick: SearchPick<Adjustables>,
+    chart_y_pick: SearchPick<PayoffYAxis>,
+    chart_x_pick: SearchPick<Adjustables>,
     ranges: [RangeInclusive<f64>; Adjustables::COUNT],
 }
 
@@ -164,555 +672,3560 @@ impl Default for OptionCalculator {
     fn default() -> Self {
         use core::array;
 
-        Self {
+        let settings = Settings::load();
+        let (vol_unit, time_unit) = (settings.vol_unit, settings.time_unit);
+        let themes = settings::all_themes();
+        let mut calculator = Self {
+            theme: settings.theme(&themes),
+            multiplier: {
+                let mut input = NumberInput::default().set_precision(settings.precision.inputs);
+                input.set_range(1.0..=f64::MAX)
+                    .set_value(settings.multiplier);
+                input
+            },
+            themes,
             sliders: DeletableList::new(CustomSlider::update, CustomSlider::view),
             answers: Default::default(),
-            param: array::from_fn(|_| {
-                let mut input = NumberInput::default().set_precision(MAX_DP);
+            answer_inputs: array::from_fn(|i| {
+                let mut input = NumberInput::default().set_precision(settings.precision.inputs);
+                input.set_range(0.0..=f64::MAX)
+                    .set_step(PARAM_STEPS[[0, 5][i]]);
+                input
+            }),
+            param: array::from_fn(|i| {
+                let mut input = NumberInput::default().set_precision(settings.precision.inputs);
+                input.set_range(PARAM_LOWEST[i]..=f64::MAX);
+                input
+            }),
+            underlying: Underlying::default(),
+            use_event: false,
+            event_inputs: array::from_fn(|_| {
+                let mut input = NumberInput::default().set_precision(settings.precision.inputs);
                 input.set_range(0.0..=f64::MAX);
                 input
             }),
+            company_option_open: false,
+            company_option: CompanyOption::default(),
+            eso_inputs: array::from_fn(|i| {
+                let mut input = NumberInput::default().set_precision(settings.precision.inputs);
+                input.set_range(if i == 3 { 1.0..=f64::MAX } else { 0.0..=f64::MAX });
+                if i > 0 {
+                    input.set_value(ESO_DEFAULTS[i - 1]);
+                }
+                // The exit rate is entered as a decimal
+                if i == 4 {
+                    input.set_suffixes(vec![("%", 0.01)]);
+                }
+                input
+            }),
+            warrant_inputs: array::from_fn(|_| {
+                let mut input = NumberInput::default().set_precision(settings.precision.inputs);
+                input.set_range(0.0..=f64::MAX);
+                input
+            }),
+            company_option_value: None,
+            settings,
+            settings_open: false,
+            window_size: Size::new(1000.0, 850.0),
+            chart_sheet_open: false,
+            vol_unit,
+            time_unit,
+            use_dates: false,
+            horizon_date: Default::default(),
+            notes: text_editor::Content::new(),
+            day_count: Default::default(),
+            valuation_date: None,
+            vol_import: None,
+            presets: SearchPick::new(presets::all()),
+            preset_select: None,
+            preset_name: Default::default(),
+            ticker_defaults: SearchPick::new(ticker_defaults::load()),
+            ticker_defaults_select: None,
+            ticker_defaults_name: Default::default(),
+            layouts: layouts::load(),
+            layout_select: None,
+            layout_name: Default::default(),
+            rate_status: Default::default(),
+            live_pending: None,
+            decay: None,
+            delete_toast: None,
+            chart_viewport: None,
+            stale_charts: Vec::new(),
+            undo_history: Vec::new(),
+            redo_history: Vec::new(),
+            last_edit: None,
+            diagnostics: None,
+            alternative: None,
+            alternative_diagnostics: None,
+            calculated: false,
+            file_status: Default::default(),
+            #[cfg(feature = "online")]
+            ticker: Default::default(),
+            #[cfg(feature = "online")]
+            quote_status: Default::default(),
+            #[cfg(feature = "broker")]
+            chain_quotes: Vec::new(),
+            #[cfg(feature = "broker")]
+            chain_ranked: Vec::new(),
+            #[cfg(feature = "broker")]
+            chain_status: Default::default(),
+            #[cfg(feature = "broker")]
+            chain_smile: None,
+            #[cfg(feature = "broker")]
+            use_smile: false,
             start_env: Default::default(),
             end_env: Default::default(),
+            vol_event: None,
             movement: Default::default(),
+            prediction: Default::default(),
             contract: Default::default(),
-            charts: DeletableList::new(|_, _| {}, PayoffChart::view),
+            charts: DeletableList::new(PayoffChart::update, PayoffChart::view),
+            heatmap: None,
+            expiry_chart: None,
+            cone_chart: None,
+            stress_grid: None,
+            simulation_chart: None,
+            pnl_histogram: None,
+            simulation_paths: SIMULATION_PATH_CHOICES[1],
+            hedge_rebalance: None,
+            hedge_stats: None,
+            lsm_paths: LSM_PATH_CHOICES[1],
+            lsm_degree: 3,
+            lsm_run: 0,
+            lsm_progress: None,
+            lsm_estimate: None,
             slider_add_select: Default::default(),
             chart_y_select: Default::default(),
             chart_x_select: Default::default(),
+            slider_add_pick: SearchPick::new(Adjustables::everything().to_vec()),
+            chart_y_pick: SearchPick::new(PayoffYAxis::everything().to_vec()),
+            chart_x_pick: SearchPick::new(Adjustables::everything().to_vec()),
             ranges: array::from_fn(|_| 0.0..=0.0),
-        }
+        };
+        calculator.charts.set_overlay(PayoffChart::overlay);
+        calculator.configure_param_inputs();
+        return calculator;
     }
 }
 
+/// Progress of the time decay animation, which sweeps the prediction end time from 0 to expiry
+struct DecayAnimation {
+    started: Instant,
+    /// Prediction end time to restore once the animation stops
+    original_time: f64,
+    /// Sell price and ROI of the answer at the animated end time
+    sell: f64,
+    roi: f64,
+}
+
+/// Groups edits that are undone as a single step
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EditGroup {
+    /// Typing into a parameter input box
+    Input(usize),
+    /// Typing into the prediction end date box
+    HorizonDate,
+    /// Typing into a volatility event input box
+    EventInput(usize),
+    /// Typing into the answer's strike or expiry box
+    AnswerInput(usize),
+    /// Typing into the notes
+    Notes,
+    /// Dragging or typing into a slider
+    Slider(usize),
+    /// Any other edit. Never grouped with the previous edit.
+    Step,
+}
+
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone)]
 enum Message {
     Charts(DeletableListMessage<PayoffChartMessage>),
     Calculate,
     NumberInputMessage(usize, NumberInputMessage),
+    EventToggle(bool),
+    UnderlyingSelect(Underlying),
+    EventInputMessage(usize, NumberInputMessage),
+    AnswerInputMessage(usize, NumberInputMessage),
+    NotesEdit(text_editor::Action),
+    VolUnitSelect(VolUnit),
+    TimeUnitSelect(TimeUnit),
+    UseDates(bool),
+    HorizonDate(DateInputMessage),
+    DayCountSelect(DayCount),
+    #[cfg(feature = "online")]
+    TickerEdit(String),
+    #[cfg(feature = "online")]
+    FetchQuote,
+    #[cfg(feature = "online")]
+    QuoteFetched(Result<quotes::Quote, String>),
+    FillRiskFree,
+    #[cfg(feature = "online")]
+    YieldsFetched(Result<YieldCurve, String>),
+    #[cfg(feature = "broker")]
+    FetchChain,
+    #[cfg(feature = "broker")]
+    ChainFetched(Result<Vec<chain::ChainQuote>, String>),
+    #[cfg(feature = "broker")]
+    UseSmile(bool),
     Sliders(DeletableListMessage<CustomSliderMessage>),
     SliderSelect(Adjustables),
     SliderAdd,
     ChartXSelect(Adjustables),
     ChartYSelect(PayoffYAxis),
     ChartAdd,
+    HeatmapToggle(bool),
+    ExpiryChartToggle(bool),
+    ConeChartToggle(bool),
+    StressGridToggle(bool),
+    SimulationToggle(bool),
+    PnlHistogramToggle(bool),
+    SimulationPathsSelect(usize),
+    HedgeToggle(bool),
+    HedgeRebalanceSelect(Rebalance),
+    LsmPathsSelect(usize),
+    LsmDegreeSelect(usize),
+    LsmRun,
+    LsmProgress(u64, f64),
+    LsmDone(u64, Box<(Contract, Environment, LsmEstimate)>),
     TabPressed,
+    /// Checks whether the "Undo delete" toast has timed out
+    DeleteToastTick,
+    DeleteToastDismiss,
+    /// Arrow key pressed to step the focused input by the given number of steps
+    StepPressed(f64),
+    EscapePressed,
+    ChartsScrolled(scrollable::Viewport),
+    /// Points of a chart sampled in the background, with the generation of the chart they were sampled for
+    ChartSampled((PayoffYAxis, Adjustables), u64, Vec<(f64, f64)>),
+    MultiplierInput(NumberInputMessage),
+    NominalScaleSelect(NominalScale),
+    PremiumQuoteSelect(PremiumQuote),
+    ChartColumnsSelect(ChartColumns),
+    ChartResolutionSelect(ChartResolution),
+    ChartPaletteSelect(ChartPalette),
+    LiveToggle(bool),
+    LiveTick,
+    /// Starts or stops the time decay animation
+    DecayPlay,
+    DecayTick,
+    LiveSolved(Environment, Movement, Option<VolEvent>, Box<[Solution; 2]>),
+    /// Chooses whether the best call (true) or best put drives the sliders and charts
+    ChooseCandidate(bool),
+    Undo,
+    Redo,
+    Export,
+    ExportSpreadsheet,
+    /// Saves an HTML report of the inputs, answer, risk tables and charts
+    ExportReport,
+    Exported(Result<bool, String>),
+    SaveScenario,
+    LoadScenario,
+    ResetSession,
+    /// Opens (true) or closes the settings dialog
+    SettingsOpen(bool),
+    CompanyOptionOpen(bool),
+    CompanyOptionSelect(CompanyOption),
+    EsoInputMessage(usize, NumberInputMessage),
+    WarrantInputMessage(usize, NumberInputMessage),
+    WindowResized(Size),
+    /// Opens (true) or closes the bottom sheet of chart controls in the compact layout
+    ChartSheetToggle(bool),
+    /// The splitter between the input panel and the charts was dragged by the given distance
+    PanelResize(f32),
+    /// Dragging the splitter ended
+    PanelResized,
+    ThemeSelect(Theme),
+    ScaleSelect(UiScale),
+    /// Units a new session starts with were changed
+    DefaultVolUnitSelect(VolUnit),
+    DefaultTimeUnitSelect(TimeUnit),
+    RangeSpanSelect(RangeSpan),
+    /// Decimal places numbers are shown to were changed
+    PrecisionSelect(Precision),
+    PresetSelect(Preset),
+    PresetNameEdit(String),
+    PresetSave,
+    PresetDelete,
+    TickerDefaultsSelect(TickerDefaults),
+    TickerDefaultsNameEdit(String),
+    TickerDefaultsSave,
+    TickerDefaultsDelete,
+    LayoutSelect(Layout),
+    LayoutNameEdit(String),
+    LayoutSave,
+    LayoutDelete,
+    ImportPrices,
+    PricesLoaded(Result<Option<Vec<u8>>, String>),
+    UseVolEstimate(f64),
+    CloseVolImport,
+    #[cfg(target_arch = "wasm32")]
+    ShareLink,
+    /// Request posted by the page embedding the web build, with the origin of the page
+    #[cfg(target_arch = "wasm32")]
+    Embed(String, embed::EmbedRequest),
+    ScenarioLoaded(Result<Option<Vec<u8>>, String>),
 }
 
 impl OptionCalculator {
-    /// Checks if all user parameter inputs are present and >=0
-    fn extract_env_and_pred(&self) -> Option<(Environment, Movement)> {
-        for input in self.param.iter() {
-            if input.get_value().is_nan() || input.value_outside_range() {
-                return None;
+    /// Creates the calculator with the autosaved session from last time (if any)
+    fn restore_session() -> (Self, Task<Message>) {
+        let mut calculator = Self::default();
+        let autosave = storage::platform_storage().load(AUTOSAVE_KEY)
+            .and_then(|json| Scenario::from_json(json.as_bytes()).ok());
+        if let Some(scenario) = autosave {
+            calculator.apply_scenario(scenario);
+        }
+        let mut tasks = Vec::new();
+        // Links prefilling inputs, then shared links, take priority over the autosave
+        #[cfg(target_arch = "wasm32")]
+        if let Some(prefill) = share::read_url_query().and_then(|q| share::QueryPrefill::from_query(&q)) {
+            for (i, value) in prefill.inputs.into_iter().enumerate() {
+                if let Some(value) = value {
+                    calculator.set_param_value(i, value);
+                }
+            }
+            if prefill.inputs[5].is_some() {
+                calculator.use_dates = false;
+            }
+            if prefill.calculate {
+                tasks.push(Task::done(Message::Calculate));
             }
         }
-        return Some(
-            (Environment { 
-                stock: self.param[0].get_value(), 
-                risk_free: self.param[2].get_value(), 
-                vol: self.param[1].get_value(), 
-                div_yield: self.param[3].get_value() 
-            },
-            Movement {
-                stock: self.param[4].get_value(),
-                time: self.param[5].get_value()
-            })
-        );
+        #[cfg(target_arch = "wasm32")]
+        if let Some(shared) = share::read_url_fragment().and_then(|f| share::SharedSetup::from_fragment(&f)) {
+            calculator.apply_shared(shared);
+        }
+        // Restoring isn't an edit the user can undo
+        calculator.undo_history.clear();
+        calculator.last_edit = None;
+        // The layout follows the size of the window, which is only known once it's open
+        tasks.push(window::latest().and_then(window::size).map(Message::WindowResized));
+        return (calculator, Task::batch(tasks));
     }
 
-    fn answer_text_block(&self) -> [String; 6] {
-        let mut out: [String; 6] = Default::default();
-        if self.answers.0 == true {
-            out[0] = String::from("Utilising Calls");
-        } else {
-            out[0] = String::from("Utilising Puts");
+    /// The answer as replied to the page embedding the web build
+    #[cfg(target_arch = "wasm32")]
+    fn embed_result(&self) -> embed::EmbedResult {
+        if !self.calculated {
+            return embed::EmbedResult::default();
         }
-        out[1] = format!("Strike: {:.3}", self.answers.1.strike);
-        out[2] = format!("Expiry: {:.3}", self.answers.1.expiry);
-        out[3] = format!("Buy Price: {:.2}", self.answers.2);
-        out[4] = format!("Sell Price: {:.2}", self.answers.3);
-        out[5] = format!("ROI: {:.3}", self.answers.4);
-        return out;
+        let (is_call, contract, buy_price, sell_price, roi) = self.answers.clone();
+        return embed::EmbedResult {
+            calculated: true,
+            stale: self.is_stale(),
+            is_call,
+            strike: contract.strike,
+            expiry: contract.expiry,
+            buy_price,
+            sell_price,
+            roi,
+        };
     }
 
-    /// Creates a "reasonable" range of values the given variable should be able to take up
-    fn create_default_range(&self, var: Adjustables) -> RangeInclusive<f64> {
-        match var {
-            Adjustables::Strike => 0.0..=2.0*self.contract.strike,
-            Adjustables::Expiry => self.movement.time..=2.0*self.movement.time,
-            Adjustables::EndPrice => 0.0..=2.0*self.movement.stock,
-            Adjustables::EndTime => 0.0..=self.contract.expiry,
-            Adjustables::EndVol => 0.0..=2.0*self.end_env.vol,
+    /// Captures the inputs, slider overrides and charts into a form that can be encoded into a link
+    #[cfg(target_arch = "wasm32")]
+    fn to_shared(&self) -> share::SharedSetup {
+        share::SharedSetup {
+            inputs: self.param.each_ref().map(|input| input.get_text().to_string()),
+            units: (self.vol_unit, self.time_unit),
+            horizon: self.use_dates.then(|| (self.horizon_date.get_text().to_string(), self.day_count)),
+            sliders: self.sliders.data.iter()
+                .map(|(adj, slider)| (*adj, slider.get_value(), slider.get_slider_range()))
+                .collect(),
+            charts: self.charts.data.iter().map(|(axes, _)| *axes).collect(),
         }
     }
 
-    /// Creates the widest range of values that is valid (or makes "sense") for the given variable
-    fn create_valid_range(&self, var: Adjustables) -> RangeInclusive<f64> {
-        match var {
-            Adjustables::Strike => 0.0..=f64::MAX,
-            Adjustables::Expiry => self.movement.time..=f64::MAX,
-            Adjustables::EndPrice => 0.0..=f64::MAX,
-            Adjustables::EndTime => 0.0..=self.contract.expiry,
-            Adjustables::EndVol => 0.0..=f64::MAX,
+    /// Reproduces a shared setup by recalculating from its inputs then reapplying its slider overrides
+    #[cfg(target_arch = "wasm32")]
+    fn apply_shared(&mut self, shared: share::SharedSetup) {
+        // Units first, so the inputs accept the suffixes of their units
+        (self.vol_unit, self.time_unit) = shared.units;
+        self.configure_param_inputs();
+        for (input, text) in self.param.iter_mut().zip(shared.inputs) {
+            input.update(NumberInputMessage::Edit(text));
+        }
+        self.use_dates = shared.horizon.is_some();
+        if let Some((date, day_count)) = shared.horizon {
+            self.horizon_date.update(DateInputMessage::Edit(date));
+            self.day_count = day_count;
+        }
+        let _ = self.update(Message::Calculate);
+        if !self.calculated {
+            return;
         }
-    }
 
-    /// Update appropriate data given the parameter and a value
-    fn set_adjustable(&mut self, var: Adjustables, val: f64) {
-        match var {
-            Adjustables::Expiry => {self.contract.expiry = val;}
-            Adjustables::EndTime => {self.movement.time = val;}
-            Adjustables::EndPrice => {self.movement.stock = val;}
-            Adjustables::Strike => {self.contract.strike = val;}
-            Adjustables::EndVol => {self.end_env.vol = val;}
+        self.sliders.data.clear();
+        self.charts.data.clear();
+        for (adj, value, range) in shared.sliders {
+            self.set_adjustable(adj, value);
+            self.ranges[adj as usize] = range;
+            let slider = Self::create_slider(adj, self.settings.precision.inputs);
+            self.sliders.unique_push(adj, slider);
+        }
+        for i in 0..self.sliders.data.len() {
+            self.configure_slider(i);
+        }
+        for (y_axis, x_axis) in shared.charts {
+            let chart = self.create_chart(y_axis, x_axis);
+            self.charts.unique_push((y_axis, x_axis), chart);
+        }
+        for i in 0..self.charts.data.len() {
+            self.configure_chart(i);
         }
     }
 
-    /// Retrieves appropriate data given the parameter and a value
-    fn get_adjustable(&self, var: Adjustables) -> f64 {
-        match var {
-            Adjustables::Strike => self.contract.strike,
-            Adjustables::Expiry => self.contract.expiry,
-            Adjustables::EndPrice => self.movement.stock,
-            Adjustables::EndTime => self.movement.time,
-            Adjustables::EndVol => self.end_env.vol,
+    /// Checks if all user parameter inputs are present and within their ranges. See PARAM_LOWEST.
+    fn extract_env_and_pred(&self) -> Option<(Environment, Movement)> {
+        // The prediction end duration box is ignored when the end is given as a date
+        let used_inputs = if self.use_dates { &self.param[..5] } else { &self.param[..] };
+        if used_inputs[4..].iter().any(|input| input.get_value().is_nan() || input.value_outside_range()) {
+            return None;
         }
+        let time = self.horizon_years()?;
+        return Some(
+            (self.extract_env()?,
+            Movement {
+                stock: self.param_value(4),
+                time
+            })
+        );
     }
 
-    fn create_chart(&self, y_axis: PayoffYAxis, x_axis: Adjustables) -> PayoffChart {
-        let mut chart: PayoffChart;
-        if y_axis == PayoffYAxis::Nominal {
-            chart = PayoffChart::new_nominal_chart(format!("{} for different {}", y_axis, x_axis), format!("{}", x_axis));
-            chart.set_benchmark_height(self.answers.2);
-            chart.set_yrange(0.0..=self.answers.3*1.1);
-        } else {
-            chart = PayoffChart::new_roi_chart(format!("{} for different {}", y_axis, x_axis), format!("{}", x_axis));
-            chart.set_yrange(0.0..=self.answers.4*1.1);
+    /// Current environment from the first four parameter inputs, if they're present and within their ranges
+    fn extract_env(&self) -> Option<Environment> {
+        for (i, input) in self.param[..4].iter().enumerate() {
+            // The yield input is ignored if the underlying has no use for it
+            if i == 3 && self.underlying.yield_name().is_none() {
+                continue;
+            }
+            if input.get_value().is_nan() || input.value_outside_range() {
+                return None;
+            }
         }
-        chart.set_xrange(self.ranges[x_axis as usize].clone());
-        return chart;
+        return Some(self.underlying.environment(self.param_value(0), self.param_value(1), self.param_value(2), self.param_value(3)));
     }
 
-    /// Configures a variable slider within the sliderlist at a given index
-    fn configure_slider(&mut self, i: usize) {
-        let adj;
-        // Check validity of i
-        if let Some(&(_adj, _)) = self.sliders.data.get(i) {
-            adj = _adj
-        } else {
+    /// Values the employee stock option or warrant entered in the current environment
+    fn value_company_option(&mut self) {
+        self.company_option_value = None;
+        let inputs: Vec<&NumberInput> = match self.company_option {
+            CompanyOption::Employee => self.eso_inputs.iter().collect(),
+            CompanyOption::Warrant => self.eso_inputs[..2].iter().chain(&self.warrant_inputs).collect(),
+        };
+        if inputs.iter().any(|input| input.get_value().is_nan() || input.value_outside_range()) {
             return;
         }
-        let val = self.get_adjustable(adj);
-        let range = self.ranges[adj as usize].clone();
-        // We know i is valid at this point
-        let slider = &mut self.sliders.data[i].1;
-        slider.set_allowed_range(range.clone())
-            .set_slider_range(range);
-        slider.set_value(val);
+        let Some(env) = self.extract_env() else {
+            return;
+        };
+        let [strike, maturity, vesting, exercise_multiple, exit_rate] = self.eso_inputs.each_ref().map(NumberInput::get_value);
+        let value = match self.company_option {
+            CompanyOption::Employee => EmployeeOption { strike, maturity, vesting, exercise_multiple, exit_rate }.value(&env),
+            CompanyOption::Warrant => {
+                let [shares, warrants] = self.warrant_inputs.each_ref().map(NumberInput::get_value);
+                Warrant { strike, maturity, shares, warrants }.value(&env)
+            }
+        };
+        let traded = OptionType::Call.bsm_price(&env, &Contract { strike, expiry: maturity });
+        self.company_option_value = Some((value, traded));
     }
 
-    /// Configures a payoff chart within the chartlist at a given index
-    fn configure_chart(&mut self, i: usize) {
-        let (y_axis, x_axis);
-        // Check validity of i
-        if let Some(((_y_axis, _x_axis), _)) = self.charts.data.get(i) {
-            (y_axis, x_axis) = (*_y_axis, *_x_axis);
-        } else {
-            return;
-        }
-        let x_range = self.ranges[x_axis as usize].clone();
-        let x_val = self.get_adjustable(x_axis);
-        let func;
-        // If using call options
-        if self.answers.0 {
-            func = self.get_parameterisation::<Call>(y_axis, x_axis);
-        } else { // Elsewise using put option
-            func = self.get_parameterisation::<Put>(y_axis, x_axis);
+    /// Label of a parameter input, following the kind of underlying
+    fn param_name(&self, i: usize) -> &'static str {
+        match i {
+            0 => self.underlying.price_name(),
+            2 => self.underlying.rate_name(),
+            3 => self.underlying.yield_name().unwrap_or(PARAM_NAMES[3]),
+            4 => self.underlying.prediction_name(),
+            _ => PARAM_NAMES[i],
         }
-        let (_, chart) = &mut self.charts.data[i];
-        chart.set_func(func)
-            .set_xrange(x_range)
-            .set_x_vert(x_val);
+    }
 
-        // Update entry price benchmark
-        let mut entry = 1.0;
-        if y_axis == PayoffYAxis::Nominal {
-            if self.answers.0 {
-                entry = Call::bsm_price_buy(&self.start_env, &self.contract).to_f64().unwrap_or(0.01);
-            } else {
-                entry = Put::bsm_price_buy(&self.start_env, &self.contract).to_f64().unwrap_or(0.01);
-            }
+    /// Value of a parameter input converted out of the unit it is entered in
+    fn param_value(&self, i: usize) -> f64 {
+        let value = self.param[i].get_value();
+        match i {
+            1 => self.vol_unit.decimal_of(value),
+            5 => self.time_unit.years_of(value),
+            _ => value,
         }
-        chart.set_benchmark_height(entry);
     }
 
-    /// Generates a single variable function that encapsulate a (practical) blackscholes calculation with 1 variable free. These
-    /// should be given to the payoff graphs to be plotted.
-    /// 
-    /// Practical meaning that prices within calculations are rounded to 2 d.p in the appropriate direction for buying/selling.
-    fn get_parameterisation<T: BlackScholesROIRounded>(&self, out: PayoffYAxis, var: Adjustables) -> Box<dyn Fn(f64) -> f64> {
-        use rust_decimal::prelude::ToPrimitive;
-
-        // Clone appropriate data
-        let func0 = {
-            let start_env = self.start_env.clone();
-            let end_env = self.end_env.clone();
-            let contract = self.contract.clone();
-            let predict = self.movement.clone();
-            Box::new(move |x| (x, start_env.clone(), end_env.clone(), contract.clone(), predict.clone()))
+    /// Sets a parameter input, converting the value into the unit the input is entered in
+    fn set_param_value(&mut self, i: usize, value: f64) {
+        let value = match i {
+            1 => self.vol_unit.expressed(value),
+            5 => self.time_unit.expressed(value),
+            _ => value,
         };
+        self.param[i].set_value(value);
+    }
 
-        // Establish data manipulation
-        let func1: Box<dyn Fn((f64, Environment, Environment, Contract, Movement)) -> (Environment, Environment, Contract, Movement)>;
-        match var {
-            Adjustables::Strike => {
-                func1 = Box::new(|(x, start_env, end_env, contract, predict)| {
-                    let mut new_contract = contract.clone();
-                    new_contract.strike = x;
-                    (start_env, end_env, new_contract, predict)
-                });
-            }
-            Adjustables::Expiry => {
-                func1 = Box::new(|(x, start_env, end_env, contract, predict)| {
-                    let mut new_contract = contract.clone();
-                    new_contract.expiry = x;
-                    (start_env, end_env, new_contract, predict)
-                });
-            }
-            Adjustables::EndPrice => {
-                func1 = Box::new(|(x, start_env, end_env, contract, predict)| {
-                    let mut new_predict = predict.clone();
-                    new_predict.stock = x;
-                    (start_env, end_env, contract, new_predict)
-                });
-            }
-            Adjustables::EndTime => {
-                func1 = Box::new(|(x, start_env, end_env, contract, predict)| {
-                    let mut new_predict = predict.clone();
-                    new_predict.time = x;
-                    (start_env, end_env, contract, new_predict)
-                });
-            }
-            Adjustables::EndVol => {
-                func1 = Box::new(|(x, start_env, end_env, contract, predict)| {
-                    let mut new_end_env = end_env.clone();
-                    new_end_env.vol = x;
-                    (start_env, new_end_env, contract, predict)
-                });
-            }
+    /// Sets the amount each parameter input is stepped by and the unit suffixes it accepts, following the
+    /// units of the volatility and duration
+    fn configure_param_inputs(&mut self) {
+        for (input, step) in self.param.iter_mut().zip(PARAM_STEPS) {
+            input.set_step(step);
         }
+        self.param[1].set_step(self.vol_unit.step())
+            .set_suffixes(self.vol_unit.suffixes());
+        // Rates and yields are entered as decimals
+        self.param[2].set_suffixes(vec![("%", 0.01)]);
+        self.param[3].set_suffixes(vec![("%", 0.01)]);
+        self.param[5].set_step(self.time_unit.step())
+            .set_suffixes(self.time_unit.suffixes());
+        self.event_inputs[0].set_step(self.time_unit.step())
+            .set_suffixes(self.time_unit.suffixes());
+        self.event_inputs[1].set_step(self.vol_unit.step())
+            .set_suffixes(self.vol_unit.suffixes());
+    }
 
-        // Establish whether to call ROI or nominal calculation
-        let func2: Box<dyn Fn((Environment, Environment, Contract, Movement)) -> f64>;
-        match out {
-            PayoffYAxis::ROI => {
-                func2 = Box::new(|(start_env, end_env, contract, movement)| {
-                    let roi = T::roi_practical(&start_env, &end_env, &contract, &movement);
-                    roi.to_f64().unwrap_or(0.0)
-                });
-            }
-            PayoffYAxis::Nominal => {
-                func2 = Box::new(|(start_env, end_env, contract, movement)| {
-                    let (_, exit) = T::buy_sell_prices_practical(&start_env, &end_env, &contract, &movement);
-                    exit.to_f64().unwrap_or(0.0)
-                })
-            }
+    /// Volatility event entered, converted out of the units it is entered in. None if no event is expected, or
+    /// Err(()) if the event inputs aren't validly filled in.
+    fn extract_vol_event(&self) -> Result<Option<VolEvent>, ()> {
+        if !self.use_event {
+            return Ok(None);
         }
+        if self.event_inputs.iter().any(|input| input.get_value().is_nan() || input.value_outside_range()) {
+            return Err(());
+        }
+        return Ok(Some(VolEvent {
+            time: self.time_unit.years_of(self.event_inputs[0].get_value()),
+            vol: self.vol_unit.decimal_of(self.event_inputs[1].get_value()),
+        }));
+    }
 
-        return Box::new(move |x| func2(func1(func0(x))));
+    /// Environment the contract is sold in at the current movement end, after any volatility event
+    fn exit_environment(&self) -> Environment {
+        return exit_env(&self.end_env, &self.movement, self.vol_event);
     }
 
-    fn update(&mut self, message: Message) -> Task<Message> {
-        match message {
-            Message::Calculate => {
-                // Validate and extract inputs
-                if let Some((env, pred)) = self.extract_env_and_pred() {
-                    self.start_env = env.clone();
-                    self.end_env = env;
-                    self.movement = pred;
-                } else {
-                    // self.answers_str = [
-                    //     String::from("Input invalid"),
-                    //     String::new(),
-                    //     String::new(),
-                    //     String::new(),
-                    //     String::new(),
-                    //     String::new(),
-                    // ];
-                    return Task::none();
-                }
-                
-                // Predicting stock to go up then we should use a call option
-                if self.movement.stock >= self.start_env.stock {
-                    // Find best contract given starting environment and predicted price movement
-                    self.contract = Call::find_best_contract(&self.start_env, &self.start_env, &self.movement);
-                    let (buy_price, sell_price) = Call::buy_sell_prices_practical(&self.start_env, &self.end_env, &self.contract, &self.movement);
-                    let roi = sell_price / buy_price;
-
-                    self.answers = (
-                        true,
-                        self.contract.clone(),
-                        buy_price.to_f64().unwrap_or(0.0),
-                        sell_price.to_f64().unwrap_or(0.0),
-                        roi.to_f64().unwrap_or(0.0),
-                    );
-
-                } else { // Elsewise we use a put option
-                    // Find best contract given starting environment and predicted price movement
-                    self.contract = Put::find_best_contract(&self.start_env, &self.start_env, &self.movement);
-                    let (buy_price, sell_price) = Put::buy_sell_prices_practical(&self.start_env, &self.end_env, &self.contract, &self.movement);
-                    let roi = sell_price / buy_price;
-
-                    self.answers = (
-                        false,
-                        self.contract.clone(),
-                        buy_price.to_f64().unwrap_or(0.0),
-                        sell_price.to_f64().unwrap_or(0.0),
-                        roi.to_f64().unwrap_or(0.0),
-                    );
-                }
-                // Configure ranges
-                for &adj in Adjustables::everything().iter() {
-                    self.ranges[adj as usize] = self.create_default_range(adj);
-                }
-
-                // Add the strike sliders if nothing is present
-                if self.sliders.data.is_empty() {
-                    let mut slider = CustomSlider::default().set_precision(MAX_DP);
-                    slider.set_title(format!("{}", Adjustables::Strike));
-                    self.sliders.unique_push(Adjustables::Strike, slider);
-                }
-                // Add the ROI-strike payoff chart if nothing is present
-                if self.charts.data.is_empty() {
-                    let chart = self.create_chart(PayoffYAxis::ROI, Adjustables::Strike);
-                    self.charts.unique_push((PayoffYAxis::ROI, Adjustables::Strike), chart);
-                }
-
-                // Configure sliders and charts
-                for i in 0..self.sliders.data.len() {
-                    self.configure_slider(i);
-                }
+    /// Size the interface is laid out in, after the interface size is applied
+    fn layout_size(&self) -> Size {
+        return self.window_size * (1.0 / self.settings.scale.0.clamp(0.5, 4.0));
+    }
+
+    /// Whether the window is too narrow for the inputs and charts side by side, e.g on a phone
+    fn is_compact(&self) -> bool {
+        return self.layout_size().width < COMPACT_WIDTH;
+    }
+
+    /// Sizes the sliders and delete buttons for touch screens in the compact layout, or back again
+    fn apply_touch(&mut self) {
+        let touch = self.is_compact();
+        self.sliders.set_touch(touch);
+        self.charts.set_touch(touch);
+        for (_, slider) in self.sliders.data.iter_mut() {
+            slider.set_touch(touch);
+        }
+    }
+
+    /// Years until the prediction end, from either the duration or date input. None if not validly entered.
+    fn horizon_years(&self) -> Option<f64> {
+        if !self.use_dates {
+            let time = self.param_value(5);
+            return (!time.is_nan() && !self.param[5].value_outside_range()).then_some(time);
+        }
+        let time = self.day_count.year_fraction(calendar::today(), self.horizon_date.get_date()?);
+        return (time >= 0.0).then_some(time);
+    }
+
+    /// Fills in the risk free rate box with the yield of the curve at the prediction end
+    fn fill_risk_free(&mut self, curve: &YieldCurve) {
+        let Some(tenor) = self.horizon_years() else {
+            self.rate_status = String::from("Enter the prediction end first");
+            return;
+        };
+        let rate = curve.rate_at(tenor);
+        self.param[2].set_value(rate);
+        self.rate_status = format!("{:.2}Y yield from {}", tenor, curve.source());
+    }
+
+    fn answer_text_block(&self) -> [String; 9] {
+        let mut out: [String; 9] = Default::default();
+        if self.answers.0 {
+            out[0] = String::from("Utilising Calls");
+        } else {
+            out[0] = String::from("Utilising Puts");
+        }
+        let Precision { inputs, prices, roi: roi_dp, .. } = self.settings.precision;
+        out[1] = format!("Strike: {:.*}", prices, self.answers.1.strike);
+        out[2] = format!("Expiry: {:.*}", inputs, self.answers.1.expiry);
+        if let Some(date) = self.valuation_date.and_then(|d| self.day_count.date_after(d, self.answers.1.expiry)) {
+            out[2] = format!("Expiry: {:.*} ({})", inputs, self.answers.1.expiry, date);
+        }
+        // Exit of the answer follows the end time while the time decay animation plays
+        let (sell, roi) = self.decay.as_ref().map_or((self.answers.3, self.answers.4), |d| (d.sell, d.roi));
+        // FX premiums follow the chosen convention, at the spot rate of entry and exit
+        let premium = |price: f64, spot: f64| match self.underlying {
+            Underlying::Fx => self.settings.premium_quote.format(price, spot, &self.settings.precision),
+            _ => format!("{:.*}", prices, price),
+        };
+        out[3] = format!("Buy Price: {}", premium(self.answers.2, self.start_env.stock));
+        out[4] = format!("Sell Price: {}", premium(sell, self.movement.stock));
+        if self.decay.is_some() {
+            out[4] = format!("Sell Price: {} (at {} {:.*})", premium(sell, self.movement.stock), Adjustables::EndTime, inputs, self.movement.time);
+        }
+        out[5] = format!("ROI: {:.*}", roi_dp, roi);
+        // Totals of trading a single contract
+        let dollars = |amount: f64| if amount < 0.0 { format!("-${:.*}", prices, -amount) } else { format!("${:.*}", prices, amount) };
+        let (cost, proceeds) = (self.answers.2 * self.settings.multiplier, sell * self.settings.multiplier);
+        out[6] = format!("Cost: {}", dollars(cost));
+        out[7] = format!("Proceeds: {}", dollars(proceeds));
+        out[8] = format!("Profit: {}", dollars(proceeds - cost));
+        return out;
+    }
+
+    /// Shows the solution of the given inputs as the answer and reconfigures the sliders and charts to it.
+    /// The candidate with the higher ROI becomes the answer, the other is kept as the alternative.
+    fn apply_solution(&mut self, env: Environment, pred: Movement, event: Option<VolEvent>, candidates: [Solution; 2]) {
+        let first_calculation = !self.calculated;
+        self.decay = None;
+        self.start_env = env.clone();
+        self.end_env = env;
+        self.vol_event = event;
+        self.prediction = pred.clone();
+        self.movement = pred;
+        self.valuation_date = self.use_dates.then(calendar::today);
+        self.calculated = true;
+        let [call, put] = candidates;
+        let (best, other) = if put.0.4 > call.0.4 { (put, call) } else { (call, put) };
+        (self.answers, self.diagnostics) = (best.0, Some(best.1));
+        (self.alternative, self.alternative_diagnostics) = (Some(other.0), Some(other.1));
+
+        // Open a layout chosen before the first calculation
+        if first_calculation && let Some(layout) = self.layout_select.clone() {
+            self.open_layout(layout.sliders, layout.charts, &[], &[]);
+        }
+        // Add the strike sliders if nothing is present
+        if self.sliders.data.is_empty() {
+            let slider = Self::create_slider(Adjustables::Strike, self.settings.precision.inputs);
+            self.sliders.unique_push(Adjustables::Strike, slider);
+        }
+        // Add the ROI-strike payoff chart if nothing is present
+        if self.charts.data.is_empty() {
+            let chart = self.create_chart(PayoffYAxis::ROI, Adjustables::Strike);
+            self.charts.unique_push((PayoffYAxis::ROI, Adjustables::Strike), chart);
+        }
+
+        self.configure_to_answer();
+
+        // Autosave so the session can be restored on next launch
+        if let Err(e) = storage::platform_storage().store(AUTOSAVE_KEY, &self.to_scenario().to_json()) {
+            self.file_status = format!("Autosave failed: {}", e);
+        }
+    }
+
+    /// Resets the contract and ranges to the answer then reconfigures the sliders and charts
+    fn configure_to_answer(&mut self) {
+        self.contract = self.answers.1.clone();
+        self.sync_answer_inputs();
+        for &adj in Adjustables::everything().iter() {
+            self.ranges[adj as usize] = self.create_default_range(adj);
+        }
+        for i in 0..self.sliders.data.len() {
+            self.configure_slider(i);
+        }
+        for i in 0..self.charts.data.len() {
+            self.configure_chart(i);
+        }
+        self.configure_heatmap();
+        self.configure_expiry_chart();
+        self.configure_cone_chart();
+        self.configure_stress_grid();
+        self.configure_simulation_chart();
+        self.configure_pnl_histogram();
+        self.configure_hedge();
+
+        #[cfg(feature = "broker")]
+        self.rank_chain();
+    }
+
+    /// Shows the answer's strike and expiry in their input boxes
+    fn sync_answer_inputs(&mut self) {
+        // Left alone while being typed into, e.g "1." is still the strike 1
+        for (input, value) in self.answer_inputs.iter_mut().zip([self.answers.1.strike, self.answers.1.expiry]) {
+            if input.get_value() != value {
+                input.set_value(value);
+            }
+        }
+    }
+
+    /// True if the inputs no longer match those the answer was calculated from.
+    /// Sliders aren't considered as they only override values in the charts, which are always up to date.
+    fn is_stale(&self) -> bool {
+        if !self.calculated {
+            return false;
+        }
+        match (self.extract_env_and_pred(), self.extract_vol_event()) {
+            (Some((env, pred)), Ok(event)) => return env != self.start_env || pred != self.movement || event != self.vol_event,
+            _ => return true,
+        }
+    }
+
+    /// Table of the answer's greeks at entry and at the predicted exit. Header row first.
+    /// Theta is given per calendar day, vega and rho per 1% change.
+    fn greeks_text_block(&self) -> [[String; 3]; 6] {
+        let (end_env, end_contract) = self.movement.apply(self.exit_environment(), self.answers.1.clone());
+        let pricer = self.pricer();
+        let greeks: [Greeks; 2] = [pricer.greeks(&self.start_env, &self.answers.1), pricer.greeks(&end_env, &end_contract)];
+        let row = |name: &str, value: fn(&Greeks) -> f64| {
+            let dp = self.settings.precision.greeks;
+            [String::from(name), format!("{:.*}", dp, value(&greeks[0])), format!("{:.*}", dp, value(&greeks[1]))]
+        };
+        return [
+            [String::from("Greeks"), String::from("Entry"), String::from("Exit")],
+            row("Delta", |g| g.delta),
+            row("Gamma", |g| g.gamma),
+            row("Theta/day", |g| g.theta / 365.0),
+            row("Vega (1%)", |g| g.vega / 100.0),
+            row("Rho (1%)", |g| g.rho / 100.0),
+        ];
+    }
+
+    /// Fair variance swap strike over the prediction time frame, as a volatility. Replicated from the fitted smile
+    /// of the option chain if there is one, otherwise from the flat volatility blended with the volatility event's.
+    fn fair_variance_vol(&self) -> f64 {
+        let time = self.movement.time;
+        #[cfg(feature = "broker")]
+        if let Some(fit) = &self.chain_smile {
+            let forward = which_option::sabr::forward(&self.start_env, time);
+            return variance_swap::fair_variance(&self.start_env, time, |strike| fit.sabr.implied_vol(forward, strike, time)).sqrt();
+        }
+        let vol = self.vol_event.map_or(self.start_env.vol, |event| event.term_vol(self.start_env.vol, time));
+        return variance_swap::fair_variance(&self.start_env, time, |_| vol).sqrt();
+    }
+
+    /// What the fair variance swap strike is replicated from, see fair_variance_vol
+    fn variance_source(&self) -> &'static str {
+        #[cfg(feature = "broker")]
+        if self.chain_smile.is_some() {
+            return "Smile";
+        }
+        if self.vol_event.is_some() {
+            return "Flat + event";
+        }
+        return "Flat";
+    }
+
+    /// Table of the answer's value at risk and expected shortfall per share over the prediction time frame,
+    /// then the fair variance swap strike over it. Header row first.
+    fn risk_text_block(&self) -> [[String; 3]; 4] {
+        let pricer = self.pricer();
+        let risks = RISK_CONFIDENCES.map(|confidence| {
+            pricer.tail_risk(&self.start_env, &self.exit_environment(), &self.answers.1, &self.movement, confidence)
+        });
+        let row = |name: &str, value: fn(&TailRisk) -> f64| {
+            let dp = self.settings.precision.prices;
+            [String::from(name), format!("{:.*}", dp, value(&risks[0])), format!("{:.*}", dp, value(&risks[1]))]
+        };
+        return [
+            [String::from("Risk"), format!("{}%", RISK_CONFIDENCES[0] * 100.0), format!("{}%", RISK_CONFIDENCES[1] * 100.0)],
+            row("VaR", |risk| risk.value_at_risk),
+            row("Exp. shortfall", |risk| risk.expected_shortfall),
+            [String::from("Var. swap vol"), format!("{:.3}", self.fair_variance_vol()), String::from(self.variance_source())],
+        ];
+    }
+
+    /// Table comparing the answer against buying the stock (or shorting it for puts) for the same prediction.
+    /// Leverage is the percentage change in price for a 1% change in the stock price at entry. Header row first.
+    fn comparison_text_block(&self) -> [[String; 3]; 4] {
+        let pricer = self.pricer();
+        let short = !pricer.is_call();
+        let roi = self.decay.as_ref().map_or(self.answers.4, |d| d.roi);
+        let stock_roi = self.movement.stock_roi(&self.start_env, short);
+        let leverage = pricer.greeks(&self.start_env, &self.answers.1).delta * self.start_env.stock / self.answers.2;
+        let Precision { prices, roi: roi_dp, .. } = self.settings.precision;
+        return [
+            [String::from("Versus"), String::from("Option"), String::from(if short { "Short stock" } else { "Stock" })],
+            [String::from("Cost/share"), format!("{:.*}", prices, self.answers.2), format!("{:.*}", prices, self.start_env.stock)],
+            [String::from("ROI"), format!("{:.*}", roi_dp, roi), format!("{:.*}", roi_dp, stock_roi)],
+            [String::from("Leverage"), format!("{:.2}x", leverage), format!("{:.2}x", if short { -1.0 } else { 1.0 })],
+        ];
+    }
+
+    /// Table comparing the answer held as a European option against an American option, which can also be
+    /// exercised before expiry, and a perpetual American option that never expires. Priced without rounding.
+    /// Header row first.
+    fn exercise_text_block(&self) -> [[String; 3]; 4] {
+        let pricer = self.pricer();
+        let contract = &self.answers.1;
+        let (end_env, end_contract) = self.movement.apply(self.exit_environment(), contract.clone());
+        let european = pricer.buy_sell_prices(&self.start_env, &self.exit_environment(), contract, &self.movement);
+        let american = (
+            american::price(pricer, &self.start_env, contract),
+            american::price(pricer, &end_env, &end_contract),
+        );
+        let perpetual = (
+            american::perpetual_price(pricer, &self.start_env, contract.strike),
+            american::perpetual_price(pricer, &end_env, contract.strike),
+        );
+        let Precision { prices, roi, .. } = self.settings.precision;
+        let row = |name: &str, (buy, sell): (f64, f64)| [
+            String::from(name),
+            format!("{:.*}/{:.*}", prices, buy, prices, sell),
+            format!("{:.*}", roi, sell / buy.max(blackscholes::ROI_FLOOR_THRESHOLD)),
+        ];
+        return [
+            [String::from("Exercise"), String::from("Buy/Sell"), String::from("ROI")],
+            row("European", european),
+            row("American", american),
+            row("Perpetual", perpetual),
+        ];
+    }
+
+    /// Ranks the contracts of the last pulled option chain against the current prediction.
+    /// Only contracts of the same type as the answer are considered.
+    #[cfg(feature = "broker")]
+    fn rank_chain(&mut self) {
+        let today = calendar::today();
+        let smile = self.chain_smile.as_ref().filter(|_| self.use_smile).map(|fit| &fit.sabr);
+        self.chain_ranked = chain::rank_chain(&self.chain_quotes, self.pricer(), today, &self.exit_environment(), &self.movement, smile);
+        if !self.chain_quotes.is_empty() {
+            self.chain_status = format!("{} of {} quoted contracts ranked", self.chain_ranked.len(), self.chain_quotes.len());
+        }
+    }
+
+    /// Captures all inputs, the calculation and the open sliders/charts
+    fn to_scenario(&self) -> Scenario {
+        Scenario {
+            version: scenario::SCENARIO_VERSION,
+            inputs: self.param.each_ref().map(|input| input.get_text().to_string()),
+            vol_unit: self.vol_unit,
+            time_unit: self.time_unit,
+            use_dates: self.use_dates,
+            underlying: self.underlying,
+            horizon_date: self.horizon_date.get_text().to_string(),
+            day_count: self.day_count,
+            notes: self.notes.text(),
+            use_event: self.use_event,
+            event_inputs: self.event_inputs.each_ref().map(|input| input.get_text().to_string()),
+            calculation: self.calculated.then(|| Calculation {
+                answers: self.answers.clone(),
+                alternative: self.alternative.clone(),
+                start_env: self.start_env.clone(),
+                end_env: self.end_env.clone(),
+                vol_event: self.vol_event,
+                movement: self.movement.clone(),
+                contract: self.contract.clone(),
+                ranges: self.ranges.clone(),
+                valuation_date: self.valuation_date,
+            }),
+            sliders: self.sliders.data.iter().map(|(adj, _)| *adj).collect(),
+            charts: self.charts.data.iter().map(|(axes, _)| *axes).collect(),
+            slider_configs: self.sliders.to_configs(CustomSlider::config).into_iter().map(|(_, config)| config).collect(),
+            chart_configs: self.charts.to_configs(PayoffChart::config).into_iter().map(|(_, config)| config).collect(),
+        }
+    }
+
+    /// Restores all inputs, the calculation and the open sliders/charts from a scenario
+    fn apply_scenario(&mut self, scenario: Scenario) {
+        self.decay = None;
+        // Units first, so the inputs accept the suffixes of their units
+        self.vol_unit = scenario.vol_unit;
+        self.time_unit = scenario.time_unit;
+        self.configure_param_inputs();
+        for (input, text) in self.param.iter_mut().zip(scenario.inputs) {
+            input.update(NumberInputMessage::Edit(text));
+        }
+        self.use_dates = scenario.use_dates;
+        self.underlying = scenario.underlying;
+        self.horizon_date.update(DateInputMessage::Edit(scenario.horizon_date));
+        self.day_count = scenario.day_count;
+        self.notes = text_editor::Content::with_text(&scenario.notes);
+        self.use_event = scenario.use_event;
+        for (input, text) in self.event_inputs.iter_mut().zip(scenario.event_inputs) {
+            input.update(NumberInputMessage::Edit(text));
+        }
+
+        self.sliders.data.clear();
+        self.charts.data.clear();
+        self.calculated = false;
+        let Some(calculation) = scenario.calculation else {
+            return;
+        };
+        self.calculated = true;
+        self.answers = calculation.answers;
+        self.sync_answer_inputs();
+        self.alternative = calculation.alternative;
+        self.alternative_diagnostics = None;
+        self.start_env = calculation.start_env;
+        self.end_env = calculation.end_env;
+        self.vol_event = calculation.vol_event;
+        self.prediction = calculation.movement.clone();
+        self.movement = calculation.movement;
+        self.contract = calculation.contract;
+        self.ranges = calculation.ranges;
+        self.valuation_date = calculation.valuation_date;
+        self.open_layout(scenario.sliders, scenario.charts, &scenario.slider_configs, &scenario.chart_configs);
+    }
+
+    /// Replaces the open sliders and charts with the given ones, configured to the current calculation.
+    /// Saved configs are applied in the same order. Sliders/charts without one keep their defaults.
+    fn open_layout(
+        &mut self,
+        sliders: Vec<Adjustables>,
+        charts: Vec<(PayoffYAxis, Adjustables)>,
+        slider_configs: &[SliderConfig],
+        chart_configs: &[ChartConfig],
+    ) {
+        let precision = self.settings.precision.inputs;
+        self.sliders.replace_all(sliders.into_iter().enumerate().map(|(i, adj)| {
+            let mut slider = Self::create_slider(adj, precision);
+            if let Some(config) = slider_configs.get(i) {
+                slider.apply_config(config);
+            }
+            (adj, slider)
+        }));
+        for i in 0..self.sliders.data.len() {
+            self.configure_slider(i);
+        }
+        let charts: Vec<_> = charts.into_iter().enumerate().map(|(i, (y_axis, x_axis))| {
+            let mut chart = self.create_chart(y_axis, x_axis);
+            if let Some(config) = chart_configs.get(i) {
+                chart.apply_config(config);
+            }
+            ((y_axis, x_axis), chart)
+        }).collect();
+        self.charts.replace_all(charts);
+        for i in 0..self.charts.data.len() {
+            self.configure_chart(i);
+        }
+        self.configure_heatmap();
+        self.configure_expiry_chart();
+        self.configure_cone_chart();
+        self.configure_stress_grid();
+        self.configure_simulation_chart();
+        self.configure_pnl_histogram();
+        self.configure_hedge();
+    }
+
+    /// Writes the answer, slider values and the sampled points of every chart as CSV
+    fn export_csv(&self) -> String {
+        let mut out = String::from("Answer\n");
+        for line in self.answer_text_block() {
+            match line.split_once(": ") {
+                Some((name, value)) => out += &format!("{},{}\n", csv_field(name), csv_field(value)),
+                None => out += &format!("{}\n", csv_field(&line)),
+            }
+        }
+
+        out += "\nSliders\nVariable,Value,Slider Min,Slider Max\n";
+        for (adj, slider) in self.sliders.data.iter() {
+            let range = slider.get_slider_range();
+            out += &format!("{},{},{},{}\n", csv_field(&adj.to_string()), slider.get_value(), range.start(), range.end());
+        }
+
+        for (_, chart) in self.charts.data.iter() {
+            out += &format!("\nChart,{}\n", csv_field(chart.get_title()));
+            out += &chart.to_csv();
+        }
+        return out;
+    }
+
+    /// Writes a spreadsheet with the inputs, answer and sliders on the first sheet followed by
+    /// a sheet of sampled points for each chart
+    fn export_xlsx(&self) -> Result<Vec<u8>, rust_xlsxwriter::XlsxError> {
+        use rust_xlsxwriter::{Format, Workbook};
+
+        /// Sheet names are limited to 31 characters by Excel
+        const MAX_SHEET_NAME: usize = 31;
+
+        let header = Format::new().set_bold().set_font_size(14);
+        let bold = Format::new().set_bold();
+        let number = Format::new().set_num_format("0.000");
+        let price = Format::new().set_num_format("0.00");
+
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet().set_name("Summary")?;
+        sheet.set_column_width(0, 25)?;
+        sheet.set_column_width(1, 15)?;
+        let mut row = 0;
+
+        sheet.write_with_format(row, 0, "Inputs", &header)?;
+        row += 1;
+        let used_inputs = if self.use_dates { 5 } else { 6 };
+        // Values are written in decimals and years regardless of the units entered in
+        for (i, input) in self.param.iter().take(used_inputs).enumerate() {
+            sheet.write(row, 0, self.param_name(i))?;
+            match self.param_value(i) {
+                value if value.is_nan() => sheet.write(row, 1, input.get_text())?,
+                value => sheet.write_with_format(row, 1, value, &number)?,
+            };
+            row += 1;
+        }
+        if self.use_dates {
+            sheet.write(row, 0, "Prediction end date")?;
+            sheet.write(row, 1, self.horizon_date.get_text())?;
+            sheet.write(row + 1, 0, "Day count")?;
+            sheet.write(row + 1, 1, self.day_count.to_string())?;
+            row += 2;
+        }
+
+        row += 1;
+        sheet.write_with_format(row, 0, "Answer", &header)?;
+        row += 1;
+        if self.calculated {
+            let (is_call, contract, buy, sell, roi) = &self.answers;
+            sheet.write(row, 0, "Option")?;
+            sheet.write(row, 1, if *is_call { "Call" } else { "Put" })?;
+            let numbers = [
+                ("Strike", contract.strike, &number),
+                ("Expiry", contract.expiry, &number),
+                ("Buy Price", *buy, &price),
+                ("Sell Price", *sell, &price),
+                ("ROI", *roi, &number),
+            ];
+            for (i, (name, value, format)) in numbers.into_iter().enumerate() {
+                sheet.write(row + 1 + i as u32, 0, name)?;
+                sheet.write_with_format(row + 1 + i as u32, 1, value, format)?;
+            }
+            row += 1 + numbers.len() as u32;
+        } else {
+            sheet.write(row, 0, "Not calculated")?;
+            row += 1;
+        }
+
+        row += 1;
+        sheet.write_with_format(row, 0, "Sliders", &header)?;
+        row += 1;
+        for (col, name) in ["Variable", "Value", "Slider Min", "Slider Max"].into_iter().enumerate() {
+            sheet.write_with_format(row, col as u16, name, &bold)?;
+        }
+        row += 1;
+        for (adj, slider) in self.sliders.data.iter() {
+            let range = slider.get_slider_range();
+            sheet.write(row, 0, adj.to_string())?;
+            for (col, value) in [slider.get_value(), *range.start(), *range.end()].into_iter().enumerate() {
+                sheet.write_with_format(row, col as u16 + 1, value, &number)?;
+            }
+            row += 1;
+        }
+
+        for ((y_axis, x_axis), chart) in self.charts.data.iter() {
+            let name: String = format!("{} vs {}", y_axis, x_axis).chars().take(MAX_SHEET_NAME).collect();
+            let sheet = workbook.add_worksheet().set_name(name)?;
+            sheet.write_with_format(0, 0, chart.get_title(), &header)?;
+            let (headers, rows) = chart.to_table();
+            for (col, name) in headers.into_iter().enumerate() {
+                sheet.write_with_format(1, col as u16, name, &bold)?;
+                sheet.set_column_width(col as u16, 18)?;
+            }
+            for (i, values) in rows.into_iter().enumerate() {
+                for (col, value) in values.into_iter().enumerate() {
+                    sheet.write_with_format(i as u32 + 2, col as u16, value, &number)?;
+                }
+            }
+        }
+        return workbook.save_to_buffer();
+    }
+
+    /// Writes an HTML report with the inputs, answer, greeks, risk tables, notes and the open charts
+    fn export_report(&self) -> String {
+        let mut report = Report::new(&format!("Trade plan ({})", calendar::today()));
+
+        let used_inputs = if self.use_dates { 5 } else { 6 };
+        let mut inputs = vec![[String::from("Input"), String::from("Value")]];
+        inputs.extend(self.param.iter().take(used_inputs).enumerate()
+            .map(|(i, input)| [self.param_name(i).to_string(), input.get_text().to_string()]));
+        inputs[2][0] += &format!(" ({})", self.vol_unit);
+        if self.use_dates {
+            inputs.push([String::from("Prediction end date"), self.horizon_date.get_text().to_string()]);
+            inputs.push([String::from("Day count"), self.day_count.to_string()]);
+        } else {
+            inputs[6][0] += &format!(" ({})", self.time_unit);
+        }
+        if let Some(event) = self.vol_event {
+            inputs.push([String::from("Vol event time"), event.time.to_string()]);
+            inputs.push([String::from("Vol after event"), event.vol.to_string()]);
+        }
+        if self.underlying.yield_name().is_none() {
+            inputs[4][1] = String::from("-");
+        }
+        inputs.push([String::from("Underlying"), self.underlying.to_string()]);
+        report.heading("Inputs").table(&inputs);
+
+        report.heading("Answer");
+        if !self.calculated {
+            report.paragraph("Not calculated");
+            return report.to_html();
+        }
+        for line in self.answer_text_block() {
+            report.paragraph(&line);
+        }
+        report.table(&self.greeks_text_block())
+            .table(&self.risk_text_block())
+            .table(&self.comparison_text_block())
+            .table(&self.exercise_text_block());
+        if let Some(hedge) = self.hedge_text_block() {
+            let rebalance = self.hedge_rebalance.unwrap_or_default();
+            report.heading(&format!("Delta hedge ({} rebalancing)", rebalance)).table(&hedge);
+        }
+
+        if !self.notes.text().trim().is_empty() {
+            report.heading("Notes").notes(self.notes.text().trim_end());
+        }
+
+        if !self.charts.data.is_empty() {
+            report.heading("Charts");
+        }
+        for (_, chart) in self.charts.data.iter() {
+            report.figure(&chart.to_svg());
+        }
+        return report.to_html();
+    }
+
+    /// Creates a "reasonable" range of values the given variable should be able to take up
+    fn create_default_range(&self, var: Adjustables) -> RangeInclusive<f64> {
+        match var {
+            Adjustables::Strike => self.settings.range_span.around(self.contract.strike),
+            Adjustables::Expiry => self.movement.time..=(1.0 + self.settings.range_span.0)*self.movement.time,
+            Adjustables::EndPrice => self.settings.range_span.around(self.movement.stock),
+            Adjustables::EndTime => 0.0..=self.contract.expiry,
+            Adjustables::EndVol => self.settings.range_span.around(self.end_env.vol),
+        }
+    }
+
+    /// Creates the widest range of values that is valid (or makes "sense") for the given variable.
+    /// Every variable is at least its lowest value, and bounded by the current values of the variables in CONSTRAINTS.
+    fn create_valid_range(&self, var: Adjustables) -> RangeInclusive<f64> {
+        let (mut start, mut end) = (var.lowest(), f64::MAX);
+        for (lower, upper) in CONSTRAINTS {
+            if var == lower {
+                end = end.min(self.get_adjustable(upper));
+            }
+            if var == upper {
+                start = start.max(self.get_adjustable(lower));
+            }
+        }
+        return start..=end;
+    }
+
+    /// Applies the valid range of every slider. Any value moved by its new range is cascaded on to the
+    /// ranges of the variables constrained by it.
+    fn enforce_constraints(&mut self) {
+        // Each pass can only move the variables constrained by those moved in the pass before
+        for _ in 0..Adjustables::COUNT {
+            let mut moved = false;
+            for i in 0..self.sliders.data.len() {
+                let var = self.sliders.data[i].0;
+                let range = self.create_valid_range(var);
+                self.sliders.data[i].1.set_allowed_range(range);
+                let value = self.sliders.data[i].1.get_value();
+                if value != self.get_adjustable(var) {
+                    self.set_adjustable(var, value);
+                    moved = true;
+                }
+                self.ranges[var as usize] = self.sliders.data[i].1.get_slider_range();
+            }
+            if !moved {
+                break;
+            }
+        }
+    }
+
+    /// Update appropriate data given the parameter and a value
+    fn set_adjustable(&mut self, var: Adjustables, val: f64) {
+        match var {
+            Adjustables::Expiry => {self.contract.expiry = val;}
+            Adjustables::EndTime => {self.movement.time = val;}
+            Adjustables::EndPrice => {self.movement.stock = val;}
+            Adjustables::Strike => {self.contract.strike = val;}
+            Adjustables::EndVol => {self.end_env.vol = val;}
+        }
+    }
+
+    /// Value of a variable in the calculated answer and prediction, before any slider overrides
+    fn original_adjustable(&self, var: Adjustables) -> f64 {
+        match var {
+            Adjustables::Strike => self.answers.1.strike,
+            Adjustables::Expiry => self.answers.1.expiry,
+            Adjustables::EndPrice => self.prediction.stock,
+            Adjustables::EndTime => self.prediction.time,
+            Adjustables::EndVol => self.start_env.vol,
+        }
+    }
+
+    /// Retrieves appropriate data given the parameter and a value
+    fn get_adjustable(&self, var: Adjustables) -> f64 {
+        match var {
+            Adjustables::Strike => self.contract.strike,
+            Adjustables::Expiry => self.contract.expiry,
+            Adjustables::EndPrice => self.movement.stock,
+            Adjustables::EndTime => self.movement.time,
+            Adjustables::EndVol => self.end_env.vol,
+        }
+    }
+
+    fn create_chart(&self, y_axis: PayoffYAxis, x_axis: Adjustables) -> PayoffChart {
+        let mut chart: PayoffChart;
+        if y_axis == PayoffYAxis::Nominal {
+            let factor = self.settings.nominal_factor();
+            chart = PayoffChart::new_nominal_chart(format!("{} for different {}", y_axis, x_axis), format!("{}", x_axis));
+            chart.set_benchmark_height(self.answers.2 * factor);
+            chart.set_yrange(0.0..=self.answers.3*1.1 * factor);
+        } else {
+            chart = PayoffChart::new_roi_chart(format!("{} for different {}", y_axis, x_axis), format!("{}", x_axis));
+            chart.set_yrange(0.0..=self.answers.4*1.1);
+        }
+        let (x_dp, y_dp) = self.chart_decimals(y_axis);
+        chart.set_xrange(self.ranges[x_axis as usize].clone())
+            .set_default_resolution(self.settings.chart_resolution.0)
+            .set_style(self.settings.chart_style(self.theme.palette()))
+            .set_decimals(x_dp, y_dp);
+        return chart;
+    }
+
+    /// Decimal places of the (x, y) values labelled on a payoff chart with the given y-axis
+    fn chart_decimals(&self, y_axis: PayoffYAxis) -> (usize, usize) {
+        let precision = self.settings.precision;
+        match y_axis {
+            PayoffYAxis::ROI => return (precision.inputs, precision.roi),
+            PayoffYAxis::Nominal => return (precision.inputs, precision.prices),
+        }
+    }
+
+    /// Creates a slider for a variable taking values to the given decimal places, snapping to the variable's default step
+    fn create_slider(adj: Adjustables, precision: usize) -> CustomSlider {
+        let mut slider = CustomSlider::default().set_precision(precision);
+        slider.set_title(format!("{}", adj))
+            .set_step(adj.default_step());
+        return slider;
+    }
+
+    /// Configures a variable slider within the sliderlist at a given index
+    fn configure_slider(&mut self, i: usize) {
+        let adj;
+        // Check validity of i
+        if let Some(&(_adj, _)) = self.sliders.data.get(i) {
+            adj = _adj
+        } else {
+            return;
+        }
+        let val = self.get_adjustable(adj);
+        let range = self.ranges[adj as usize].clone();
+        let valid_range = self.create_valid_range(adj);
+        let original = self.original_adjustable(adj);
+        // Times can be picked as dates when the prediction was entered as a date
+        let dates = self.valuation_date
+            .filter(|_| matches!(adj, Adjustables::Expiry | Adjustables::EndTime))
+            .map(|date| (date, self.day_count));
+        let touch = self.is_compact();
+        // We know i is valid at this point
+        let slider = &mut self.sliders.data[i].1;
+        slider.set_touch(touch)
+            .set_dates(dates)
+            .set_allowed_range(valid_range)
+            .set_slider_range(range);
+        slider.set_original(original)
+            .set_value(val);
+    }
+
+    /// Configures a payoff chart within the chartlist at a given index.
+    /// Charts that are off-screen are marked stale instead, and configured once scrolled into view.
+    fn configure_chart(&mut self, i: usize) {
+        if let Some(&(id, _)) = self.charts.data.get(i) && !self.chart_visible(i) {
+            if !self.stale_charts.contains(&id) {
+                self.stale_charts.push(id);
+            }
+            return;
+        }
+        self.configure_chart_now(i);
+    }
+
+    /// Moves the crosshair and x-axis range of a payoff chart within the chartlist at a given index, without
+    /// computing its function again. For when only the chart's own x-axis variable has moved.
+    fn position_chart(&mut self, i: usize) {
+        let Some(&((_, x_axis), _)) = self.charts.data.get(i) else {
+            return;
+        };
+        // Off-screen charts are left stale to be configured in full once in view
+        if !self.chart_visible(i) || self.stale_charts.contains(&self.charts.data[i].0) {
+            self.configure_chart(i);
+            return;
+        }
+        let (x_range, x_val) = (self.ranges[x_axis as usize].clone(), self.get_adjustable(x_axis));
+        self.charts.data[i].1.set_xrange(x_range)
+            .set_x_vert(x_val);
+    }
+
+    /// Whether the payoff chart of the given axes has to be computed again when a variable moves.
+    /// A chart's own x-axis variable only moves its crosshair, apart from the entry price of nominal charts.
+    fn chart_depends_on((y_axis, x_axis): (PayoffYAxis, Adjustables), var: Adjustables) -> bool {
+        if var != x_axis {
+            return true;
+        }
+        return y_axis == PayoffYAxis::Nominal && matches!(var, Adjustables::Strike | Adjustables::Expiry);
+    }
+
+    /// Whether the heatmap has to be computed again when a variable moves. The strike and expiry only move its marker.
+    fn heatmap_depends_on(var: Adjustables) -> bool {
+        return !matches!(var, Adjustables::Strike | Adjustables::Expiry);
+    }
+
+    /// Whether the simulation chart has to be configured again when a variable moves.
+    /// Paths are simulated from the start environment, so the predicted end price and volatility don't affect it.
+    /// (The time of a volatility event only changes with a new calculation.)
+    fn simulation_depends_on(var: Adjustables) -> bool {
+        return matches!(var, Adjustables::Strike | Adjustables::Expiry | Adjustables::EndTime);
+    }
+
+    /// Whether the expiry chart has to be configured again when a variable moves
+    fn expiry_chart_depends_on(var: Adjustables) -> bool {
+        return matches!(var, Adjustables::Strike | Adjustables::Expiry | Adjustables::EndPrice);
+    }
+
+    /// Whether the cone chart has to be configured again when a variable moves. The cone is drawn from the start
+    /// environment, so only the prediction moves it.
+    fn cone_chart_depends_on(var: Adjustables) -> bool {
+        return matches!(var, Adjustables::EndPrice | Adjustables::EndTime);
+    }
+
+    /// Configures every stale chart now in view. Or every stale chart if all, e.g before the charts are exported.
+    fn configure_stale_charts(&mut self, all: bool) {
+        for id in std::mem::take(&mut self.stale_charts) {
+            // Deleted charts are dropped
+            let Some(i) = self.charts.scan_ID(&id) else {
+                continue;
+            };
+            if all || self.chart_visible(i) {
+                self.configure_chart_now(i);
+            } else {
+                self.stale_charts.push(id);
+            }
+        }
+    }
+
+    /// Samples every chart with out of date points in the background, posting the points back as ChartSampled.
+    /// Charts already being sampled are sampled again once their points come back.
+    fn sample_charts_in_background(&mut self) -> Task<Message> {
+        let tasks: Vec<Task<Message>> = self.charts.data.iter_mut()
+            .filter_map(|(id, chart)| {
+                let id = *id;
+                chart.sample_in_background()
+                    .map(|sampling| Task::perform(sampling, move |(generation, samples)| Message::ChartSampled(id, generation, samples)))
+            })
+            .collect();
+        return Task::batch(tasks);
+    }
+
+    /// Whether the chart at a given index is within (or near) the visible part of the chart panel
+    fn chart_visible(&self, i: usize) -> bool {
+        // A maximized chart hides every other
+        if let Some(maximized) = self.charts.data.iter().position(|(_, chart)| chart.is_maximized()) {
+            return i == maximized;
+        }
+        return self.visible_chart_rows().contains(&(i / self.settings.chart_columns.count()));
+    }
+
+    /// Rows of the chart grid within the visible part of the chart panel, along with the overscan rows
+    fn visible_chart_rows(&self) -> Range<usize> {
+        let columns = self.settings.chart_columns.count();
+        let rows = self.charts.data.len().div_ceil(columns);
+        let Some(viewport) = self.chart_viewport else {
+            return 0..rows;
+        };
+        let bounds = viewport.bounds();
+        // Matches the row height the grid is drawn with
+        let row_height = bounds.width / columns as f32 / CHART_ASPECT_RATIO;
+        if row_height <= 0.0 {
+            return 0..rows;
+        }
+        let shown = (bounds.height / row_height).ceil() as usize;
+        // The grid may have shrunk since the viewport was last published, in which case it shows the last rows
+        let first = ((viewport.absolute_offset().y / row_height) as usize).min(rows.saturating_sub(shown));
+        return first.saturating_sub(CHART_OVERSCAN_ROWS)..(first + shown + CHART_OVERSCAN_ROWS).min(rows);
+    }
+
+    /// Configures a payoff chart within the chartlist at a given index, whether or not it's in view
+    fn configure_chart_now(&mut self, i: usize) {
+        let (y_axis, x_axis);
+        // Check validity of i
+        if let Some(((_y_axis, _x_axis), _)) = self.charts.data.get(i) {
+            (y_axis, x_axis) = (*_y_axis, *_x_axis);
+        } else {
+            return;
+        }
+        self.stale_charts.retain(|id| *id != (y_axis, x_axis));
+        let x_range = self.ranges[x_axis as usize].clone();
+        let x_val = self.get_adjustable(x_axis);
+        let pricer = self.pricer();
+        let mut func = self.get_parameterisation(y_axis, x_axis);
+        let factor = self.settings.nominal_factor();
+        if y_axis == PayoffYAxis::Nominal && factor != 1.0 {
+            func = Arc::new(move |x| func(x) * factor);
+        }
+        // Mark the strike and breakevens against the stock end price
+        let mut markers = Vec::new();
+        if x_axis == Adjustables::EndPrice {
+            let range = (*x_range.start(), *x_range.end());
+            let breakevens = pricer.breakevens(&self.start_env, &self.exit_environment(), &self.contract, &self.movement, range);
+            markers.push((String::from("Strike"), self.contract.strike));
+            markers.extend(breakevens.into_iter().map(|x| (String::from("Breakeven"), x)));
+        }
+        // Likelihood of each end price, which only exists after some time has passed
+        let mut density: Option<ChartFunc> = None;
+        if x_axis == Adjustables::EndPrice && self.movement.time > 0.0 {
+            let (env, time) = (self.start_env.clone(), self.movement.time);
+            density = Some(Arc::new(move |x| env.terminal_density(time, x)));
+        }
+        let (_, chart) = &mut self.charts.data[i];
+        chart.set_func(func)
+            .set_xrange(x_range)
+            .set_x_vert(x_val)
+            .set_markers(markers)
+            .set_density(density);
+
+        // Update entry price benchmark
+        let mut entry = 1.0;
+        if y_axis == PayoffYAxis::Nominal {
+            entry = pricer.bsm_price_buy(&self.start_env, &self.contract).to_f64().unwrap_or(0.01) * factor;
+        }
+        chart.set_benchmark_height(entry);
+    }
+
+    /// Configures the payoff at expiry chart (if open) to the current contract
+    fn configure_expiry_chart(&mut self) {
+        let pricer = self.pricer();
+        let Some(chart) = &mut self.expiry_chart else {
+            return;
+        };
+        let premium = pricer.bsm_price_buy(&self.start_env, &self.contract);
+        chart.set_option(pricer.is_call(), self.contract.strike, premium.to_f64().unwrap_or(0.01))
+            .set_xrange(self.ranges[Adjustables::EndPrice as usize].clone())
+            .set_prediction(self.movement.stock)
+            .set_scale(self.settings.nominal_factor())
+            .set_style(self.settings.chart_style(self.theme.palette()));
+    }
+
+    /// Configures the expected move chart (if open) to the start environment and the prediction
+    fn configure_cone_chart(&mut self) {
+        let Some(chart) = &mut self.cone_chart else {
+            return;
+        };
+        let env = &self.start_env;
+        chart.set_stock(env.stock, env.risk_free - env.div_yield, env.vol)
+            .set_time(self.movement.time)
+            .set_prediction(self.movement.stock)
+            .set_style(self.settings.chart_style(self.theme.palette()));
+    }
+
+    /// Redraws every chart in the style of the current theme and chart palette
+    fn restyle_charts(&mut self) {
+        let style = self.settings.chart_style(self.theme.palette());
+        for (_, chart) in self.charts.data.iter_mut() {
+            chart.set_style(style);
+        }
+        if let Some(heatmap) = &mut self.heatmap {
+            heatmap.set_style(style);
+        }
+        if let Some(chart) = &mut self.expiry_chart {
+            chart.set_style(style);
+        }
+        if let Some(chart) = &mut self.cone_chart {
+            chart.set_style(style);
+        }
+        if let Some(grid) = &mut self.stress_grid {
+            grid.set_style(style);
+        }
+        if let Some(chart) = &mut self.simulation_chart {
+            chart.set_style(style);
+        }
+        if let Some(chart) = &mut self.pnl_histogram {
+            chart.set_style(style);
+        }
+    }
+
+    /// Resimulates the stock price paths (if the simulation chart is open) over the prediction time frame
+    /// and recomputes the bands of the contract's value along them
+    fn configure_simulation_chart(&mut self) {
+        let pricer = self.pricer();
+        let Some(chart) = &mut self.simulation_chart else {
+            return;
+        };
+        let mut rng = montecarlo::Rng::new(SIMULATION_SEED);
+        let paths = montecarlo::gbm_paths(&self.start_env, self.movement.time, SIMULATION_STEPS, self.simulation_paths, &mut rng);
+        let bands = montecarlo::value_bands(pricer, &self.start_env, &self.contract, &paths, self.movement.time, self.vol_event);
+        let premium = pricer.bsm_price_buy(&self.start_env, &self.contract);
+        chart.set_bands(bands, self.simulation_paths)
+            .set_premium(premium.to_f64().unwrap_or(0.01))
+            .set_scale(self.settings.nominal_factor())
+            .set_style(self.settings.chart_style(self.theme.palette()));
+    }
+
+    /// Resimulates the stock price paths (if the profit/loss histogram is open) over the prediction time frame
+    /// and bins the profit/loss of buying the contract and selling it at the end of them
+    fn configure_pnl_histogram(&mut self) {
+        let pricer = self.pricer();
+        let Some(chart) = &mut self.pnl_histogram else {
+            return;
+        };
+        // Only the end of each path is priced, which a single step of geometric brownian motion reaches exactly
+        let mut rng = montecarlo::Rng::new(SIMULATION_SEED);
+        let paths = montecarlo::gbm_paths(&self.start_env, self.movement.time, 1, self.simulation_paths, &mut rng);
+        let premium = pricer.bsm_price_buy(&self.start_env, &self.contract).to_f64().unwrap_or(0.01);
+        let mut outcomes: Vec<f64> = montecarlo::final_values(pricer, &self.start_env, &self.contract, &paths, self.movement.time, self.vol_event)
+            .into_iter()
+            .map(|value| value - premium)
+            .collect();
+        let stats = PnlStats::of(&mut outcomes);
+        chart.set_outcomes(outcomes, stats.mean, stats.percentiles)
+            .set_scale(self.settings.nominal_factor())
+            .set_style(self.settings.chart_style(self.theme.palette()));
+    }
+
+    /// Simulates delta hedging the contract (if the delta hedge is opened) over paths of the prediction time frame
+    fn configure_hedge(&mut self) {
+        self.hedge_stats = None;
+        let Some(rebalance) = self.hedge_rebalance.filter(|_| self.calculated) else {
+            return;
+        };
+        let mut rng = montecarlo::Rng::new(SIMULATION_SEED);
+        let steps = rebalance.steps_over(self.movement.time);
+        let paths = montecarlo::gbm_paths(&self.start_env, self.movement.time, steps, HEDGE_PATHS, &mut rng);
+        let (mut hedged, mut unhedged) = montecarlo::delta_hedge(
+            self.pricer(), &self.start_env, &self.contract, &paths, self.movement.time, self.vol_event
+        );
+        self.hedge_stats = Some((PnlStats::of(&mut hedged), PnlStats::of(&mut unhedged)));
+    }
+
+    /// Table of the profit/loss per share of holding the contract over the simulated paths with and without
+    /// a delta hedge. Header row first. None unless computed.
+    fn hedge_text_block(&self) -> Option<[[String; 3]; 6]> {
+        let (hedged, unhedged) = self.hedge_stats.as_ref()?;
+        let dp = self.settings.precision.prices;
+        let row = |name: &str, value: fn(&PnlStats) -> f64| {
+            [String::from(name), format!("{:.*}", dp, value(hedged)), format!("{:.*}", dp, value(unhedged))]
+        };
+        return Some([
+            [String::from("P&L/share"), String::from("Hedged"), String::from("Unhedged")],
+            row("Mean", |stats| stats.mean),
+            row("Std dev", |stats| stats.std_dev),
+            row("5th pct", |stats| stats.percentiles[0]),
+            row("Median", |stats| stats.percentiles[2]),
+            row("95th pct", |stats| stats.percentiles[4]),
+        ]);
+    }
+
+    /// Recomputes the stress grid (if open) for the current contract, shocking the stock price and volatility
+    /// at the prediction end
+    fn configure_stress_grid(&mut self) {
+        let (pricer, exit_env) = (self.pricer(), self.exit_environment());
+        let Some(grid) = &mut self.stress_grid else {
+            return;
+        };
+        let entry = pricer.bsm_price_buy(&self.start_env, &self.contract).to_f64().unwrap_or(0.01);
+        let (end_env, end_contract) = self.movement.apply(exit_env, self.contract.clone());
+        grid.set_style(self.settings.chart_style(self.theme.palette()))
+            .set_decimals(self.settings.precision.prices, self.settings.precision.roi)
+            .set_rows(|spot_shocks, vol_shock| {
+                let env = Environment { vol: end_env.vol * (1.0 + vol_shock), ..end_env.clone() };
+                // Prices are homogeneous in the stock and strike prices, so shocking the stock price by a factor is
+                // the same as dividing the strike by it and scaling the price back up. This prices a row as a batch.
+                let contracts: Vec<Contract> = spot_shocks.iter()
+                    .map(|shock| Contract { strike: end_contract.strike / (1.0 + shock), ..end_contract.clone() })
+                    .collect();
+                return pricer.bsm_price_batch(&env, &contracts).into_iter().zip(spot_shocks)
+                    .map(|(price, shock)| {
+                        let value = price * (1.0 + shock);
+                        (value, value / entry)
+                    })
+                    .collect();
+            });
+    }
+
+    /// Recomputes the heatmap (if open) over the strike and expiry ranges for the current scenario
+    fn configure_heatmap(&mut self) {
+        let (pricer, end_env) = (self.pricer(), self.exit_environment());
+        let Some(heatmap) = &mut self.heatmap else {
+            return;
+        };
+        let (start_env, movement) = (self.start_env.clone(), self.movement.clone());
+        heatmap.set_ranges(self.ranges[Adjustables::Strike as usize].clone(), self.ranges[Adjustables::Expiry as usize].clone())
+            .set_marker(self.contract.strike, self.contract.expiry)
+            .set_style(self.settings.chart_style(self.theme.palette()))
+            .set_rows(move |strikes, expiry| {
+                // Contracts expiring before the prediction end can't be sold then
+                if expiry < movement.time {
+                    return vec![f64::NAN; strikes.len()];
+                }
+                let contracts: Vec<Contract> = strikes.iter().map(|&strike| Contract { strike, expiry }).collect();
+                return pricer.roi_practical_batch(&start_env, &end_env, &contracts, &movement);
+            });
+    }
+
+    /// Moves the prediction end time to the given time and updates everything shown for it.
+    /// The answer is repriced at the new time rather than solved again.
+    fn set_decay_time(&mut self, time: f64) {
+        self.movement.time = time;
+        for i in 0..self.sliders.data.len() {
+            if self.sliders.data[i].0 == Adjustables::EndTime {
+                self.configure_slider(i);
+            }
+        }
+        for i in 0..self.charts.data.len() {
+            self.configure_chart(i);
+        }
+        self.configure_heatmap();
+        self.configure_expiry_chart();
+        self.configure_cone_chart();
+        self.configure_stress_grid();
+        self.configure_simulation_chart();
+        self.configure_pnl_histogram();
+        self.configure_hedge();
+
+        let (pricer, end_env) = (self.pricer(), self.exit_environment());
+        if let Some(decay) = self.decay.as_mut() {
+            let (buy, sell) = pricer.buy_sell_prices_practical(&self.start_env, &end_env, &self.answers.1, &self.movement);
+            decay.sell = sell.to_f64().unwrap_or(0.0);
+            decay.roi = (sell / buy).to_f64().unwrap_or(0.0);
+        }
+    }
+
+    /// Generates a single variable function of the current scenario for the payoff graphs. See Parameterisation.
+    fn get_parameterisation(&self, out: PayoffYAxis, var: Adjustables) -> ChartFunc {
+        return Parameterisation {
+            pricer: self.pricer(),
+            out,
+            var,
+            start_env: self.start_env.clone(),
+            end_env: self.end_env.clone(),
+            contract: self.contract.clone(),
+            movement: self.movement.clone(),
+            event: self.vol_event,
+        }.into_func();
+    }
+
+    /// Pricer of the answer's option type, which everything shown for the answer is priced with
+    fn pricer(&self) -> OptionType {
+        return OptionType::from_is_call(self.answers.0);
+    }
+
+    /// The undo group of a message, or None if handling it doesn't change the scenario
+    fn edit_group(message: &Message) -> Option<EditGroup> {
+        match message {
+            Message::NumberInputMessage(i, _) => Some(EditGroup::Input(*i)),
+            Message::HorizonDate(_) => Some(EditGroup::HorizonDate),
+            Message::EventInputMessage(i, _) => Some(EditGroup::EventInput(*i)),
+            Message::AnswerInputMessage(i, _) => Some(EditGroup::AnswerInput(*i)),
+            Message::NotesEdit(action) if action.is_edit() => Some(EditGroup::Notes),
+            Message::Sliders(DeletableListMessage::Item(i, _)) => Some(EditGroup::Slider(*i)),
+            Message::Charts(DeletableListMessage::Item(_, PayoffChartMessage::Clicked(_))) => Some(EditGroup::Step),
+            Message::Charts(DeletableListMessage::Item(..)) => None,
+            Message::Calculate
+            | Message::Sliders(_)
+            | Message::Charts(_)
+            | Message::SliderAdd
+            | Message::ChartAdd
+            | Message::UseDates(_)
+            | Message::EventToggle(_)
+            | Message::UnderlyingSelect(_)
+            | Message::VolUnitSelect(_)
+            | Message::TimeUnitSelect(_)
+            | Message::DayCountSelect(_)
+            | Message::PresetSelect(_)
+            | Message::TickerDefaultsSelect(_)
+            | Message::LayoutSelect(_)
+            | Message::UseVolEstimate(_)
+            | Message::ChooseCandidate(_)
+            | Message::FillRiskFree
+            | Message::ResetSession
+            | Message::ScenarioLoaded(Ok(Some(_))) => Some(EditGroup::Step),
+            #[cfg(feature = "online")]
+            Message::QuoteFetched(Ok(_)) | Message::YieldsFetched(_) => Some(EditGroup::Step),
+            #[cfg(target_arch = "wasm32")]
+            Message::Embed(_, embed::EmbedRequest::SetInputs { .. }) => Some(EditGroup::Step),
+            _ => None,
+        }
+    }
+
+    /// Stores the settings, reporting in the file status if that fails
+    fn save_settings(&mut self) {
+        if let Err(e) = self.settings.store() {
+            self.file_status = format!("Saving settings failed: {}", e);
+        }
+    }
+
+    /// Snapshots the current state onto the undo history before an edit is applied
+    fn record_edit(&mut self, group: EditGroup) {
+        if group != EditGroup::Step && self.last_edit == Some(group) {
+            return;
+        }
+        self.last_edit = Some(group);
+        self.undo_history.push(self.to_scenario());
+        if self.undo_history.len() > UNDO_LIMIT {
+            self.undo_history.remove(0);
+        }
+        self.redo_history.clear();
+    }
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        if let Some(group) = Self::edit_group(&message) {
+            self.record_edit(group);
+            if self.settings.live {
+                self.live_pending = Some(Instant::now());
+            }
+        }
+        match message {
+            Message::Undo => {
+                if let Some(scenario) = self.undo_history.pop() {
+                    self.redo_history.push(self.to_scenario());
+                    self.apply_scenario(scenario);
+                    self.last_edit = None;
+                }
+                return Task::none();
+            }
+            Message::Redo => {
+                if let Some(scenario) = self.redo_history.pop() {
+                    self.undo_history.push(self.to_scenario());
+                    self.apply_scenario(scenario);
+                    self.last_edit = None;
+                }
+                return Task::none();
+            }
+            Message::Calculate => {
+                // Validate and extract inputs
+                let (Some((env, pred)), Ok(event)) = (self.extract_env_and_pred(), self.extract_vol_event()) else {
+                    return Task::none();
+                };
+                let candidates = solve_candidates(self.underlying, &env, &exit_env(&env, &pred, event), &pred);
+                self.apply_solution(env, pred, event, candidates);
+                return Task::none();
+            }
+            Message::MultiplierInput(number_msg) => {
+                self.multiplier.update(number_msg);
+                let multiplier = self.multiplier.get_value();
+                if multiplier.is_nan() || self.multiplier.value_outside_range() {
+                    return Task::none();
+                }
+                self.settings.multiplier = multiplier;
+                self.save_settings();
+                for i in 0..self.charts.data.len() {
+                    self.configure_chart(i);
+                }
+                self.configure_expiry_chart();
+                self.configure_simulation_chart();
+                self.configure_pnl_histogram();
+                return Task::none();
+            }
+            Message::NominalScaleSelect(scale) => {
+                self.settings.nominal_scale = scale;
+                self.save_settings();
+                for i in 0..self.charts.data.len() {
+                    self.configure_chart(i);
+                }
+                self.configure_expiry_chart();
+                self.configure_simulation_chart();
+                self.configure_pnl_histogram();
+                return Task::none();
+            }
+            Message::PremiumQuoteSelect(quote) => {
+                self.settings.premium_quote = quote;
+                self.save_settings();
+                return Task::none();
+            }
+            Message::ChartColumnsSelect(columns) => {
+                self.settings.chart_columns = columns;
+                self.save_settings();
+                self.configure_stale_charts(false);
+                return Task::none();
+            }
+            Message::ChartResolutionSelect(resolution) => {
+                self.settings.chart_resolution = resolution;
+                self.save_settings();
+                for (_, chart) in self.charts.data.iter_mut() {
+                    chart.set_default_resolution(resolution.0);
+                }
+                return Task::none();
+            }
+            Message::LiveToggle(live) => {
+                self.settings.live = live;
+                self.save_settings();
+                self.live_pending = live.then(Instant::now);
+                return Task::none();
+            }
+            Message::LiveTick => {
+                let Some(pending) = self.live_pending else {
+                    return Task::none();
+                };
+                if pending.elapsed() < LIVE_DEBOUNCE {
+                    return Task::none();
+                }
+                self.live_pending = None;
+                let (Some((env, pred)), Ok(event)) = (self.extract_env_and_pred(), self.extract_vol_event()) else {
+                    return Task::none();
+                };
+                if !self.is_stale() && self.calculated {
+                    return Task::none();
+                }
+                // Run off the UI thread so typing stays responsive
+                let underlying = self.underlying;
+                return Task::perform(async move {
+                    let candidates = solve_candidates(underlying, &env, &exit_env(&env, &pred, event), &pred);
+                    (env, pred, event, Box::new(candidates))
+                }, |(env, pred, event, candidates)| Message::LiveSolved(env, pred, event, candidates));
+            }
+            Message::DecayPlay => {
+                if let Some(decay) = self.decay.take() {
+                    self.set_decay_time(decay.original_time);
+                } else if self.calculated {
+                    self.decay = Some(DecayAnimation {
+                        started: Instant::now(),
+                        original_time: self.movement.time,
+                        sell: self.answers.3,
+                        roi: self.answers.4,
+                    });
+                    self.set_decay_time(0.0);
+                }
+                return Task::none();
+            }
+            Message::DecayTick => {
+                let Some(decay) = self.decay.as_ref() else {
+                    return Task::none();
+                };
+                let progress = decay.started.elapsed().as_secs_f64() / DECAY_ANIMATION_LENGTH.as_secs_f64();
+                // Finish by returning to the predicted end time
+                if progress >= 1.0 {
+                    let original_time = decay.original_time;
+                    self.decay = None;
+                    self.set_decay_time(original_time);
+                    return Task::none();
+                }
+                self.set_decay_time(progress * self.contract.expiry);
+                return Task::none();
+            }
+            Message::ChooseCandidate(is_call) => {
+                if is_call != self.answers.0 && let Some(alternative) = self.alternative.take() {
+                    self.alternative = Some(std::mem::replace(&mut self.answers, alternative));
+                    std::mem::swap(&mut self.diagnostics, &mut self.alternative_diagnostics);
+                    self.configure_to_answer();
+                }
+                return Task::none();
+            }
+            Message::LiveSolved(env, pred, event, candidates) => {
+                // Discard if the inputs changed again while solving. A newer recalculation is pending.
+                if self.extract_env_and_pred() == Some((env.clone(), pred.clone())) && self.extract_vol_event() == Ok(event) {
+                    self.apply_solution(env, pred, event, *candidates);
+                }
+                return Task::none();
+            }
+            Message::NumberInputMessage(i, number_msg) => {
+                self.param[i].update(number_msg);
+                return Task::none();
+            }
+            Message::AnswerInputMessage(i, number_msg) => {
+                self.answer_inputs[i].update(number_msg);
+                let value = self.answer_inputs[i].get_value();
+                if !self.calculated || value.is_nan() || self.answer_inputs[i].value_outside_range() {
+                    return Task::none();
+                }
+                // Evaluate the entered contract in place of the optimiser's, priced like a calculated answer
+                // (without any slider overrides)
+                if i == 0 {
+                    self.answers.1.strike = value;
+                } else {
+                    self.answers.1.expiry = value;
+                }
+                let end_env = exit_env(&self.start_env, &self.prediction, self.vol_event);
+                let (buy, sell) = self.pricer().buy_sell_prices_practical(&self.start_env, &end_env, &self.answers.1, &self.prediction);
+                self.answers.2 = buy.to_f64().unwrap_or(0.0);
+                self.answers.3 = sell.to_f64().unwrap_or(0.0);
+                self.answers.4 = (sell / buy).to_f64().unwrap_or(0.0);
+                self.configure_to_answer();
+                return Task::none();
+            }
+            Message::NotesEdit(action) => {
+                self.notes.perform(action);
+                return Task::none();
+            }
+            Message::EventToggle(use_event) => {
+                self.use_event = use_event;
+                return Task::none();
+            }
+            Message::UnderlyingSelect(underlying) => {
+                self.underlying = underlying;
+                return Task::none();
+            }
+            Message::EventInputMessage(i, number_msg) => {
+                self.event_inputs[i].update(number_msg);
+                return Task::none();
+            }
+            Message::VolUnitSelect(unit) => {
+                // Keep the same volatility, just expressed in the new unit
+                let vol = self.param_value(1);
+                let event_vol = self.vol_unit.decimal_of(self.event_inputs[1].get_value());
+                self.vol_unit = unit;
+                self.configure_param_inputs();
+                if !vol.is_nan() {
+                    self.set_param_value(1, vol);
+                }
+                if !event_vol.is_nan() {
+                    self.event_inputs[1].set_value(unit.expressed(event_vol));
+                }
+                return Task::none();
+            }
+            Message::TimeUnitSelect(unit) => {
+                let duration = self.param_value(5);
+                let event_time = self.time_unit.years_of(self.event_inputs[0].get_value());
+                self.time_unit = unit;
+                self.configure_param_inputs();
+                if !duration.is_nan() {
+                    self.set_param_value(5, duration);
+                }
+                if !event_time.is_nan() {
+                    self.event_inputs[0].set_value(unit.expressed(event_time));
+                }
+                return Task::none();
+            }
+            Message::UseDates(use_dates) => {
+                self.use_dates = use_dates;
+                // Carry over any duration already entered as a starting point for the date
+                let duration = self.param_value(5);
+                if use_dates && self.horizon_date.get_date().is_none()
+                    && let Some(date) = self.day_count.date_after(calendar::today(), duration) {
+                    self.horizon_date.set_date(date);
+                }
+                return Task::none();
+            }
+            Message::HorizonDate(date_msg) => {
+                self.horizon_date.update(date_msg);
+                return Task::none();
+            }
+            Message::DayCountSelect(day_count) => {
+                self.day_count = day_count;
+                return Task::none();
+            }
+            #[cfg(feature = "online")]
+            Message::TickerEdit(ticker) => {
+                self.ticker = ticker;
+                return Task::none();
+            }
+            #[cfg(feature = "online")]
+            Message::FetchQuote => {
+                self.quote_status = String::from("Fetching...");
+                return Task::perform(quotes::fetch_quote(self.ticker.clone()), Message::QuoteFetched);
+            }
+            #[cfg(feature = "online")]
+            Message::QuoteFetched(result) => {
+                match result {
+                    Ok(quote) => {
+                        self.set_param_value(0, quote.price);
+                        self.set_param_value(1, quote.vol_estimate);
+                        self.set_param_value(3, quote.div_yield);
+                        self.quote_status = format!("Filled in {} (volatility is a 1Y historical estimate)", quote.ticker);
+                    }
+                    Err(e) => {
+                        self.quote_status = e;
+                    }
+                }
+                return Task::none();
+            }
+            #[cfg(feature = "broker")]
+            Message::FetchChain => {
+                match chain::TradierSandbox::from_env() {
+                    Ok(provider) => {
+                        self.chain_status = format!("Fetching from {}...", provider.name());
+                        return Task::perform(provider.fetch_chain(self.ticker.clone()), Message::ChainFetched);
+                    }
+                    Err(e) => {
+                        self.chain_status = e;
+                        return Task::none();
+                    }
+                }
+            }
+            #[cfg(feature = "broker")]
+            Message::ChainFetched(result) => {
+                match result {
+                    Ok(quotes) => {
+                        self.chain_smile = chain::fit_smile(&quotes, calendar::today(), &self.start_env);
+                        self.chain_quotes = quotes;
+                        self.rank_chain();
+                    }
+                    Err(e) => {
+                        self.chain_status = e;
+                    }
+                }
+                return Task::none();
+            }
+            #[cfg(feature = "broker")]
+            Message::UseSmile(use_smile) => {
+                self.use_smile = use_smile;
+                self.rank_chain();
+                return Task::none();
+            }
+            Message::Sliders(list_message) => {
+                let before = Adjustables::everything().map(|var| self.get_adjustable(var));
+                let ranges_before = self.ranges.clone();
+                self.sliders.update(list_message.clone());
+
+                match list_message {
+                    // Update appropriate value from parameter slider
+                    DeletableListMessage::Item(i, _) => {
+                        let var = self.sliders.data[i].0;
+                        let val = self.sliders.data[i].1.get_value();
+                        self.set_adjustable(var, val);
+                    }
+                    DeletableListMessage::Delete(_) => {
+                        // Only the latest deletion can be undone
+                        self.charts.forget_deleted();
+                        self.delete_toast = Some(Instant::now());
+                        return Task::none();
+                    }
+                    // The restored slider's value is applied along with the constraints below
+                    DeletableListMessage::UndoDelete => {
+                        self.delete_toast = None;
+                    }
+                    DeletableListMessage::Clear => {
+                        if self.charts.deleted().is_none() {
+                            self.delete_toast = None;
+                        }
+                        return Task::none();
+                    }
+                }
+
+                // Update valid ranges the sliders can take up
+                self.enforce_constraints();
+
+                // Only the charts computed from a moved variable are computed again. The rest just follow the sliders.
+                let moved: Vec<Adjustables> = Adjustables::everything().into_iter()
+                    .filter(|&var| self.get_adjustable(var) != before[var as usize])
+                    .collect();
+                let rescaled = |var: Adjustables| self.ranges[var as usize] != ranges_before[var as usize];
+                let heatmap_rescaled = rescaled(Adjustables::Strike) || rescaled(Adjustables::Expiry);
+                let expiry_rescaled = rescaled(Adjustables::EndPrice);
+
+                // Charts are drawn coarser while a slider is dragged to keep up, then in full once let go
+                let dragging = matches!(list_message, DeletableListMessage::Item(_, CustomSliderMessage::Slide(_)));
+                for i in 0..self.charts.data.len() {
+                    let id = self.charts.data[i].0;
+                    if moved.iter().any(|&var| Self::chart_depends_on(id, var)) {
+                        self.charts.data[i].1.set_draft(dragging);
+                        self.configure_chart(i);
+                    } else {
+                        if !dragging {
+                            self.charts.data[i].1.set_draft(false);
+                        }
+                        self.position_chart(i);
+                    }
+                }
+                if heatmap_rescaled || moved.iter().any(|&var| Self::heatmap_depends_on(var)) {
+                    self.configure_heatmap();
+                } else if let Some(heatmap) = &mut self.heatmap {
+                    heatmap.set_marker(self.contract.strike, self.contract.expiry);
+                }
+                if expiry_rescaled || moved.iter().any(|&var| Self::expiry_chart_depends_on(var)) {
+                    self.configure_expiry_chart();
+                }
+                if moved.iter().any(|&var| Self::cone_chart_depends_on(var)) {
+                    self.configure_cone_chart();
+                }
+                // Depends on every variable, and is quick to compute
+                self.configure_stress_grid();
+                if moved.iter().any(|&var| Self::simulation_depends_on(var)) {
+                    self.configure_simulation_chart();
+                    self.configure_pnl_histogram();
+                    self.configure_hedge();
+                }
+                // Sampled off the UI thread so the slider keeps moving smoothly
+                return self.sample_charts_in_background();
+            }
+            Message::SliderSelect(variable) => {
+                self.slider_add_select = Some(variable);
+                return Task::none();
+            }
+            Message::SliderAdd => {
+                if let Some(variable) = self.slider_add_select {
+                    let mut slider = Self::create_slider(variable, self.settings.precision.inputs);
+                    slider.set_allowed_range(variable.lowest()..=f64::MAX);
+                    self.sliders.unique_push(variable, slider);
+                    self.configure_slider(self.sliders.data.len()-1);
+                }
+                return Task::none();
+            }
+            Message::ChartXSelect(variable) => {
+                self.chart_x_select = Some(variable);
+                return Task::none();
+            }
+            Message::ChartYSelect(y) => {
+                self.chart_y_select = Some(y);
+                return Task::none();
+            }
+            Message::ChartAdd => {
+                if let (Some(y_axis), Some(x_axis)) = (self.chart_y_select, self.chart_x_select) {
+                    let chart = self.create_chart(y_axis, x_axis);
+                    self.charts.unique_push((y_axis, x_axis), chart);
+                    if let Some(i) = self.charts.scan_ID(&(y_axis, x_axis)) {
+                        self.configure_chart(i);
+                    }
+                }
+                return Task::none();
+            }
+            Message::HeatmapToggle(open) => {
+                self.heatmap = open.then(|| HeatmapChart::new(
+                    String::from("ROI for different Strike and Expiry"),
+                    format!("{}", Adjustables::Strike),
+                    format!("{}", Adjustables::Expiry),
+                ));
+                if self.calculated {
+                    self.configure_heatmap();
+                }
+                return Task::none();
+            }
+            Message::ExpiryChartToggle(open) => {
+                self.expiry_chart = open.then(ExpiryChart::default);
+                if self.calculated {
+                    self.configure_expiry_chart();
+                }
+                return Task::none();
+            }
+            Message::ConeChartToggle(open) => {
+                self.cone_chart = open.then(ConeChart::default);
+                if self.calculated {
+                    self.configure_cone_chart();
+                }
+                return Task::none();
+            }
+            Message::StressGridToggle(open) => {
+                self.stress_grid = open.then(StressGrid::default);
+                if self.calculated {
+                    self.configure_stress_grid();
+                }
+                return Task::none();
+            }
+            Message::SimulationToggle(open) => {
+                self.simulation_chart = open.then(SimulationChart::default);
+                if self.calculated {
+                    self.configure_simulation_chart();
+                }
+                return Task::none();
+            }
+            Message::PnlHistogramToggle(open) => {
+                self.pnl_histogram = open.then(HistogramChart::default);
+                if self.calculated {
+                    self.configure_pnl_histogram();
+                }
+                return Task::none();
+            }
+            Message::HedgeToggle(open) => {
+                self.hedge_rebalance = open.then(Rebalance::default);
+                self.configure_hedge();
+                return Task::none();
+            }
+            Message::HedgeRebalanceSelect(rebalance) => {
+                self.hedge_rebalance = Some(rebalance);
+                self.configure_hedge();
+                return Task::none();
+            }
+            Message::LsmPathsSelect(paths) => {
+                self.lsm_paths = paths;
+                return Task::none();
+            }
+            Message::LsmDegreeSelect(degree) => {
+                self.lsm_degree = degree;
+                return Task::none();
+            }
+            Message::LsmRun => {
+                self.lsm_run += 1;
+                self.lsm_progress = Some(0.0);
+                let run = self.lsm_run;
+                let (pricer, env, contract) = (self.pricer(), self.start_env.clone(), self.answers.1.clone());
+                let (paths, degree) = (self.lsm_paths, self.lsm_degree);
+                // Worked through a date at a time in the background, reporting progress after each
+                let valuation = iced::task::sipper(async move |mut progress| {
+                    let mut rng = montecarlo::Rng::new(SIMULATION_SEED);
+                    let paths = montecarlo::gbm_paths(&env, contract.expiry, montecarlo::LSM_EXERCISE_DATES, paths, &mut rng);
+                    let mut valuation = montecarlo::LongstaffSchwartz::new(pricer, &env, &contract, paths, degree);
+                    while !valuation.step() {
+                        progress.send(valuation.progress()).await;
+                    }
+                    Box::new((contract, env, valuation.estimate()))
+                });
+                return Task::sip(valuation, move |progress| Message::LsmProgress(run, progress), move |estimate| Message::LsmDone(run, estimate));
+            }
+            Message::LsmProgress(run, progress) => {
+                if run == self.lsm_run {
+                    self.lsm_progress = Some(progress);
+                }
+                return Task::none();
+            }
+            Message::LsmDone(run, estimate) => {
+                if run == self.lsm_run {
+                    self.lsm_progress = None;
+                    self.lsm_estimate = Some(*estimate);
+                }
+                return Task::none();
+            }
+            Message::SimulationPathsSelect(paths) => {
+                self.simulation_paths = paths;
+                if self.calculated {
+                    self.configure_simulation_chart();
+                    self.configure_pnl_histogram();
+                }
+                return Task::none();
+            }
+            Message::Charts(list_msg) => {
+                // Exporting needs to happen outside of the chart as saving is asynchronous
+                if let DeletableListMessage::Item(i, PayoffChartMessage::Export(kind)) = list_msg {
+                    let chart = &self.charts.data[i].1;
+                    let contents: Result<Vec<u8>, String> = match kind {
+                        #[cfg(not(target_arch = "wasm32"))]
+                        FileKind::Png => chart.to_png(),
+                        FileKind::Csv => Ok(chart.to_csv().into_bytes()),
+                        FileKind::Svg => Ok(chart.to_svg().into_bytes()),
+                        _ => Err(String::from("Unsupported chart export format")),
+                    };
+                    match contents {
+                        Ok(contents) => {
+                            self.file_status = String::new();
+                            return Task::perform(export::save_file(chart.get_title().to_string(), kind, contents), Message::Exported);
+                        }
+                        Err(e) => {
+                            self.file_status = format!("Export failed: {}", e);
+                            return Task::none();
+                        }
+                    }
+                }
+                // Clicking a chart sets its x-axis variable to the clicked value
+                if let DeletableListMessage::Item(i, PayoffChartMessage::Clicked(x)) = list_msg {
+                    let var = self.charts.data[i].0.1;
+                    let range = self.create_valid_range(var);
+                    let scale = 10f64.powi(self.settings.precision.inputs as i32);
+                    self.set_adjustable(var, ((x * scale).round() / scale).clamp(*range.start(), *range.end()));
+
+                    for i in 0..self.sliders.data.len() {
+                        if self.sliders.data[i].0 == var {
+                            self.configure_slider(i);
+                        }
+                    }
+                    self.enforce_constraints();
+                    for i in 0..self.charts.data.len() {
+                        self.configure_chart(i);
+                    }
+                    self.configure_heatmap();
+                    self.configure_expiry_chart();
+                    self.configure_cone_chart();
+                    self.configure_stress_grid();
+                    self.configure_simulation_chart();
+                    self.configure_pnl_histogram();
+                    self.configure_hedge();
+                    return Task::none();
+                }
+                if let DeletableListMessage::Delete(_) = list_msg {
+                    // Only the latest deletion can be undone
+                    self.sliders.forget_deleted();
+                    self.delete_toast = Some(Instant::now());
+                }
+                if let DeletableListMessage::Clear = list_msg && self.sliders.deleted().is_none() {
+                    self.delete_toast = None;
+                }
+                if let DeletableListMessage::UndoDelete = list_msg {
+                    self.delete_toast = None;
+                    let restored = self.charts.deleted().copied();
+                    self.charts.update(list_msg);
+                    // The restored chart may have missed changes to the sliders, and its sampled points, while deleted
+                    if let Some(i) = restored.and_then(|id| self.charts.scan_ID(&id)) {
+                        self.charts.data[i].1.stop_sampling();
+                        self.configure_chart(i);
+                    }
+                    return Task::none();
+                }
+                // Only one chart can be maximized at a time
+                if let DeletableListMessage::Item(i, PayoffChartMessage::ToggleMaximize) = list_msg {
+                    for (j, (_, chart)) in self.charts.data.iter_mut().enumerate() {
+                        if j != i {
+                            chart.set_maximized(false);
+                        }
+                    }
+                }
+                self.charts.update(list_msg);
+                // Deleting or restoring charts can bring others into view
+                self.configure_stale_charts(false);
+                return Task::none();
+            }
+            Message::EscapePressed => {
+                for (_, chart) in self.charts.data.iter_mut() {
+                    chart.set_maximized(false);
+                }
+                self.configure_stale_charts(false);
+                return Task::none();
+            }
+            Message::ChartsScrolled(viewport) => {
+                self.chart_viewport = Some(viewport);
+                self.configure_stale_charts(false);
+                return Task::none();
+            }
+            Message::ChartSampled(id, generation, samples) => {
+                if let Some(i) = self.charts.scan_ID(&id) {
+                    self.charts.data[i].1.set_samples(generation, samples);
+                }
+                // Sample again if the chart changed while these were sampled
+                return self.sample_charts_in_background();
+            }
+            Message::DeleteToastTick => {
+                if self.delete_toast.is_some_and(|deleted| deleted.elapsed() >= UNDO_DELETE_TIMEOUT) {
+                    return self.update(Message::DeleteToastDismiss);
+                }
+                return Task::none();
+            }
+            Message::DeleteToastDismiss => {
+                self.delete_toast = None;
+                self.sliders.forget_deleted();
+                self.charts.forget_deleted();
+                return Task::none();
+            }
+            Message::TabPressed => {
+                return operation::focus_next();
+            }
+            Message::StepPressed(steps) => {
+                // Only the focused input (if any) is stepped
+                let mut tasks: Vec<Task<Message>> = (0..self.param.len())
+                    .map(|i| self.param[i].step_if_focused(steps).map(move |number_msg| Message::NumberInputMessage(i, number_msg)))
+                    .collect();
+                tasks.push(self.multiplier.step_if_focused(steps).map(Message::MultiplierInput));
+                tasks.extend((0..self.sliders.data.len()).map(|i| {
+                    self.sliders.data[i].1.nudge_if_focused(steps)
+                        .map(move |slider_msg| Message::Sliders(DeletableListMessage::Item(i, slider_msg)))
+                }));
+                return Task::batch(tasks);
+            }
+            Message::Export => {
+                self.file_status = String::new();
+                self.configure_stale_charts(true);
+                let contents = self.export_csv().into_bytes();
+                return Task::perform(export::save_file(String::from("which_option"), FileKind::Csv, contents), Message::Exported);
+            }
+            Message::ExportReport => {
+                self.file_status = String::new();
+                self.configure_stale_charts(true);
+                let contents = self.export_report().into_bytes();
+                return Task::perform(export::save_file(String::from("which_option_report"), FileKind::Html, contents), Message::Exported);
+            }
+            Message::ExportSpreadsheet => {
+                self.file_status = String::new();
+                self.configure_stale_charts(true);
+                match self.export_xlsx() {
+                    Ok(contents) => {
+                        return Task::perform(export::save_file(String::from("which_option"), FileKind::Xlsx, contents), Message::Exported);
+                    }
+                    Err(e) => {
+                        self.file_status = format!("Export failed: {}", e);
+                        return Task::none();
+                    }
+                }
+            }
+            Message::SaveScenario => {
+                self.file_status = String::new();
+                let contents = self.to_scenario().to_json().into_bytes();
+                return Task::perform(export::save_file(String::from("scenario"), FileKind::Json, contents), Message::Exported);
+            }
+            Message::LoadScenario => {
+                self.file_status = String::new();
+                return Task::perform(export::open_file(FileKind::Json), Message::ScenarioLoaded);
+            }
+            Message::ResetSession => {
+                let result = storage::platform_storage().remove(AUTOSAVE_KEY);
+                // The window keeps its size, and the undo history so the reset can be undone
+                let window_size = self.window_size;
+                let (undo_history, redo_history) = (std::mem::take(&mut self.undo_history), std::mem::take(&mut self.redo_history));
+                *self = Self::default();
+                self.window_size = window_size;
+                (self.undo_history, self.redo_history) = (undo_history, redo_history);
+                self.apply_touch();
+                if let Err(e) = result {
+                    self.file_status = format!("Reset failed: {}", e);
+                }
+                return Task::none();
+            }
+            #[cfg(target_arch = "wasm32")]
+            Message::Embed(origin, request) => {
+                let task = match request {
+                    embed::EmbedRequest::SetInputs { inputs } => {
+                        for (i, value) in inputs.values().into_iter().enumerate() {
+                            if let Some(value) = value {
+                                self.set_param_value(i, value);
+                            }
+                        }
+                        // The prediction end is given as a duration
+                        if inputs.prediction_time.is_some() {
+                            self.use_dates = false;
+                        }
+                        return Task::none();
+                    }
+                    embed::EmbedRequest::Calculate => self.update(Message::Calculate),
+                    embed::EmbedRequest::GetResult => Task::none(),
+                };
+                if let Err(e) = embed::post_result(&self.embed_result(), &origin) {
+                    self.file_status = format!("Replying to the embedding page failed: {}", e);
+                }
+                return task;
+            }
+            #[cfg(target_arch = "wasm32")]
+            Message::ShareLink => {
+                match share::write_url_fragment(&self.to_shared().to_fragment()) {
+                    Some(url) => {
+                        self.file_status = String::from("Link copied to clipboard");
+                        return iced::clipboard::write(url);
+                    }
+                    None => {
+                        self.file_status = String::from("Failed to create link");
+                        return Task::none();
+                    }
+                }
+            }
+            Message::ThemeSelect(theme) => {
+                self.settings.theme = theme.to_string();
+                self.save_settings();
+                self.theme = theme;
+                self.restyle_charts();
+                return Task::none();
+            }
+            Message::ChartPaletteSelect(palette) => {
+                self.settings.chart_palette = palette;
+                self.save_settings();
+                self.restyle_charts();
+                return Task::none();
+            }
+            Message::ScaleSelect(scale) => {
+                self.settings.scale = scale;
+                self.save_settings();
+                return Task::none();
+            }
+            Message::SettingsOpen(open) => {
+                self.settings_open = open;
+                return Task::none();
+            }
+            Message::CompanyOptionOpen(open) => {
+                self.company_option_open = open;
+                self.value_company_option();
+                return Task::none();
+            }
+            Message::CompanyOptionSelect(kind) => {
+                self.company_option = kind;
+                self.value_company_option();
+                return Task::none();
+            }
+            Message::EsoInputMessage(i, number_msg) => {
+                self.eso_inputs[i].update(number_msg);
+                self.value_company_option();
+                return Task::none();
+            }
+            Message::WarrantInputMessage(i, number_msg) => {
+                self.warrant_inputs[i].update(number_msg);
+                self.value_company_option();
+                return Task::none();
+            }
+            Message::WindowResized(size) => {
+                let was_compact = self.is_compact();
+                self.window_size = size;
+                if self.is_compact() != was_compact {
+                    self.apply_touch();
+                }
+                return Task::none();
+            }
+            Message::ChartSheetToggle(open) => {
+                self.chart_sheet_open = open;
+                return Task::none();
+            }
+            Message::PanelResize(dx) => {
+                self.settings.panel_width = (self.settings.panel_width + dx).clamp(*PANEL_WIDTH_RANGE.start(), *PANEL_WIDTH_RANGE.end());
+                return Task::none();
+            }
+            Message::PanelResized => {
+                self.save_settings();
+                return Task::none();
+            }
+            Message::DefaultVolUnitSelect(unit) => {
+                self.settings.vol_unit = unit;
+                self.save_settings();
+                return Task::none();
+            }
+            Message::DefaultTimeUnitSelect(unit) => {
+                self.settings.time_unit = unit;
+                self.save_settings();
+                return Task::none();
+            }
+            Message::RangeSpanSelect(span) => {
+                self.settings.range_span = span;
+                self.save_settings();
+                return Task::none();
+            }
+            Message::PrecisionSelect(precision) => {
+                self.settings.precision = precision;
+                self.save_settings();
+                // Inputs take the new precision from the next edit
+                let dp = precision.inputs;
+                self.multiplier = self.multiplier.clone().set_precision(dp);
+                for input in self.param.iter_mut().chain(&mut self.event_inputs).chain(&mut self.answer_inputs) {
+                    *input = input.clone().set_precision(dp);
+                }
+                for (_, slider) in self.sliders.data.iter_mut() {
+                    slider.change_precision(dp);
+                }
                 for i in 0..self.charts.data.len() {
-                    self.configure_chart(i);
+                    let (x_dp, y_dp) = self.chart_decimals(self.charts.data[i].0.0);
+                    self.charts.data[i].1.set_decimals(x_dp, y_dp);
+                }
+                if let Some(grid) = &mut self.stress_grid {
+                    grid.set_decimals(precision.prices, precision.roi);
                 }
-
                 return Task::none();
             }
-            Message::NumberInputMessage(i, number_msg) => {
-                self.param[i].update(number_msg);
+            Message::PresetSelect(preset) => {
+                for (i, value) in preset.inputs.into_iter().enumerate() {
+                    self.set_param_value(i, value);
+                }
+                // Presets give the prediction end as a duration
+                self.use_dates = false;
+                self.preset_name = if preset.built_in { String::new() } else { preset.name.clone() };
+                self.preset_select = Some(preset);
                 return Task::none();
             }
-            Message::Sliders(list_message) => {
-                self.sliders.update(list_message.clone());
-
-                // Update appropriate value from parameter slider
-                if let DeletableListMessage::Item(i, _) = list_message {
-                    let var = self.sliders.data[i].0.clone();
-                    let val = self.sliders.data[i].1.get_value();
-                    self.set_adjustable(var, val);
-                } else {
+            Message::PresetNameEdit(name) => {
+                self.preset_name = name;
+                return Task::none();
+            }
+            Message::PresetSave => {
+                let name = self.preset_name.trim().to_string();
+                let Some((env, movement)) = self.extract_env_and_pred() else {
+                    self.file_status = String::from("Fill in every input before saving a preset");
+                    return Task::none();
+                };
+                if name.is_empty() {
+                    self.file_status = String::from("Enter a name for the preset");
                     return Task::none();
                 }
-
-                // Update valid ranges the sliders can take up
-                for i in 0..self.sliders.data.len() {
-                    let var = self.sliders.data[i].0.clone();
-                    let range = self.create_valid_range(var.clone());
-                    self.sliders.data[i].1.set_allowed_range(range);
-                    self.ranges[var as usize] = self.sliders.data[i].1.get_slider_range();
+                let preset = Preset {
+                    name,
+                    inputs: [env.stock, env.vol, env.risk_free, env.div_yield, movement.stock, movement.time],
+                    built_in: false,
+                };
+                // Saving under an existing name replaces that preset
+                let mut all: Vec<Preset> = self.presets.options().iter()
+                    .filter(|p| p.built_in || p.name != preset.name)
+                    .cloned()
+                    .collect();
+                all.push(preset.clone());
+                self.file_status = match presets::store_user(&all) {
+                    Ok(()) => format!("Saved preset \"{}\"", preset.name),
+                    Err(e) => format!("Saving preset failed: {}", e),
+                };
+                self.presets.set_options(all);
+                self.preset_select = Some(preset);
+                return Task::none();
+            }
+            Message::PresetDelete => {
+                let Some(preset) = self.preset_select.take_if(|p| !p.built_in) else {
+                    return Task::none();
+                };
+                let all: Vec<Preset> = self.presets.options().iter()
+                    .filter(|p| *p != &preset)
+                    .cloned()
+                    .collect();
+                self.file_status = match presets::store_user(&all) {
+                    Ok(()) => format!("Deleted preset \"{}\"", preset.name),
+                    Err(e) => format!("Deleting preset failed: {}", e),
+                };
+                self.presets.set_options(all);
+                return Task::none();
+            }
+            Message::TickerDefaultsSelect(defaults) => {
+                // Only the current environment is filled in, the prediction is left as is
+                for (i, value) in defaults.inputs.into_iter().enumerate() {
+                    self.set_param_value(i, value);
                 }
-
-                for i in 0..self.charts.data.len() {
-                    self.configure_chart(i);
+                self.ticker_defaults_name = defaults.ticker.clone();
+                self.ticker_defaults_select = Some(defaults);
+                return Task::none();
+            }
+            Message::TickerDefaultsNameEdit(name) => {
+                self.ticker_defaults_name = name;
+                return Task::none();
+            }
+            Message::TickerDefaultsSave => {
+                let ticker = self.ticker_defaults_name.trim().to_uppercase();
+                if self.param[..4].iter().any(|input| input.get_value().is_nan() || input.value_outside_range()) {
+                    self.file_status = String::from("Fill in the current environment before saving ticker defaults");
+                    return Task::none();
+                }
+                if ticker.is_empty() {
+                    self.file_status = String::from("Enter a ticker for the defaults");
+                    return Task::none();
                 }
+                let defaults = TickerDefaults {
+                    ticker,
+                    inputs: std::array::from_fn(|i| self.param_value(i)),
+                };
+                // Saving under an existing ticker replaces its defaults
+                let mut all: Vec<TickerDefaults> = self.ticker_defaults.options().iter()
+                    .filter(|d| d.ticker != defaults.ticker)
+                    .cloned()
+                    .collect();
+                all.push(defaults.clone());
+                all.sort_by(|a, b| a.ticker.cmp(&b.ticker));
+                self.file_status = match ticker_defaults::store(&all) {
+                    Ok(()) => format!("Saved {} defaults", defaults.ticker),
+                    Err(e) => format!("Saving ticker defaults failed: {}", e),
+                };
+                self.ticker_defaults.set_options(all);
+                self.ticker_defaults_select = Some(defaults);
                 return Task::none();
             }
-            Message::SliderSelect(variable) => {
-                self.slider_add_select = Some(variable);
+            Message::TickerDefaultsDelete => {
+                let Some(defaults) = self.ticker_defaults_select.take() else {
+                    return Task::none();
+                };
+                let all: Vec<TickerDefaults> = self.ticker_defaults.options().iter()
+                    .filter(|d| *d != &defaults)
+                    .cloned()
+                    .collect();
+                self.file_status = match ticker_defaults::store(&all) {
+                    Ok(()) => format!("Deleted {} defaults", defaults.ticker),
+                    Err(e) => format!("Deleting ticker defaults failed: {}", e),
+                };
+                self.ticker_defaults.set_options(all);
                 return Task::none();
             }
-            Message::SliderAdd => {
-                if let Some(variable) = self.slider_add_select {
-                    let mut slider = CustomSlider::default().set_precision(MAX_DP);
-                    slider.set_title(format!("{}", variable))
-                        .set_allowed_range(0.0..=f64::MAX);
-                    self.sliders.unique_push(variable, slider);
-                    self.configure_slider(self.sliders.data.len()-1);
+            Message::LayoutSelect(layout) => {
+                // Sliders and charts can only be configured once there is a calculation
+                if self.calculated {
+                    self.open_layout(layout.sliders.clone(), layout.charts.clone(), &[], &[]);
                 }
+                self.layout_name = layout.name.clone();
+                self.layout_select = Some(layout);
                 return Task::none();
             }
-            Message::ChartXSelect(variable) => {
-                self.chart_x_select = Some(variable);
+            Message::LayoutNameEdit(name) => {
+                self.layout_name = name;
                 return Task::none();
             }
-            Message::ChartYSelect(y) => {
-                self.chart_y_select = Some(y);
+            Message::LayoutSave => {
+                let name = self.layout_name.trim().to_string();
+                if name.is_empty() {
+                    self.file_status = String::from("Enter a name for the layout");
+                    return Task::none();
+                }
+                let layout = Layout {
+                    name,
+                    sliders: self.sliders.data.iter().map(|(adj, _)| *adj).collect(),
+                    charts: self.charts.data.iter().map(|(axes, _)| *axes).collect(),
+                };
+                // Saving under an existing name replaces that layout
+                self.layouts.retain(|l| l.name != layout.name);
+                self.layouts.push(layout.clone());
+                self.file_status = match layouts::store(&self.layouts) {
+                    Ok(()) => format!("Saved layout \"{}\"", layout.name),
+                    Err(e) => format!("Saving layout failed: {}", e),
+                };
+                self.layout_select = Some(layout);
                 return Task::none();
             }
-            Message::ChartAdd => {
-                if let (Some(y_axis), Some(x_axis)) = (self.chart_y_select, self.chart_x_select) {
-                    let mut chart = self.create_chart(y_axis, x_axis);
-                    let func;
-                    // Using call options
-                    if self.answers.0 == true {
-                        func = self.get_parameterisation::<Call>(y_axis, x_axis)
-                    } else { // Else using put options
-                        func = self.get_parameterisation::<Put>(y_axis, x_axis)
+            Message::LayoutDelete => {
+                let Some(layout) = self.layout_select.take() else {
+                    return Task::none();
+                };
+                self.layouts.retain(|l| l != &layout);
+                self.file_status = match layouts::store(&self.layouts) {
+                    Ok(()) => format!("Deleted layout \"{}\"", layout.name),
+                    Err(e) => format!("Deleting layout failed: {}", e),
+                };
+                return Task::none();
+            }
+            Message::FillRiskFree => {
+                // Try for the latest curve when online, otherwise use the saved one
+                #[cfg(feature = "online")]
+                {
+                    self.rate_status = String::from("Fetching...");
+                    return Task::perform(rates::fetch_treasury_curve(), Message::YieldsFetched);
+                }
+                #[cfg(not(feature = "online"))]
+                {
+                    self.fill_risk_free(&YieldCurve::load());
+                    return Task::none();
+                }
+            }
+            #[cfg(feature = "online")]
+            Message::YieldsFetched(result) => {
+                match result {
+                    Ok(curve) => {
+                        let _ = curve.store();
+                        self.fill_risk_free(&curve);
+                    }
+                    Err(e) => {
+                        self.fill_risk_free(&YieldCurve::load());
+                        if self.horizon_years().is_some() {
+                            self.rate_status = format!("{} (fetch failed: {})", self.rate_status, e);
+                        }
                     }
-                    chart.set_func(func);
-                    self.charts.unique_push((y_axis, x_axis), chart);
                 }
                 return Task::none();
             }
-            Message::Charts(list_msg) => {
-                self.charts.update(list_msg);
+            Message::ImportPrices => {
+                self.file_status = String::new();
+                return Task::perform(export::open_file(FileKind::Csv), Message::PricesLoaded);
+            }
+            Message::PricesLoaded(result) => {
+                let history = result.and_then(|bytes| {
+                    bytes.map(|b| PriceHistory::from_csv(&String::from_utf8_lossy(&b))).transpose()
+                });
+                match history {
+                    Ok(Some(history)) => self.vol_import = Some(history.vol_estimates()),
+                    Ok(None) => {}
+                    Err(e) => self.file_status = format!("Import failed: {}", e),
+                }
                 return Task::none();
             }
-            Message::TabPressed => {
-                return operation::focus_next();
+            Message::UseVolEstimate(vol) => {
+                self.set_param_value(1, vol);
+                self.vol_import = None;
+                return Task::none();
+            }
+            Message::CloseVolImport => {
+                self.vol_import = None;
+                return Task::none();
+            }
+            Message::ScenarioLoaded(result) => {
+                match result.and_then(|bytes| bytes.map(|b| Scenario::from_json(&b)).transpose()) {
+                    Ok(Some(scenario)) => {
+                        self.apply_scenario(scenario);
+                        self.file_status = String::from("Loaded");
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.file_status = format!("Load failed: {}", e);
+                    }
+                }
+                return Task::none();
+            }
+            Message::Exported(result) => {
+                self.file_status = match result {
+                    Ok(true) => String::from("Exported"),
+                    Ok(false) => String::new(),
+                    Err(e) => format!("Export failed: {}", e),
+                };
+                return Task::none();
             }
         }
     }
 
-    fn view(&self) -> Element<'_, Message> {
-        
-        fn header1_text(s: &str) -> iced::widget::Text<'_> {
-            const HEADER1_SIZE: u32 = 20;
-            text(s)
-                .size(HEADER1_SIZE)
-                .font(FIRA_SANS_BOLD)
-        }
+    /// A maximized chart taking over the whole charts panel. None if no chart is maximized.
+    fn view_maximized_chart(&self) -> Option<Element<'_, Message>> {
+        let i = self.charts.data.iter().position(|(_, chart)| chart.is_maximized())?;
+        return Some(container(column![
+            text("Press Escape or Restore to return to every chart").size(12).style(text::secondary),
+            self.charts.view_item(i).map(Message::Charts),
+        ].spacing(5)
+        .align_x(Center))
+        .padding(20)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into());
+    }
 
-        row![
-            scrollable(column![
+    /// The payoff charts and optional charts sized to fill the given area, drawing only the visible rows of
+    /// payoff charts
+    fn view_charts(&self, area: Size, visible_rows: Range<usize>) -> Column<'_, Message> {
+        column![
+            container(
                 tooltip(
-                    header1_text("Current Environment"),
-                    container("Details about the stock in the current moment.")
+                    header1_text("Charts"),
+                    container(
+                        "Payoff charts to visualise how final ROI/selling-price\n\
+                        changes as certain values change. Values are \n\
+                        overridden/manipulated with the variable sliders."
+                        )
                         .padding(5)
                         .style(container::rounded_box),
                     tooltip::Position::FollowCursor
-                ),
-                text!("Stock price"),
-                self.param[0].view().map(|number_msg| Message::NumberInputMessage(0, number_msg)),
-                text!("Volatility"),
-                self.param[1].view().map(|number_msg| Message::NumberInputMessage(1, number_msg)),
-                text!("Risk free rate"),
-                self.param[2].view().map(|number_msg| Message::NumberInputMessage(2, number_msg)),
-                text!("Dividend yield"),
-                self.param[3].view().map(|number_msg| Message::NumberInputMessage(3, number_msg)),
+                )
+            ).width(Length::Fill).align_x(Left),
+            // Charts keep a fixed aspect ratio however many columns they are split into
+            self.charts.view_grid(
+                self.settings.chart_columns.count(),
+                area.width / self.settings.chart_columns.count() as f32 / CHART_ASPECT_RATIO,
+                visible_rows,
+            ).map(Message::Charts),
+            self.heatmap.as_ref().filter(|_| self.calculated).map(|heatmap| container(heatmap.view())
+                .height(0.5 * area.height)),
+            self.expiry_chart.as_ref().filter(|_| self.calculated).map(|chart| container(chart.view())
+                .height(0.5 * area.height)),
+            self.cone_chart.as_ref().filter(|_| self.calculated).map(|chart| container(chart.view())
+                .height(0.5 * area.height)),
+            self.stress_grid.as_ref().filter(|_| self.calculated).map(|grid| grid.view()),
+            self.simulation_chart.as_ref().filter(|_| self.calculated).map(|chart| container(chart.view())
+                .height(0.5 * area.height)),
+            self.pnl_histogram.as_ref().filter(|_| self.calculated).map(|chart| container(chart.view())
+                .height(0.5 * area.height)),
+        ]
+        .padding(20)
+        .spacing(5)
+        .align_x(Center)
+    }
+
+    /// Controls for adding charts, toggling the optional charts and choosing layouts.
+    /// Wrapped onto as many lines as needed in the compact layout.
+    fn view_chart_controls(&self, compact: bool) -> Element<'_, Message> {
+        let chart_row = row![
+            self.chart_y_pick.view("Choose Y-axis Content", self.chart_y_select.as_ref(), Message::ChartYSelect)
+                .width(Length::Fixed(180.0)),
+            self.chart_x_pick.view("Choose X-axis Content", self.chart_x_select.as_ref(), Message::ChartXSelect)
+                .width(Length::Fixed(180.0)),
+            button("Add Chart").on_press(Message::ChartAdd),
+            button("Remove All")
+                .style(button::secondary)
+                .on_press_maybe((!self.charts.data.is_empty()).then_some(Message::Charts(DeletableListMessage::Clear))),
+            pick_list(ChartColumns::everything(), Some(self.settings.chart_columns), Message::ChartColumnsSelect),
+            pick_list(ChartResolution::everything(), Some(self.settings.chart_resolution), Message::ChartResolutionSelect),
+            pick_list(ChartPalette::everything(), Some(self.settings.chart_palette), Message::ChartPaletteSelect),
+            checkbox(self.heatmap.is_some())
+                .label("Strike/Expiry heatmap")
+                .on_toggle(Message::HeatmapToggle),
+            checkbox(self.expiry_chart.is_some())
+                .label("Payoff at expiry")
+                .on_toggle(Message::ExpiryChartToggle),
+            checkbox(self.cone_chart.is_some())
+                .label("Expected move")
+                .on_toggle(Message::ConeChartToggle),
+            checkbox(self.stress_grid.is_some())
+                .label("Stress test")
+                .on_toggle(Message::StressGridToggle),
+            checkbox(self.simulation_chart.is_some())
+                .label("Simulate paths")
+                .on_toggle(Message::SimulationToggle),
+            checkbox(self.pnl_histogram.is_some())
+                .label("P&L histogram")
+                .on_toggle(Message::PnlHistogramToggle),
+            (self.simulation_chart.is_some() || self.pnl_histogram.is_some()).then(|| {
+                pick_list(SIMULATION_PATH_CHOICES, Some(self.simulation_paths), Message::SimulationPathsSelect)
+            }),
+            button(if self.decay.is_some() { "Stop" } else { "Play time decay" })
+                .on_press_maybe(self.calculated.then_some(Message::DecayPlay)),
+        ].spacing(5)
+        .align_y(iced::alignment::Vertical::Center);
+        let layout_row = row![
+            pick_list(self.layouts.as_slice(), self.layout_select.as_ref(), Message::LayoutSelect)
+                .placeholder("Choose Layout"),
+            iced::widget::text_input("Layout name", &self.layout_name)
+                .on_input(Message::LayoutNameEdit)
+                .on_submit(Message::LayoutSave)
+                .width(Length::Fixed(120.0)),
+            button("Save Layout").on_press(Message::LayoutSave),
+            button("Delete").on_press_maybe(self.layout_select.is_some().then_some(Message::LayoutDelete)),
+        ].spacing(5);
+        if compact {
+            return column![chart_row.wrap().vertical_spacing(5), layout_row.wrap().vertical_spacing(5)]
+                .spacing(5)
+                .into();
+        }
+        return column![
+            container(chart_row).width(Length::Fill).align_x(Center),
+            container(layout_row).width(Length::Fill).align_x(Center),
+        ].spacing(5)
+        .into();
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        // The prediction end can either be entered as a duration in years or as a calendar date
+        let horizon_input: Element<'_, Message> = if self.use_dates {
+            column![
+                text!("Prediction end date"),
+                self.horizon_date.view().map(Message::HorizonDate),
+                text!("Day count convention"),
+                pick_list(DayCount::everything(), Some(self.day_count), Message::DayCountSelect),
+            ].spacing(5)
+            .into()
+        } else {
+            column![
+                text(PARAM_NAMES[5]),
+                row![
+                    self.param[5].view().map(|number_msg| Message::NumberInputMessage(5, number_msg)),
+                    pick_list(TimeUnit::everything(), Some(self.time_unit), Message::TimeUnitSelect),
+                ].spacing(5),
+            ].spacing(5)
+            .into()
+        };
+
+        // Links can only be shared on the web build
+        #[cfg(target_arch = "wasm32")]
+        let share_button: Option<Element<'_, Message>> = Some(button("Share").on_press(Message::ShareLink).into());
+        #[cfg(not(target_arch = "wasm32"))]
+        let share_button: Option<Element<'_, Message>> = None;
+
+        // Controls to pull in live market data
+        #[cfg(feature = "online")]
+        let quote_fetcher: Option<Element<'_, Message>> = Some(column![
+            row![
+                iced::widget::text_input("Ticker", &self.ticker)
+                    .on_input(Message::TickerEdit)
+                    .on_submit(Message::FetchQuote),
+                button("Fetch").on_press(Message::FetchQuote),
+            ].spacing(5),
+            text(&self.quote_status).size(12),
+        ].spacing(5)
+        .into());
+        #[cfg(not(feature = "online"))]
+        let quote_fetcher: Option<Element<'_, Message>> = None;
+
+        // Answers are greyed out once the inputs change until recalculated
+        let stale = self.is_stale();
+        // Strike and expiry can be typed over to evaluate another contract
+        let answer_block = Column::with_children(
+            self.answer_text_block().into_iter().enumerate().map(|(i, s)| {
+                let line = if stale { text(s).style(text::secondary) } else { text(s) };
+                if self.calculated && (i == 1 || i == 2) {
+                    let j = i - 1;
+                    // Keep any date after the expiry
+                    let trailing = if i == 2 { self.valuation_date.and_then(|d| self.day_count.date_after(d, self.answers.1.expiry)) } else { None };
+                    return row![
+                        text(if i == 1 { "Strike:" } else { "Expiry:" }),
+                        container(self.answer_inputs[j].view().map(move |number_msg| Message::AnswerInputMessage(j, number_msg))).width(160),
+                        trailing.map(|date| text!("({})", date)),
+                    ].spacing(5)
+                    .align_y(iced::alignment::Vertical::Center)
+                    .into();
+                }
+                line.into()
+            })
+        );
+        let stale_hint = stale.then(|| if self.settings.live {
+            text("Recalculating...").size(12).style(text::secondary)
+        } else {
+            text("Inputs changed, press Calculate to update").size(12).style(text::warning)
+        });
+
+        // Best call and put side by side. The chosen one is the answer.
+        let candidate_picker: Option<Element<'_, Message>> = self.alternative.as_ref().filter(|_| self.calculated).map(|alternative| {
+            let (call, put) = if self.answers.0 { (&self.answers, alternative) } else { (alternative, &self.answers) };
+            column![
+                text("Contract driving the sliders and charts:").size(12),
+                row![
+                    radio(format!("Best call (ROI {:.*})", self.settings.precision.roi, call.4), true, Some(self.answers.0), Message::ChooseCandidate)
+                        .size(14)
+                        .text_size(12),
+                    radio(format!("Best put (ROI {:.*})", self.settings.precision.roi, put.4), false, Some(self.answers.0), Message::ChooseCandidate)
+                        .size(14)
+                        .text_size(12),
+                ].spacing(10),
+            ].spacing(5)
+            .into()
+        });
+
+        // Decimal places of each kind of number shown
+        let precision = self.settings.precision;
+        let dp_pick = |name: &'static str, dp: usize, with: fn(Precision, usize) -> Precision| row![
+            text(name).size(12),
+            pick_list(Precision::CHOICES, Some(dp), move |dp| Message::PrecisionSelect(with(precision, dp)))
+                .text_size(12),
+        ].spacing(3)
+        .align_y(iced::alignment::Vertical::Center);
+        let precision_picker = row![
+            text("Decimals").size(12),
+            dp_pick("Inputs", precision.inputs, |p, inputs| Precision { inputs, ..p }),
+            dp_pick("Prices", precision.prices, |p, prices| Precision { prices, ..p }),
+            dp_pick("ROI", precision.roi, |p, roi| Precision { roi, ..p }),
+            dp_pick("Greeks", precision.greeks, |p, greeks| Precision { greeks, ..p }),
+        ].spacing(8)
+        .align_y(iced::alignment::Vertical::Center);
+
+        // Greeks and downside risk of the answer. Only meaningful once calculated.
+        let stat_table = |rows: Vec<[String; 3]>| -> Element<'_, Message> {
+            Column::with_children(rows.into_iter().map(|cells| {
+                iced::widget::Row::with_children(
+                    cells.into_iter().map(|cell| {
+                        let cell = text(cell).size(12).width(Length::FillPortion(1));
+                        if stale { cell.style(text::secondary) } else { cell }.into()
+                    })
+                ).into()
+            })).into()
+        };
+        let greeks_table = self.calculated.then(|| stat_table(self.greeks_text_block().to_vec()));
+        let risk_table = self.calculated.then(|| stat_table(self.risk_text_block().to_vec()));
+        let comparison_table = self.calculated.then(|| stat_table(self.comparison_text_block().to_vec()));
+        let exercise_table = self.calculated.then(|| tooltip(
+            stat_table(self.exercise_text_block().to_vec()),
+            container("Prices of the answer if it were an American option, exercisable\nany time before expiry (Bjerksund-Stensland approximation), or\none that never expires.")
+                .padding(5)
+                .style(container::rounded_box),
+            tooltip::Position::FollowCursor
+        ));
+        let lsm_picker = self.calculated.then(|| {
+            let status = match (&self.lsm_progress, &self.lsm_estimate) {
+                (Some(progress), _) => format!("Simulating... {:.0}%", progress * 100.0),
+                (None, Some((contract, env, estimate))) => {
+                    let dp = self.settings.precision.prices;
+                    let earlier = if *contract != self.answers.1 || *env != self.start_env { " (of an earlier answer)" } else { "" };
+                    format!("American buy price {:.*} ± {:.*}{}", dp, estimate.price, dp, estimate.std_error, earlier)
+                }
+                (None, None) => String::new(),
+            };
+            column![
+                row![
+                    tooltip(
+                        text("Longstaff-Schwartz").size(12),
+                        container("Values the answer as an American option by simulating stock price\npaths and regressing the value of holding on at each exercise date.")
+                            .padding(5)
+                            .style(container::rounded_box),
+                        tooltip::Position::FollowCursor
+                    ),
+                    pick_list(LSM_PATH_CHOICES, Some(self.lsm_paths), Message::LsmPathsSelect).text_size(12),
+                    text("paths, degree").size(12),
+                    pick_list(LSM_DEGREE_CHOICES, Some(self.lsm_degree), Message::LsmDegreeSelect).text_size(12),
+                    button(text("Run").size(12)).padding([2, 5]).on_press_maybe(self.lsm_progress.is_none().then_some(Message::LsmRun)),
+                ].spacing(5)
+                .align_y(iced::alignment::Vertical::Center)
+                .wrap(),
+                (!status.is_empty()).then(|| text(status).size(12)),
+            ].spacing(5)
+        });
+        let hedge_picker = self.calculated.then(|| row![
+            checkbox(self.hedge_rebalance.is_some())
+                .label("Delta hedge")
+                .text_size(12)
+                .on_toggle(Message::HedgeToggle),
+            self.hedge_rebalance.map(|rebalance| {
+                pick_list(Rebalance::everything(), Some(rebalance), Message::HedgeRebalanceSelect).text_size(12)
+            }),
+            self.hedge_rebalance.is_some().then(|| text!("over {} paths", HEDGE_PATHS).size(12)),
+        ].spacing(5)
+        .align_y(iced::alignment::Vertical::Center));
+        let hedge_table = self.hedge_text_block().map(|rows| stat_table(rows.to_vec()));
 
+        // Live option chain ranked against the prediction
+        #[cfg(feature = "broker")]
+        let chain_table: Option<Element<'_, Message>> = {
+            const CHAIN_ROWS: usize = 10;
+            let precision = self.settings.precision;
+            fn chain_row<'a>(cells: [String; 6]) -> Element<'a, Message> {
+                iced::widget::Row::with_children(
+                    cells.into_iter().map(|cell| text(cell).size(12).width(Length::FillPortion(1)).into())
+                ).into()
+            }
+            Some(column![
                 rule::horizontal(2),
+                tooltip(
+                    header1_text("Option Chain"),
+                    container(
+                        "Quoted contracts for the ticker ranked by the ROI of buying\n\
+                        at the ask price and selling at the prediction end.\n\
+                        Requires a Tradier sandbox token in TRADIER_TOKEN."
+                    )
+                    .padding(5)
+                    .style(container::rounded_box),
+                    tooltip::Position::FollowCursor
+                ),
+                button("Fetch Chain").on_press(Message::FetchChain),
+                text(&self.chain_status).size(12),
+                tooltip(
+                    checkbox(self.use_smile)
+                        .label("Exit on the volatility smile")
+                        .on_toggle_maybe(self.chain_smile.is_some().then_some(Message::UseSmile))
+                        .text_size(12),
+                    container(
+                        "Sells each contract at the volatility of a SABR smile fit to\n\
+                        the chain's quoted implied volatilities, instead of one flat\n\
+                        volatility across every strike."
+                    )
+                    .padding(5)
+                    .style(container::rounded_box),
+                    tooltip::Position::FollowCursor
+                ),
+                text(self.chain_smile.as_ref().map(|fit| format!(
+                    "Smile fit to {}: α {:.3}, β {}, ρ {:.3}, ν {:.3} (RMS error {:.4})",
+                    fit.expiry.format("%y-%m-%d"), fit.sabr.alpha, fit.sabr.beta, fit.sabr.rho, fit.sabr.nu, fit.rms_error,
+                )).unwrap_or_default()).size(12),
+                self.chain_smile.as_ref().map(|_| tooltip(
+                    text!("Fair variance swap strike over the prediction: {:.3} volatility, against {:.3} input", self.fair_variance_vol(), self.start_env.vol).size(12),
+                    container(
+                        "Volatility the market prices in over the prediction time frame,\n\
+                        replicated from options across the whole smile. An input above\n\
+                        it is rich relative to the market, below it cheap."
+                    )
+                    .padding(5)
+                    .style(container::rounded_box),
+                    tooltip::Position::FollowCursor
+                )),
+                chain_row([
+                    String::from("Strike"), String::from("Expiry"), String::from("Bid/Ask"),
+                    String::from("IV"), String::from("Exit"), String::from("ROI"),
+                ]),
+                Column::with_children(self.chain_ranked.iter().take(CHAIN_ROWS).map(|ranked| chain_row([
+                    format!("{:.*}", precision.prices, ranked.quote.strike),
+                    ranked.quote.expiry.format("%y-%m-%d").to_string(),
+                    format!("{:.*}/{:.*}", precision.prices, ranked.quote.bid, precision.prices, ranked.quote.ask),
+                    ranked.quote.iv.map(|iv| format!("{:.3}", iv)).unwrap_or(String::from("-")),
+                    format!("{:.*}", precision.prices, ranked.exit),
+                    format!("{:.*}", precision.roi, ranked.roi),
+                ]))),
+            ].spacing(5)
+            .into())
+        };
+        #[cfg(not(feature = "broker"))]
+        let chain_table: Option<Element<'_, Message>> = None;
 
+        let inputs_panel = column![
+            row![
+                button("Save Scenario").on_press(Message::SaveScenario),
+                button("Load Scenario").on_press(Message::LoadScenario),
+                share_button,
                 tooltip(
-                    header1_text("Prediction"),
-                    container("Prediction on what price the stock will reach and when.")
+                    button("Reset").on_press(Message::ResetSession).style(button::danger),
+                    container("Clears everything and forgets the autosaved session.")
                         .padding(5)
                         .style(container::rounded_box),
                     tooltip::Position::FollowCursor
                 ),
-                text!("Prediction stock price"),
-                self.param[4].view().map(|number_msg| Message::NumberInputMessage(4, number_msg)),
-                text!("Prediction end duration"),
-                self.param[5].view().map(|number_msg| Message::NumberInputMessage(5, number_msg)),
-                button("Calculate").on_press(Message::Calculate),
-
-                rule::horizontal(2),
-
                 tooltip(
-                    header1_text("Answer"),
-                    container(
-                        "The option contract to buy immediately and to sell\n\
-                        at the prediction end duration that maximises ROI.\n\
-                        Assumes that:\n\
-                        \x20- Current Environment variables \n\
-                        \x20  (except stock price) stay constant\n\
-                        \x20- Prediction becomes perfectly true\n\
-                        \x20- Strike and expiry are chosen based on\n\
-                        \x20  \"perfectly smooth\" payoff graphs"
-                    )
+                    button("ESOs & Warrants").on_press(Message::CompanyOptionOpen(true)).style(button::secondary),
+                    container("Values an employee stock option grant or a warrant issue in the current environment.")
+                        .padding(5)
+                        .style(container::rounded_box),
+                    tooltip::Position::FollowCursor
+                ),
+                button("Settings").on_press(Message::SettingsOpen(true)).style(button::secondary),
+            ].spacing(5)
+            // Wrapped onto more lines in the compact layout
+            .wrap()
+            .vertical_spacing(5),
+            row![
+                self.presets.view("Choose Preset", self.preset_select.as_ref(), Message::PresetSelect)
+                    .width(Length::Fill),
+                iced::widget::text_input("Preset name", &self.preset_name)
+                    .on_input(Message::PresetNameEdit)
+                    .on_submit(Message::PresetSave)
+                    .width(Length::Fixed(120.0)),
+                button("Save Preset").on_press(Message::PresetSave),
+                button("Delete").on_press_maybe(
+                    self.preset_select.as_ref().filter(|p| !p.built_in).map(|_| Message::PresetDelete)
+                ),
+            ].spacing(5),
+            tooltip(
+                header1_text("Current Environment"),
+                container("Details about the stock in the current moment.")
                     .padding(5)
                     .style(container::rounded_box),
+                tooltip::Position::FollowCursor
+            ),
+            quote_fetcher,
+            row![
+                self.ticker_defaults.view("Search ticker defaults", self.ticker_defaults_select.as_ref(), Message::TickerDefaultsSelect)
+                    .width(Length::Fill),
+                iced::widget::text_input("Ticker", &self.ticker_defaults_name)
+                    .on_input(Message::TickerDefaultsNameEdit)
+                    .on_submit(Message::TickerDefaultsSave)
+                    .width(Length::Fixed(80.0)),
+                tooltip(
+                    button("Save Defaults").on_press(Message::TickerDefaultsSave),
+                    container("Saves the four inputs below as the ticker's typical environment.\nRecalling them leaves the prediction alone.")
+                        .padding(5)
+                        .style(container::rounded_box),
+                    tooltip::Position::FollowCursor
+                ),
+                button("Delete").on_press_maybe(self.ticker_defaults_select.is_some().then_some(Message::TickerDefaultsDelete)),
+            ].spacing(5),
+            tooltip(
+                row![
+                    text("Underlying").width(Length::Fill),
+                    pick_list(Underlying::everything(), Some(self.underlying), Message::UnderlyingSelect),
+                ].spacing(5)
+                .align_y(iced::alignment::Vertical::Center),
+                container(
+                    "Futures are priced with the Black-76 model, taking the price\n\
+                    below as the futures price. Futures don't pay dividends, so\n\
+                    there's no dividend yield.\n\
+                    Currency pairs are priced with the Garman-Kohlhagen model,\n\
+                    taking the price below as the spot rate in domestic currency\n\
+                    per unit of foreign."
+                )
+                .padding(5)
+                .style(container::rounded_box),
+                tooltip::Position::FollowCursor
+            ),
+            text(self.param_name(0)),
+            self.param[0].view().map(|number_msg| Message::NumberInputMessage(0, number_msg)),
+            row![
+                text(PARAM_NAMES[1]).width(Length::Fill),
+                tooltip(
+                    button(text("Estimate from prices").size(12)).padding([2, 5]).on_press(Message::ImportPrices),
+                    container("Estimate volatility from a CSV of daily prices\n(e.g. with Date,Open,High,Low,Close columns).")
+                        .padding(5)
+                        .style(container::rounded_box),
                     tooltip::Position::FollowCursor
                 ),
-                Column::with_children(
-                    self.answer_text_block().into_iter().map(|s| text(s).into())
+            ].align_y(iced::alignment::Vertical::Center),
+            row![
+                self.param[1].view().map(|number_msg| Message::NumberInputMessage(1, number_msg)),
+                pick_list(VolUnit::everything(), Some(self.vol_unit), Message::VolUnitSelect),
+            ].spacing(5),
+            row![
+                text(self.param_name(2)).width(Length::Fill),
+                tooltip(
+                    button(text("From yield curve").size(12)).padding([2, 5]).on_press(Message::FillRiskFree),
+                    container("Use the government bond yield for the prediction end duration.")
+                        .padding(5)
+                        .style(container::rounded_box),
+                    tooltip::Position::FollowCursor
                 ),
+            ].align_y(iced::alignment::Vertical::Center),
+            self.param[2].view().map(|number_msg| Message::NumberInputMessage(2, number_msg)),
+            (!self.rate_status.is_empty()).then(|| text(&self.rate_status).size(12)),
+            self.underlying.yield_name().map(text),
+            self.underlying.yield_name().map(|_| self.param[3].view().map(|number_msg| Message::NumberInputMessage(3, number_msg))),
 
+            rule::horizontal(2),
+
+            tooltip(
+                header1_text("Prediction"),
+                container("Prediction on what price the stock will reach and when.")
+                    .padding(5)
+                    .style(container::rounded_box),
+                tooltip::Position::FollowCursor
+            ),
+            text(self.param_name(4)),
+            self.param[4].view().map(|number_msg| Message::NumberInputMessage(4, number_msg)),
+            checkbox(self.use_dates)
+                .label("Enter end as a date")
+                .on_toggle(Message::UseDates),
+            horizon_input,
+            tooltip(
+                checkbox(self.use_event)
+                    .label("Volatility event")
+                    .on_toggle(Message::EventToggle),
+                container(
+                    "A scheduled event (e.g earnings) after which volatility drops.\n\
+                    The contract is bought at the current volatility, and sold at\n\
+                    the post-event volatility if the event has passed by then."
+                )
+                .padding(5)
+                .style(container::rounded_box),
+                tooltip::Position::FollowCursor
+            ),
+            self.use_event.then(|| column![
+                text!("Time until event ({})", self.time_unit),
+                self.event_inputs[0].view().map(|number_msg| Message::EventInputMessage(0, number_msg)),
+                text!("Post-event volatility ({})", self.vol_unit),
+                self.event_inputs[1].view().map(|number_msg| Message::EventInputMessage(1, number_msg)),
+            ].spacing(5)),
+            text("Notes"),
+            text_editor(&self.notes)
+                .placeholder("Reasoning behind the prediction")
+                .size(12)
+                .height(80)
+                .on_action(Message::NotesEdit),
+            row![
+                button("Calculate").on_press(Message::Calculate),
+                checkbox(self.settings.live)
+                    .label("Live")
+                    .on_toggle(Message::LiveToggle),
+            ].spacing(10)
+            .align_y(iced::alignment::Vertical::Center),
+
+            rule::horizontal(2),
+
+            tooltip(
+                header1_text("Answer"),
+                container(
+                    "The option contract to buy immediately and to sell\n\
+                    at the prediction end duration that maximises ROI.\n\
+                    Assumes that:\n\
+                    \x20- Current Environment variables \n\
+                    \x20  (except stock price) stay constant\n\
+                    \x20- Prediction becomes perfectly true\n\
+                    \x20- Strike and expiry are chosen based on\n\
+                    \x20  \"perfectly smooth\" payoff graphs"
+                )
+                .padding(5)
+                .style(container::rounded_box),
+                tooltip::Position::FollowCursor
+            ),
+            stale_hint,
+            answer_block,
+            row![
+                text("Contract size").size(12),
+                self.multiplier.adjust_then_view(|x| x.width(70).size(12)).map(Message::MultiplierInput),
+                text("Nominal charts").size(12),
+                pick_list(NominalScale::everything(), Some(self.settings.nominal_scale), Message::NominalScaleSelect)
+                    .text_size(12),
+            ].spacing(5)
+            .align_y(iced::alignment::Vertical::Center),
+            (self.underlying == Underlying::Fx).then(|| row![
+                text("Premiums").size(12),
+                pick_list(PremiumQuote::everything(), Some(self.settings.premium_quote), Message::PremiumQuoteSelect)
+                    .text_size(12),
+            ].spacing(5)
+            .align_y(iced::alignment::Vertical::Center)),
+            candidate_picker,
+            greeks_table,
+            risk_table,
+            comparison_table,
+            exercise_table,
+            lsm_picker,
+            hedge_picker,
+            hedge_table,
+            row![
+                button("Export CSV").on_press(Message::Export),
+                button("Export Excel").on_press(Message::ExportSpreadsheet),
+                button("Generate Report").on_press(Message::ExportReport),
+                text(&self.file_status).size(12),
+            ].spacing(5)
+            .align_y(iced::alignment::Vertical::Center),
+            chain_table,
+
+            rule::horizontal(2),
+
+            tooltip(
+                header1_text("Variable Sliders"),
+                container(
+                    "Numerical sliders to override values for the\n\
+                    option initally purchased or the ending environment.\n\
+                    The slider values only affect the payoff charts."
+                    )
+                    .padding(5)
+                    .style(container::rounded_box),
+                tooltip::Position::FollowCursor
+            ),
+            self.sliders.view(|x| x.spacing(5)).map(Message::Sliders),
+            row![
+                self.slider_add_pick.view("Choose Variable", self.slider_add_select.as_ref(), Message::SliderSelect)
+                    .width(Length::Fixed(180.0)),
+                button("Add Slider").on_press(Message::SliderAdd),
+                button("Remove All")
+                    .style(button::secondary)
+                    .on_press_maybe((!self.sliders.data.is_empty()).then_some(Message::Sliders(DeletableListMessage::Clear))),
+            ].spacing(5)
+        ].padding(20)
+        .spacing(5)
+        .align_x(Left);
+
+        let content: Element<'_, Message> = if self.is_compact() {
+            // Panels are stacked into one scroll, with the chart controls moved into a bottom sheet
+            let area = self.layout_size();
+            self.view_maximized_chart().unwrap_or_else(|| scrollable(column![
+                inputs_panel.width(Length::Fill),
                 rule::horizontal(2),
+                self.view_charts(area, 0..usize::MAX),
+            ]).into())
+        } else {
+            row![
+                // Clamped in case the settings were edited by hand
+                scrollable(inputs_panel.width(self.settings.panel_width.clamp(*PANEL_WIDTH_RANGE.start(), *PANEL_WIDTH_RANGE.end()))),
+                splitter(Message::PanelResize, Message::PanelResized),
+                responsive(|area| {
+                    // A maximized chart takes over the whole panel
+                    self.view_maximized_chart().unwrap_or_else(|| scrollable(
+                        self.view_charts(area, self.visible_chart_rows()).push(self.view_chart_controls(false))
+                    ).on_scroll(Message::ChartsScrolled)
+                    .into())
+                }),
+            ].into()
+        };
+        // Chart controls of the compact layout, shown over the bottom of the window
+        let chart_sheet = self.is_compact().then(|| column![
+            self.chart_sheet_open.then(|| container(scrollable(self.view_chart_controls(true)))
+                .padding(10)
+                .max_height(0.5 * self.layout_size().height)
+                .width(Length::Fill)
+                .style(container::rounded_box)),
+            button(text(if self.chart_sheet_open { "Hide chart options" } else { "Chart options" }).align_x(Center))
+                .width(Length::Fill)
+                .padding(10)
+                .style(button::secondary)
+                .on_press(Message::ChartSheetToggle(!self.chart_sheet_open)),
+        ]);
+
+        // Status strip describing the last calculation
+        let status_bar = match &self.diagnostics {
+            Some(diagnostics) => container(row![
+                text(diagnostics.summary()).size(12).width(Length::Fill),
+                row(diagnostics.warnings.iter().map(|warning| {
+                    text!("Warning: {}", warning).size(12).style(text::warning).into()
+                })).spacing(15),
+            ]),
+            None if self.calculated => container(text("Calculation restored from a saved session").size(12)),
+            None => container(text("Not calculated yet").size(12)),
+        };
+        // Offers to undo the latest deletion of a slider or chart for a few seconds
+        let deleted = self.sliders.deleted().map(|adj| (format!("Removed the {} slider", adj), Message::Sliders(DeletableListMessage::UndoDelete)))
+            .or(self.charts.deleted().map(|(y_axis, x_axis)| (format!("Removed the {} against {} chart", y_axis, x_axis), Message::Charts(DeletableListMessage::UndoDelete))));
+        let delete_toast = self.delete_toast.and(deleted).map(|(description, undo)| {
+            container(row![
+                text(description).size(12).width(Length::Fill),
+                button(text("Undo delete").size(12)).padding([2, 5]).on_press(undo),
+                button(text("X").size(10)).padding([2, 5]).style(button::secondary).on_press(Message::DeleteToastDismiss),
+            ].spacing(10)
+            .align_y(iced::alignment::Vertical::Center))
+            .padding([4, 10])
+            .width(Length::Fill)
+            .style(container::rounded_box)
+        });
+        let content = column![
+            container(content).height(Length::Fill),
+            chart_sheet,
+            delete_toast,
+            rule::horizontal(1),
+            status_bar.padding([2, 10]).width(Length::Fill),
+        ];
 
+        if self.settings_open {
+            fn setting<'a>(name: &'static str, pick: impl Into<Element<'a, Message>>) -> iced::widget::Row<'a, Message> {
+                row![
+                    text(name).width(Length::Fill),
+                    pick.into(),
+                ].spacing(10).align_y(iced::alignment::Vertical::Center)
+            }
+            let dialog = container(column![
+                header1_text("Settings"),
+                setting("Theme", pick_list(self.themes.as_slice(), Some(&self.theme), Message::ThemeSelect)),
+                setting("Interface size", pick_list(UiScale::everything(), Some(self.settings.scale), Message::ScaleSelect)),
+                precision_picker,
+                setting("Volatility unit of new sessions", pick_list(VolUnit::everything(), Some(self.settings.vol_unit), Message::DefaultVolUnitSelect)),
+                setting("Duration unit of new sessions", pick_list(TimeUnit::everything(), Some(self.settings.time_unit), Message::DefaultTimeUnitSelect)),
                 tooltip(
-                    header1_text("Variable Sliders"),
-                    container(
-                        "Numerical sliders to override values for the\n\
-                        option initally purchased or the ending environment.\n\
-                        The slider values only affect the payoff charts."
-                        )
+                    setting("Default slider/chart range", pick_list(RangeSpan::everything(), Some(self.settings.range_span), Message::RangeSpanSelect)),
+                    container("How far the ranges extend either side of the answer.\nTakes effect on the next calculation.")
                         .padding(5)
                         .style(container::rounded_box),
                     tooltip::Position::FollowCursor
                 ),
-                self.sliders.view(|x| x.spacing(5)).map(|msg| Message::Sliders(msg)),
-                row![
-                    pick_list(Adjustables::everything(), self.slider_add_select, Message::SliderSelect)
-                        .placeholder("Choose Variable"),
-                    button("Add Slider").on_press(Message::SliderAdd),
-                ]
-            ].padding(20)
-            .spacing(5)
-            .width(350)
-            .align_x(Left)),
-
-            rule::vertical(2),
-
-            responsive( |area| {
-                scrollable(
-                    column![
-                        container(
-                            tooltip(
-                                header1_text("Charts"),
-                                container(
-                                    "Payoff charts to visualise how final ROI/selling-price\n\
-                                    changes as certain values change. Values are \n\
-                                    overridden/manipulated with the variable sliders."
-                                    )
-                                    .padding(5)
-                                    .style(container::rounded_box),
-                                tooltip::Position::FollowCursor
-                            )
-                        ).width(Length::Fill).align_x(Left),
-                        container(self.charts.view(|x| x).map(|msg| Message::Charts(msg)))
-                            .height((0.5 * area.height * self.charts.data.len() as f32) - 80.0),
-                        container(row![
-                            pick_list(PayoffYAxis::everything(), self.chart_y_select, Message::ChartYSelect)
-                                .placeholder("Choose Y-axis Content"),
-                            pick_list(Adjustables::everything(), self.chart_x_select, Message::ChartXSelect)
-                                .placeholder("Choose X-axis Content"),
-                            button("Add Chart").on_press(Message::ChartAdd),
-                        ]).width(Length::Fill).align_x(Center)
-                    ]
-                    .padding(20)
-                    .spacing(5)
-                    .align_x(Center)
-                ).into()
-            })
-        ].into()
+                text("Contracts are priced with the Black-Scholes model, or its Black-76 and Garman-Kohlhagen variants for futures and currencies.").size(12),
+                container(button("Close").on_press(Message::SettingsOpen(false))).width(Length::Fill).align_x(Center),
+            ].spacing(10))
+            // Narrower on phones
+            .width(Length::Fill)
+            .max_width(450)
+            .padding(20)
+            .style(container::rounded_box);
+            return modal(content, dialog, Message::SettingsOpen(false));
+        }
+
+        if self.company_option_open {
+            let value = match self.company_option_value {
+                Some((value, traded)) => {
+                    let dp = self.settings.precision.prices;
+                    format!("Value per option: {:.*}\nA traded call on the same terms: {:.*}", dp, value, dp, traded)
+                }
+                None => String::from("Fill in the inputs below, and the stock price, volatility and rates of the calculator"),
+            };
+            fn input_row<'a>(name: &'a str, input: Element<'a, Message>) -> Element<'a, Message> {
+                return row![
+                    text(name).width(Length::Fill),
+                    container(input).width(Length::Fixed(150.0)),
+                ].spacing(10).align_y(iced::alignment::Vertical::Center).into();
+            }
+            let eso_rows = ESO_NAMES.iter().zip(&self.eso_inputs).enumerate()
+                .map(|(i, (name, input))| input_row(name, input.view().map(move |number_msg| Message::EsoInputMessage(i, number_msg))));
+            let (description, rows): (&str, Vec<Element<'_, Message>>) = match self.company_option {
+                CompanyOption::Employee => (
+                    "Valued by the Hull-White model in the current environment. The option can't be exercised\n\
+                    until it vests, and is exercised early once the stock reaches the multiple of the strike or\n\
+                    the employee leaves. Leaving before vesting forfeits it.",
+                    eso_rows.collect(),
+                ),
+                CompanyOption::Warrant => (
+                    "Valued as a call in the current environment, scaled down by the dilution of the new\n\
+                    shares each exercised warrant issues. The stock price is taken to already reflect the issue,\n\
+                    so the call is written on the stock plus each share's part of the warrants' value.",
+                    eso_rows.take(2).chain(WARRANT_NAMES.iter().zip(&self.warrant_inputs).enumerate()
+                        .map(|(i, (name, input))| input_row(name, input.view().map(move |number_msg| Message::WarrantInputMessage(i, number_msg)))))
+                        .collect(),
+                ),
+            };
+            let dialog = container(column![
+                header1_text("ESOs & Warrants"),
+                pick_list(CompanyOption::everything(), Some(self.company_option), Message::CompanyOptionSelect),
+                text(description).size(12),
+                Column::with_children(rows).spacing(5),
+                text(value),
+                container(button("Close").on_press(Message::CompanyOptionOpen(false))).width(Length::Fill).align_x(Center),
+            ].spacing(10))
+            .width(Length::Fill)
+            .max_width(500)
+            .padding(20)
+            .style(container::rounded_box);
+            return modal(content, dialog, Message::CompanyOptionOpen(false));
+        }
+
+        let Some(estimates) = &self.vol_import else {
+            return content.into();
+        };
+        // Each estimate can be used if enough data was available to compute it
+        let estimate_row = |name: &'static str, vol: Option<f64>| {
+            row![
+                text(name).width(Length::Fill),
+                text(vol.map(|v| format!("{:.4}", v)).unwrap_or(String::from("Unavailable"))).width(Length::Fixed(100.0)),
+                button("Use").on_press_maybe(vol.map(Message::UseVolEstimate)),
+            ].spacing(10).align_y(iced::alignment::Vertical::Center)
+        };
+        let dialog = container(column![
+            header1_text("Historical Volatility"),
+            text!("Estimated from {} days of prices", estimates.days),
+            estimate_row("Close-to-close", estimates.close_to_close),
+            estimate_row("Parkinson (high-low)", estimates.parkinson),
+            container(button("Close").on_press(Message::CloseVolImport)).width(Length::Fill).align_x(Center),
+        ].spacing(10))
+        .width(Length::Fill)
+        .max_width(400)
+        .padding(20)
+        .style(container::rounded_box);
+        return modal(content, dialog, Message::CloseVolImport);
+    }
+
+    fn theme(&self) -> Theme {
+        return self.theme.clone();
     }
 
     fn subscription(&self) -> Subscription<Message> {
         use iced::keyboard;
 
-        keyboard::listen().filter_map(|event| match event {
+        // Shift steps 10 times as far for coarse changes, Ctrl a tenth as far for fine changes
+        fn arrow_steps(modifiers: keyboard::Modifiers) -> f64 {
+            if modifiers.shift() {
+                return 10.0;
+            }
+            if modifiers.control() {
+                return 0.1;
+            }
+            return 1.0;
+        }
+
+        let keys = keyboard::listen().filter_map(|event| match event {
             keyboard::Event::KeyPressed {
                 key: keyboard::Key::Named(key),
                 modifiers,
                 ..
             } => match (key, modifiers) {
                 (keyboard::key::Named::Tab, _) => Some(Message::TabPressed),
+                (keyboard::key::Named::Escape, _) => Some(Message::EscapePressed),
+                (keyboard::key::Named::ArrowUp, m) => Some(Message::StepPressed(arrow_steps(m))),
+                (keyboard::key::Named::ArrowDown, m) => Some(Message::StepPressed(-arrow_steps(m))),
+                _ => None,
+            }
+            keyboard::Event::KeyPressed {
+                key: keyboard::Key::Character(c),
+                modifiers,
+                ..
+            } if modifiers.command() => match (c.as_str(), modifiers.shift()) {
+                ("z", false) => Some(Message::Undo),
+                ("z", true) | ("y", false) => Some(Message::Redo),
                 _ => None,
             }
             _ => None,
-        })
+        });
+        let resizes = window::resize_events().map(|(_, size)| Message::WindowResized(size));
+        let mut subscriptions = vec![keys, resizes];
+        #[cfg(target_arch = "wasm32")]
+        subscriptions.push(Subscription::run(embed::requests).map(|(origin, request)| Message::Embed(origin, request)));
+        // Only tick while a live recalculation is waiting for the inputs to settle
+        if self.live_pending.is_some() {
+            subscriptions.push(iced::time::every(LIVE_DEBOUNCE / 4).map(|_| Message::LiveTick));
+        }
+        if self.decay.is_some() {
+            subscriptions.push(iced::time::every(DECAY_FRAME).map(|_| Message::DecayTick));
+        }
+        if self.delete_toast.is_some() {
+            subscriptions.push(iced::time::every(Duration::from_secs(1)).map(|_| Message::DeleteToastTick));
+        }
+        return Subscription::batch(subscriptions);
     }
 }
 
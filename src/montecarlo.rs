@@ -0,0 +1,341 @@
+//! Monte Carlo simulation of the stock price as geometric brownian motion, and of an option's value along the way.
+
+use crate::blackscholes::{Contract, Environment, Movement, OptionType, VolEvent};
+
+/// Percentiles of the simulated option value given at each time step, lowest first
+pub const PERCENTILES: [f64; 5] = [0.05, 0.25, 0.5, 0.75, 0.95];
+
+/// Seeded pseudo random number generator (SplitMix64). Simulations with the same seed draw the same paths,
+/// so a chart doesn't flicker between redraws.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        return Self { state: seed };
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        return z ^ (z >> 31);
+    }
+
+    /// Uniformly distributed within (0, 1), never exactly 0 or 1
+    pub fn uniform(&mut self) -> f64 {
+        return ((self.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+    }
+
+    /// Standard normally distributed, by the Box-Muller transform
+    pub fn std_normal(&mut self) -> f64 {
+        let (u1, u2) = (self.uniform(), self.uniform());
+        return (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+    }
+}
+
+/// Simulates stock price paths from the environment over the given time, split into equal steps.
+/// Each path starts at the current stock price and holds steps + 1 prices.
+/// Paths drift at the risk free rate less the dividend yield, the same distribution as Environment::terminal_density.
+pub fn gbm_paths(env: &Environment, time: f64, steps: usize, paths: usize, rng: &mut Rng) -> Vec<Vec<f64>> {
+    let dt = time / steps as f64;
+    let drift = (env.risk_free - env.div_yield - env.vol.powi(2) / 2.0) * dt;
+    let spread = env.vol * dt.sqrt();
+    return (0..paths).map(|_| {
+        let mut path = Vec::with_capacity(steps + 1);
+        path.push(env.stock);
+        for _ in 0..steps {
+            let last = path[path.len() - 1];
+            path.push(last * (drift + spread * rng.std_normal()).exp());
+        }
+        path
+    }).collect();
+}
+
+/// Percentiles (see PERCENTILES) of the option's mark-to-model value at each step of the simulated paths.
+/// The option is priced in the environment at the price of each path, with its expiry running down over the time.
+/// The volatility switches to the event's (if any) once the event has passed.
+/// Returns the time of each step alongside its percentiles.
+pub fn value_bands(pricer: OptionType, env: &Environment, contract: &Contract, paths: &[Vec<f64>], time: f64, event: Option<VolEvent>) -> Vec<(f64, [f64; 5])> {
+    let steps = paths.first().map_or(0, |path| path.len());
+    return (0..steps).map(|step| {
+        let t = if steps > 1 { time * step as f64 / (steps - 1) as f64 } else { 0.0 };
+        let env = &event.map_or_else(|| env.clone(), |event| event.exit_env(env, &Movement { stock: env.stock, time: t }));
+        // Prices are homogeneous in the stock and strike prices, so every path can be priced as one batch in the
+        // same environment by scaling the strike instead of the stock price
+        let contracts: Vec<Contract> = paths.iter()
+            .map(|path| Contract { strike: contract.strike * env.stock / path[step], expiry: (contract.expiry - t).max(0.0) })
+            .collect();
+        let mut values: Vec<f64> = pricer.bsm_price_batch(env, &contracts).into_iter().zip(paths)
+            .map(|(price, path)| price * path[step] / env.stock)
+            .collect();
+        values.sort_by(f64::total_cmp);
+        let last = values.len().saturating_sub(1) as f64;
+        let bands = PERCENTILES.map(|p| values.get((p * last).round() as usize).copied().unwrap_or(f64::NAN));
+        (t, bands)
+    }).collect();
+}
+
+/// Mark-to-model value of the option at the end of each simulated path, over the given time.
+/// The volatility switches to the event's (if any) once the event has passed.
+pub fn final_values(pricer: OptionType, env: &Environment, contract: &Contract, paths: &[Vec<f64>], time: f64, event: Option<VolEvent>) -> Vec<f64> {
+    let contract = Contract { expiry: (contract.expiry - time).max(0.0), ..contract.clone() };
+    return paths.iter().filter_map(|path| path.last()).map(|&stock| {
+        let env = Environment { stock, ..env.clone() };
+        let env = event.map_or_else(|| env.clone(), |event| event.exit_env(&env, &Movement { stock, time }));
+        pricer.bsm_price(&env, &contract)
+    }).collect();
+}
+
+/// How often a delta hedge is rebalanced
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Rebalance {
+    #[default]
+    Daily,
+    Weekly,
+    Monthly,
+}
+impl std::fmt::Display for Rebalance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Daily => "Daily",
+            Self::Weekly => "Weekly",
+            Self::Monthly => "Monthly",
+        })
+    }
+}
+impl Rebalance {
+    const COUNT: usize = 3;
+
+    pub fn everything() -> [Self; Self::COUNT] {
+        [Self::Daily, Self::Weekly, Self::Monthly]
+    }
+
+    /// Years between rebalances. Days are trading days.
+    pub fn interval(&self) -> f64 {
+        match self {
+            Self::Daily => 1.0 / 252.0,
+            Self::Weekly => 1.0 / 52.0,
+            Self::Monthly => 1.0 / 12.0,
+        }
+    }
+
+    /// Number of rebalances over the given time, at least 1 (the hedge put on at the start)
+    pub fn steps_over(&self, time: f64) -> usize {
+        return ((time / self.interval()).ceil() as usize).max(1);
+    }
+}
+
+/// Summary of a distribution of profits/losses
+#[derive(Debug, Clone)]
+pub struct PnlStats {
+    pub mean: f64,
+    pub std_dev: f64,
+    /// Values at PERCENTILES
+    pub percentiles: [f64; 5],
+}
+impl PnlStats {
+    /// Summarises the given outcomes. Sorts them in place.
+    pub fn of(outcomes: &mut [f64]) -> Self {
+        outcomes.sort_by(f64::total_cmp);
+        let n = outcomes.len() as f64;
+        let mean = outcomes.iter().sum::<f64>() / n;
+        let variance = outcomes.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+        let last = outcomes.len().saturating_sub(1) as f64;
+        return Self {
+            mean,
+            std_dev: variance.sqrt(),
+            percentiles: PERCENTILES.map(|p| outcomes.get((p * last).round() as usize).copied().unwrap_or(f64::NAN)),
+        };
+    }
+}
+
+/// Profit/loss per option of buying the option and holding it to the end of each path, both with and without
+/// delta hedging at every step of the path, in that order.
+///
+/// The hedge shorts delta shares of the stock, rebalanced at each step. Cash earns the risk free rate,
+/// and the dividends of the shorted stock are paid out of it. Without hedging, the premium is still financed
+/// at the risk free rate so the two are comparable. The volatility switches to the event's (if any) once passed.
+pub fn delta_hedge(pricer: OptionType, env: &Environment, contract: &Contract, paths: &[Vec<f64>], time: f64, event: Option<VolEvent>) -> (Vec<f64>, Vec<f64>) {
+    let steps = paths.first().map_or(0, |path| path.len().saturating_sub(1));
+    let dt = if steps > 0 { time / steps as f64 } else { 0.0 };
+    // Option priced at step i of a path
+    let priced = |i: usize, stock: f64| {
+        let t = i as f64 * dt;
+        let env = Environment { stock, ..env.clone() };
+        let env = event.map_or_else(|| env.clone(), |event| event.exit_env(&env, &Movement { stock, time: t }));
+        let contract = Contract { expiry: (contract.expiry - t).max(0.0), ..contract.clone() };
+        return (pricer.bsm_price(&env, &contract), pricer.greeks(&env, &contract).delta);
+    };
+    let (premium, start_delta) = priced(0, env.stock);
+    let growth = (env.risk_free * dt).exp();
+    let dividend = (env.div_yield * dt).exp() - 1.0;
+
+    return paths.iter().map(|path| {
+        let mut cash = start_delta * path[0] - premium;
+        let mut delta = start_delta;
+        for i in 1..=steps {
+            cash = cash * growth - delta * path[i - 1] * dividend;
+            if i < steps {
+                let (_, new_delta) = priced(i, path[i]);
+                cash += (new_delta - delta) * path[i];
+                delta = new_delta;
+            }
+        }
+        let (value, _) = priced(steps, path[steps]);
+        let hedged = value - delta * path[steps] + cash;
+        let unhedged = value - premium * (env.risk_free * time).exp();
+        (hedged, unhedged)
+    }).unzip();
+}
+
+/// Dates an American option can be exercised on in a Longstaff-Schwartz valuation, spread evenly up to expiry
+pub const LSM_EXERCISE_DATES: usize = 50;
+
+/// Price estimated by simulation, alongside its standard error
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LsmEstimate {
+    pub price: f64,
+    pub std_error: f64,
+}
+
+/// Longstaff-Schwartz valuation of an American option over simulated paths, worked backwards from expiry a date at a
+/// time so its progress can be reported (see step). At each date, exercising is compared against the value of holding
+/// on, estimated by regressing the later cash flows of the paths in the money on a polynomial in the stock price.
+pub struct LongstaffSchwartz {
+    option: OptionType,
+    strike: f64,
+    risk_free: f64,
+    dt: f64,
+    degree: usize,
+    /// Exercise dates after now, the last being expiry
+    dates: usize,
+    paths: Vec<Vec<f64>>,
+    /// Cash flow of each path, from the earliest date it's exercised on so far
+    cash: Vec<f64>,
+    /// Date each path's cash flow is received on
+    exercised: Vec<usize>,
+    /// Next date to work back from. 0 once done.
+    date: usize,
+}
+impl LongstaffSchwartz {
+    /// Starts valuing over the given stock price paths, with the option exercised at expiry on each. Each path starts
+    /// now and holds the price at every exercise date, evenly spread until the contract's expiry, the same length for
+    /// every path (e.g from gbm_paths). Any model of the stock can be simulated, such as one with stochastic volatility.
+    /// The polynomial of the regression has terms up to the given degree.
+    pub fn new(option: OptionType, env: &Environment, contract: &Contract, paths: Vec<Vec<f64>>, degree: usize) -> Self {
+        let dates = paths.first().map_or(1, |path| path.len().saturating_sub(1)).max(1);
+        let mut valuation = Self {
+            option,
+            strike: contract.strike,
+            risk_free: env.risk_free,
+            dt: contract.expiry / dates as f64,
+            degree,
+            dates,
+            cash: Vec::new(),
+            exercised: vec![dates; paths.len()],
+            paths,
+            date: dates - 1,
+        };
+        valuation.cash = valuation.paths.iter().map(|path| path.get(dates).map_or(0.0, |&stock| valuation.payoff(stock))).collect();
+        return valuation;
+    }
+
+    fn payoff(&self, stock: f64) -> f64 {
+        match self.option {
+            OptionType::Call => (stock - self.strike).max(0.0),
+            OptionType::Put => (self.strike - stock).max(0.0),
+        }
+    }
+
+    /// Works back one exercise date. Returns true once every date has been worked through.
+    pub fn step(&mut self) -> bool {
+        if self.date == 0 {
+            return true;
+        }
+        let date = self.date;
+        let in_money: Vec<usize> = (0..self.paths.len()).filter(|i| self.payoff(self.paths[*i][date]) > 0.0).collect();
+        // Regressed on the stock price relative to the strike, so the powers stay well scaled
+        let basis = |stock: f64| (0..=self.degree).map(|power| (stock / self.strike).powi(power as i32)).collect::<Vec<f64>>();
+        let samples: Vec<(Vec<f64>, f64)> = in_money.iter().map(|i| {
+            let discount = (-self.risk_free * (self.exercised[*i] - date) as f64 * self.dt).exp();
+            (basis(self.paths[*i][date]), self.cash[*i] * discount)
+        }).collect();
+        if let Some(coefficients) = least_squares(&samples, self.degree + 1) {
+            for (i, (terms, _)) in in_money.iter().zip(&samples) {
+                let continuation: f64 = terms.iter().zip(&coefficients).map(|(term, c)| term * c).sum();
+                let exercise = self.payoff(self.paths[*i][date]);
+                if exercise > continuation {
+                    self.cash[*i] = exercise;
+                    self.exercised[*i] = date;
+                }
+            }
+        }
+        self.date -= 1;
+        return self.date == 0;
+    }
+
+    /// Fraction of the exercise dates worked through, from 0 to 1
+    pub fn progress(&self) -> f64 {
+        if self.dates <= 1 {
+            return 1.0;
+        }
+        return (self.dates - 1 - self.date) as f64 / (self.dates - 1) as f64;
+    }
+
+    /// Price of the option from the dates worked through so far. Exercising straight away is taken if worth more.
+    pub fn estimate(&self) -> LsmEstimate {
+        let values: Vec<f64> = self.cash.iter().zip(&self.exercised)
+            .map(|(cash, date)| cash * (-self.risk_free * *date as f64 * self.dt).exp())
+            .collect();
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+        let immediate = self.paths.first().map_or(0.0, |path| self.payoff(path[0]));
+        if immediate > mean {
+            return LsmEstimate { price: immediate, std_error: 0.0 };
+        }
+        return LsmEstimate { price: mean, std_error: (variance / n).sqrt() };
+    }
+}
+
+/// Coefficients of the least squares fit of each sample's value on its terms, by solving the normal equations.
+/// None if there are too few samples or the terms are degenerate.
+fn least_squares(samples: &[(Vec<f64>, f64)], terms: usize) -> Option<Vec<f64>> {
+    if samples.len() <= terms {
+        return None;
+    }
+    // Augmented matrix of the normal equations [XᵀX | Xᵀy]
+    let mut matrix = vec![vec![0.0; terms + 1]; terms];
+    for (x, y) in samples {
+        for row in 0..terms {
+            for col in 0..terms {
+                matrix[row][col] += x[row] * x[col];
+            }
+            matrix[row][terms] += x[row] * y;
+        }
+    }
+    // Gaussian elimination with partial pivoting
+    for col in 0..terms {
+        let pivot = (col..terms).max_by(|a, b| matrix[*a][col].abs().total_cmp(&matrix[*b][col].abs()))?;
+        matrix.swap(col, pivot);
+        if matrix[col][col].abs() < f64::EPSILON {
+            return None;
+        }
+        let pivot_row = matrix[col].clone();
+        for row in matrix.iter_mut().skip(col + 1) {
+            let factor = row[col] / pivot_row[col];
+            for (value, pivot) in row.iter_mut().zip(&pivot_row).skip(col) {
+                *value -= factor * pivot;
+            }
+        }
+    }
+    let mut coefficients = vec![0.0; terms];
+    for row in (0..terms).rev() {
+        let known: f64 = ((row + 1)..terms).map(|k| matrix[row][k] * coefficients[k]).sum();
+        coefficients[row] = (matrix[row][terms] - known) / matrix[row][row];
+    }
+    return Some(coefficients);
+}
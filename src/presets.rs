@@ -0,0 +1,60 @@
+//! Named sets of the six parameter inputs that can be filled in at once.
+//! A few presets are built in, others are saved by the user into storage.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::Storage;
+
+/// Storage key the user's presets are saved under
+const PRESETS_KEY: &str = "presets";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    /// Values of the six parameter inputs. The prediction end is given in years.
+    pub inputs: [f64; 6],
+    /// True if shipped with the application rather than saved by the user
+    #[serde(skip)]
+    pub built_in: bool,
+}
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.built_in {
+            return write!(f, "{} (built-in)", self.name);
+        }
+        return write!(f, "{}", self.name);
+    }
+}
+
+/// Presets shipped with the application
+pub fn built_in() -> Vec<Preset> {
+    let preset = |name: &str, inputs: [f64; 6]| Preset { name: String::from(name), inputs, built_in: true };
+    return vec![
+        preset("High-vol tech stock", [150.0, 0.6, 0.04, 0.0, 180.0, 0.5]),
+        preset("Low-vol index", [500.0, 0.15, 0.04, 0.015, 520.0, 1.0]),
+        preset("Pre-earnings", [100.0, 0.8, 0.04, 0.005, 110.0, 0.08]),
+    ];
+}
+
+/// Presets previously saved by the user
+pub fn load_user() -> Vec<Preset> {
+    crate::storage::platform_storage().load(PRESETS_KEY)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Saves the user's presets, replacing those previously saved. Built-in presets are skipped.
+pub fn store_user(presets: &[Preset]) -> Result<(), String> {
+    let user: Vec<&Preset> = presets.iter().filter(|p| !p.built_in).collect();
+    let json = serde_json::to_string_pretty(&user).map_err(|e| e.to_string())?;
+    return crate::storage::platform_storage().store(PRESETS_KEY, &json);
+}
+
+/// Built-in presets followed by the user's presets
+pub fn all() -> Vec<Preset> {
+    let mut presets = built_in();
+    presets.extend(load_user());
+    return presets;
+}
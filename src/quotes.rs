@@ -0,0 +1,79 @@
+//! Fetching of live market data to populate the Current Environment inputs.
+//! Only compiled with the `online` feature.
+//!
+//! Data is pulled from Yahoo Finance's public chart endpoint. Note that the endpoint does not
+//! send CORS headers, so on the web build requests will be blocked by most browsers.
+
+use serde_json::Value;
+
+use crate::analytics::close_to_close_vol;
+
+const YAHOO_CHART_URL: &str = "https://query1.finance.yahoo.com/v8/finance/chart/";
+
+/// Market data for a single ticker
+#[derive(Debug, Clone)]
+pub struct Quote {
+    pub ticker: String,
+    /// Latest traded price
+    pub price: f64,
+    /// Trailing 12 month dividend yield. (E.g 2% would be 0.02).
+    pub div_yield: f64,
+    /// Annualised close-to-close volatility over the last year.
+    /// Only an estimate of implied volatility as the free endpoint provides no options data.
+    pub vol_estimate: f64,
+}
+
+/// Fetches the latest quote of the given ticker
+pub async fn fetch_quote(ticker: String) -> Result<Quote, String> {
+    let ticker = ticker.trim().to_uppercase();
+    if ticker.is_empty() {
+        return Err(String::from("No ticker entered"));
+    }
+    let url = format!("{}{}?range=1y&interval=1d&events=div", YAHOO_CHART_URL, ticker);
+
+    let client = {
+        let builder = reqwest::Client::builder();
+        // Yahoo rejects requests without a user agent. Browsers set their own on the web build.
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = builder.user_agent("Mozilla/5.0");
+        builder.build().map_err(|e| e.to_string())?
+    };
+    let body: Value = client.get(&url)
+        .send().await
+        .map_err(|e| e.to_string())?
+        .json().await
+        .map_err(|e| e.to_string())?;
+
+    return parse_chart(ticker, &body);
+}
+
+/// Extracts a Quote from the JSON response of the chart endpoint
+fn parse_chart(ticker: String, body: &Value) -> Result<Quote, String> {
+    let result = &body["chart"]["result"][0];
+    if result.is_null() {
+        let reason = body["chart"]["error"]["description"].as_str().unwrap_or("Unexpected response");
+        return Err(format!("{}: {}", ticker, reason));
+    }
+
+    let price = result["meta"]["regularMarketPrice"].as_f64()
+        .ok_or(format!("{}: No price available", ticker))?;
+
+    // Days without trading data show up as nulls
+    let closes: Vec<f64> = result["indicators"]["quote"][0]["close"].as_array()
+        .map(|closes| closes.iter().filter_map(Value::as_f64).collect())
+        .unwrap_or_default();
+    let vol_estimate = close_to_close_vol(&closes)
+        .ok_or(format!("{}: Not enough price history to estimate volatility", ticker))?;
+
+    // Paid dividends are keyed by timestamp
+    let dividends: f64 = result["events"]["dividends"].as_object()
+        .map(|divs| divs.values().filter_map(|div| div["amount"].as_f64()).sum())
+        .unwrap_or(0.0);
+
+    return Ok(Quote {
+        ticker,
+        price,
+        div_yield: dividends / price,
+        vol_estimate,
+    });
+}
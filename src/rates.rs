@@ -0,0 +1,161 @@
+//! Risk free rate lookup from a government bond yield curve.
+//!
+//! A built-in table of US Treasury yields is used by default. The table can be replaced by editing
+//! the `yield_curve` entry in storage (a JSON file in the config directory on desktop), or with the
+//! `online` feature, by fetching the latest daily curve published by the US Treasury.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::Storage;
+
+/// Storage key the yield curve is saved under
+const YIELD_CURVE_KEY: &str = "yield_curve";
+
+/// Yields of government bonds against their tenor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YieldCurve {
+    /// Date the yields were published. None for the built-in table.
+    pub as_of: Option<NaiveDate>,
+    /// (Tenor in years, annual yield) ordered by tenor. (E.g 4% would be 0.04).
+    pub points: Vec<(f64, f64)>,
+}
+impl Default for YieldCurve {
+    /// Approximate US Treasury par yields. Only meant as a rough starting point.
+    fn default() -> Self {
+        Self {
+            as_of: None,
+            points: vec![
+                (1.0 / 12.0, 0.042),
+                (0.25, 0.041),
+                (0.5, 0.040),
+                (1.0, 0.038),
+                (2.0, 0.036),
+                (3.0, 0.036),
+                (5.0, 0.037),
+                (7.0, 0.039),
+                (10.0, 0.041),
+                (20.0, 0.046),
+                (30.0, 0.046),
+            ],
+        }
+    }
+}
+
+impl YieldCurve {
+    /// The saved yield curve, or the built-in table if none is saved.
+    /// The built-in table is then saved so it can be found and edited.
+    pub fn load() -> Self {
+        let saved = crate::storage::platform_storage().load(YIELD_CURVE_KEY)
+            .and_then(|json| serde_json::from_str::<Self>(&json).ok())
+            .filter(|curve| !curve.points.is_empty())
+            .map(|mut curve| {
+                // Hand edited tables may be out of order
+                curve.points.sort_by(|a, b| a.0.total_cmp(&b.0));
+                curve
+            });
+        return saved.unwrap_or_else(|| {
+            let curve = Self::default();
+            let _ = curve.store();
+            curve
+        });
+    }
+
+    pub fn store(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        return crate::storage::platform_storage().store(YIELD_CURVE_KEY, &json);
+    }
+
+    /// Yield for the given tenor in years. Linearly interpolated between points and held flat
+    /// beyond the shortest and longest tenors.
+    pub fn rate_at(&self, tenor: f64) -> f64 {
+        let (Some(first), Some(last)) = (self.points.first(), self.points.last()) else {
+            return 0.0;
+        };
+        if tenor <= first.0 {
+            return first.1;
+        }
+        for pair in self.points.windows(2) {
+            let ((t0, r0), (t1, r1)) = (pair[0], pair[1]);
+            if tenor <= t1 {
+                return r0 + (r1 - r0) * (tenor - t0) / (t1 - t0);
+            }
+        }
+        return last.1;
+    }
+
+    /// Description of where the yields come from
+    pub fn source(&self) -> String {
+        match self.as_of {
+            Some(date) => format!("Treasury curve of {}", date),
+            None => String::from("built-in curve"),
+        }
+    }
+}
+
+/// Fetches the latest daily par yield curve published by the US Treasury
+#[cfg(feature = "online")]
+pub async fn fetch_treasury_curve() -> Result<YieldCurve, String> {
+    use chrono::Datelike;
+
+    const TREASURY_RATES_URL: &str =
+        "https://home.treasury.gov/resource-center/data-chart-center/interest-rates/daily-treasury-rates.csv";
+
+    // The feed is split by year and will be empty early in January, so fall back to last year
+    let year = crate::calendar::today().year();
+    let mut last_err = String::new();
+    for year in [year, year - 1] {
+        let url = format!(
+            "{}/{}/all?type=daily_treasury_yield_curve&field_tdr_date_value={}&_format=csv",
+            TREASURY_RATES_URL, year, year
+        );
+        let csv = reqwest::get(&url).await
+            .map_err(|e| e.to_string())?
+            .text().await
+            .map_err(|e| e.to_string())?;
+        match parse_treasury_csv(&csv) {
+            Ok(curve) => return Ok(curve),
+            Err(e) => last_err = e,
+        }
+    }
+    return Err(last_err);
+}
+
+/// Extracts the most recent curve from the Treasury's CSV. Columns are "Date" followed by tenors
+/// such as "1 Mo" or "10 Yr", rows are ordered newest first and yields are in percent.
+#[cfg(feature = "online")]
+fn parse_treasury_csv(csv: &str) -> Result<YieldCurve, String> {
+    let mut lines = csv.lines().map(str::trim).filter(|line| !line.is_empty());
+    let header: Vec<&str> = lines.next().ok_or("Empty response")?
+        .split(',')
+        .map(|h| h.trim().trim_matches('"'))
+        .collect();
+    let latest: Vec<&str> = lines.next().ok_or("No yields published")?
+        .split(',')
+        .map(|f| f.trim().trim_matches('"'))
+        .collect();
+
+    let as_of = NaiveDate::parse_from_str(latest[0], "%m/%d/%Y").ok();
+    let mut points: Vec<(f64, f64)> = header.iter().zip(&latest).skip(1)
+        .filter_map(|(name, value)| Some((parse_tenor(name)?, value.parse::<f64>().ok()? / 100.0)))
+        .collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    if points.is_empty() {
+        return Err(String::from("No yields found in response"));
+    }
+    return Ok(YieldCurve { as_of, points });
+}
+
+/// Parses a tenor column name like "6 Mo", "1.5 Month" or "2 Yr" into years
+#[cfg(feature = "online")]
+fn parse_tenor(name: &str) -> Option<f64> {
+    let (amount, unit) = name.split_once(' ')?;
+    let amount: f64 = amount.parse().ok()?;
+    if unit.starts_with("Mo") {
+        return Some(amount / 12.0);
+    }
+    if unit.starts_with("Yr") || unit.starts_with("Year") {
+        return Some(amount);
+    }
+    return None;
+}
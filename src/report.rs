@@ -0,0 +1,80 @@
+//! Standalone HTML reports of a calculation, for sharing a trade plan with someone who doesn't run the app.
+//! Everything is inlined into the one document, with charts embedded as SVG, so it opens in any browser
+//! offline and can be printed to PDF from there.
+
+/// Styling of the report, kept plain so it prints well
+const STYLE: &str = "\
+body { font-family: sans-serif; max-width: 900px; margin: 2em auto; color: #222; }
+h1 { border-bottom: 2px solid #888; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #bbb; padding: 4px 10px; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+th { background: #eee; }
+figure { margin: 1em 0; }
+svg { max-width: 100%; height: auto; }
+.notes { white-space: pre-wrap; border-left: 3px solid #888; padding-left: 1em; }";
+
+/// HTML document built up a section at a time
+#[derive(Debug, Clone)]
+pub struct Report {
+    title: String,
+    body: String,
+}
+impl Report {
+    pub fn new(title: &str) -> Self {
+        return Self {
+            title: title.to_string(),
+            body: String::new(),
+        };
+    }
+
+    pub fn heading(&mut self, heading: &str) -> &mut Self {
+        self.body += &format!("<h2>{}</h2>\n", escape(heading));
+        return self;
+    }
+
+    pub fn paragraph(&mut self, paragraph: &str) -> &mut Self {
+        self.body += &format!("<p>{}</p>\n", escape(paragraph));
+        return self;
+    }
+
+    /// Free text shown with its line breaks kept
+    pub fn notes(&mut self, notes: &str) -> &mut Self {
+        self.body += &format!("<p class=\"notes\">{}</p>\n", escape(notes));
+        return self;
+    }
+
+    /// Table of the given rows, with the first row as the header
+    pub fn table<R: AsRef<[String]>>(&mut self, rows: &[R]) -> &mut Self {
+        self.body += "<table>\n";
+        for (i, row) in rows.iter().enumerate() {
+            let tag = if i == 0 { "th" } else { "td" };
+            self.body += "<tr>";
+            for cell in row.as_ref() {
+                self.body += &format!("<{tag}>{}</{tag}>", escape(cell));
+            }
+            self.body += "</tr>\n";
+        }
+        self.body += "</table>\n";
+        return self;
+    }
+
+    /// Embeds an SVG image as is. The SVG is trusted to come from the app's own charts.
+    pub fn figure(&mut self, svg: &str) -> &mut Self {
+        self.body += &format!("<figure>\n{}\n</figure>\n", svg);
+        return self;
+    }
+
+    pub fn to_html(&self) -> String {
+        return format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n{}</body>\n</html>\n",
+            self.body,
+            title = escape(&self.title),
+        );
+    }
+}
+
+/// Escapes text for placing within HTML elements
+fn escape(text: &str) -> String {
+    return text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;");
+}
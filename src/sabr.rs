@@ -0,0 +1,153 @@
+//! SABR model of the volatility smile, giving each strike its own implied volatility for the Black-Scholes pricer
+//! rather than one flat volatility across every strike. Parameters are fit to the implied volatilities of a few
+//! quoted contracts of the same expiry.
+
+use serde::{Deserialize, Serialize};
+
+use crate::blackscholes::{Contract, Environment};
+
+/// Iterations the calibration's simplex search is given to converge
+const CALIBRATION_ITERATIONS: usize = 2000;
+/// Spread of the simplex's vertices the calibration stops at
+const CALIBRATION_TOLERANCE: f64 = 1e-10;
+/// Distance from the forward within which a strike is taken to be at the money, where the general formula
+/// divides by zero
+const ATM_TOLERANCE: f64 = 1e-7;
+/// Correlations are kept strictly within (-1, 1), as the formula divides by 1 - rho
+const MAX_CORRELATION: f64 = 0.999;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Parameters of the SABR stochastic volatility model, for a single expiry
+pub struct Sabr {
+    /// Initial level of the volatility. Positive.
+    pub alpha: f64,
+    /// Elasticity of the forward's moves to its price, within [0, 1]. 1 is lognormal, 0 is normal.
+    pub beta: f64,
+    /// Correlation between the forward and its volatility, within (-1, 1). Tilts the smile into a skew.
+    pub rho: f64,
+    /// Volatility of the volatility. Non-negative. Curves the smile upwards away from the money.
+    pub nu: f64,
+}
+impl Sabr {
+    /// Black-Scholes implied volatility of the strike for the forward price and time to expiry given,
+    /// by Hagan et al's approximation
+    pub fn implied_vol(&self, forward: f64, strike: f64, expiry: f64) -> f64 {
+        let Self { alpha, beta, rho, nu } = *self;
+        let one_beta = 1.0 - beta;
+        let fk_beta = (forward * strike).powf(one_beta / 2.0);
+        let log_fk = (forward / strike).ln();
+        let correction = 1.0 + expiry * (
+            one_beta.powi(2) / 24.0 * alpha.powi(2) / fk_beta.powi(2)
+            + rho * beta * nu * alpha / (4.0 * fk_beta)
+            + (2.0 - 3.0 * rho.powi(2)) / 24.0 * nu.powi(2)
+        );
+        let z = nu / alpha * fk_beta * log_fk;
+        // z / x(z) tends to 1 towards the money
+        let z_ratio = if z.abs() < ATM_TOLERANCE {
+            1.0
+        } else {
+            let x = (((1.0 - 2.0 * rho * z + z.powi(2)).sqrt() + z - rho) / (1.0 - rho)).ln();
+            z / x
+        };
+        let denominator = fk_beta * (1.0 + one_beta.powi(2) / 24.0 * log_fk.powi(2) + one_beta.powi(4) / 1920.0 * log_fk.powi(4));
+        return alpha / denominator * z_ratio * correction;
+    }
+
+    /// Environment the contract is priced in under the smile. The environment's volatility is swapped for the
+    /// smile's implied volatility at the contract's strike and expiry.
+    pub fn environment(&self, env: &Environment, contract: &Contract) -> Environment {
+        let vol = self.implied_vol(forward(env, contract.expiry), contract.strike, contract.expiry);
+        return Environment { vol, ..env.clone() };
+    }
+
+    /// Root mean squared difference between the smile's implied volatilities and those quoted,
+    /// given as (strike, implied volatility)
+    pub fn rms_error(&self, forward: f64, expiry: f64, quotes: &[(f64, f64)]) -> f64 {
+        let squared: f64 = quotes.iter()
+            .map(|(strike, vol)| (self.implied_vol(forward, *strike, expiry) - vol).powi(2))
+            .sum();
+        return (squared / quotes.len() as f64).sqrt();
+    }
+
+    /// Fits alpha, rho and nu to the implied volatilities quoted at a single expiry, given as (strike, implied volatility),
+    /// by least squares. Beta is fixed as given, as it trades off against rho and is usually picked rather than fit
+    /// (e.g 1 for equities).
+    ///
+    /// Returns None if fewer than three quotes are given, or the fit doesn't settle on a sensible smile.
+    pub fn calibrate(beta: f64, forward: f64, expiry: f64, quotes: &[(f64, f64)]) -> Option<Self> {
+        if quotes.len() < 3 || forward <= 0.0 || expiry <= 0.0 {
+            return None;
+        }
+        // Parameters are searched over unbounded values that map onto their valid ranges
+        let params = |p: &[f64; 3]| Self {
+            alpha: p[0].exp(),
+            beta,
+            rho: MAX_CORRELATION * p[1].tanh(),
+            nu: p[2].exp(),
+        };
+        let cost = |p: &[f64; 3]| {
+            let cost = params(p).rms_error(forward, expiry, quotes);
+            if cost.is_finite() { cost } else { f64::INFINITY }
+        };
+        // Starts off a flat smile at the volatility quoted closest to the money
+        let (_, atm_vol) = quotes.iter()
+            .min_by(|a, b| (a.0 - forward).abs().total_cmp(&(b.0 - forward).abs()))?;
+        let start = [(atm_vol * forward.powf(1.0 - beta)).ln(), 0.0, 0.5f64.ln()];
+        let fitted = params(&nelder_mead(cost, start));
+        let valid = [fitted.alpha, fitted.rho, fitted.nu].iter().all(|x| x.is_finite())
+            && fitted.rms_error(forward, expiry, quotes).is_finite();
+        return valid.then_some(fitted);
+    }
+}
+
+/// Forward price of the stock for delivery after the given time
+pub fn forward(env: &Environment, time: f64) -> f64 {
+    return env.stock * ((env.risk_free - env.div_yield) * time).exp();
+}
+
+/// Minimises the cost over three parameters by the Nelder-Mead simplex method, starting around the given point
+fn nelder_mead(cost: impl Fn(&[f64; 3]) -> f64, start: [f64; 3]) -> [f64; 3] {
+    let mut simplex: Vec<([f64; 3], f64)> = (0..4).map(|i| {
+        let mut point = start;
+        if i > 0 {
+            point[i - 1] += 0.5;
+        }
+        (point, cost(&point))
+    }).collect();
+    // Moves a point along the line from the centroid through it by the given factor
+    let along = |centroid: &[f64; 3], point: &[f64; 3], factor: f64| -> [f64; 3] {
+        std::array::from_fn(|i| centroid[i] + factor * (point[i] - centroid[i]))
+    };
+    for _ in 0..CALIBRATION_ITERATIONS {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        if (simplex[3].1 - simplex[0].1).abs() < CALIBRATION_TOLERANCE {
+            break;
+        }
+        let centroid: [f64; 3] = std::array::from_fn(|i| simplex[..3].iter().map(|(p, _)| p[i]).sum::<f64>() / 3.0);
+        let worst = simplex[3];
+        let reflected = along(&centroid, &worst.0, -1.0);
+        let reflected_cost = cost(&reflected);
+        if reflected_cost < simplex[0].1 {
+            let expanded = along(&centroid, &worst.0, -2.0);
+            let expanded_cost = cost(&expanded);
+            simplex[3] = if expanded_cost < reflected_cost { (expanded, expanded_cost) } else { (reflected, reflected_cost) };
+        } else if reflected_cost < simplex[2].1 {
+            simplex[3] = (reflected, reflected_cost);
+        } else {
+            let contracted = along(&centroid, &worst.0, 0.5);
+            let contracted_cost = cost(&contracted);
+            if contracted_cost < worst.1 {
+                simplex[3] = (contracted, contracted_cost);
+            } else {
+                // Shrinks everything towards the best point
+                let best = simplex[0].0;
+                for vertex in simplex.iter_mut().skip(1) {
+                    vertex.0 = along(&best, &vertex.0, 0.5);
+                    vertex.1 = cost(&vertex.0);
+                }
+            }
+        }
+    }
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    return simplex[0].0;
+}
@@ -0,0 +1,89 @@
+//! Serializable snapshot of everything the user has set up in the calculator, so it can be
+//! saved to a JSON file and restored later.
+
+use std::ops::RangeInclusive;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{Adjustables, PayoffYAxis};
+use crate::custom_widgets::{ChartConfig, SliderConfig};
+use crate::blackscholes::{Contract, Environment, Movement, VolEvent};
+use crate::calendar::DayCount;
+use crate::underlying::Underlying;
+use crate::units::{TimeUnit, VolUnit};
+
+/// Version of the scenario format written by this build
+pub const SCENARIO_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub version: u32,
+    /// Raw text of the six parameter input boxes
+    pub inputs: [String; 6],
+    /// Units the inputs were entered in. Scenarios saved before units existed use the defaults.
+    #[serde(default)]
+    pub vol_unit: VolUnit,
+    #[serde(default)]
+    pub time_unit: TimeUnit,
+    pub use_dates: bool,
+    /// Kind of asset the options are on. Stocks for scenarios saved before other kinds existed.
+    #[serde(default)]
+    pub underlying: Underlying,
+    /// Raw text of the prediction end date input box
+    pub horizon_date: String,
+    pub day_count: DayCount,
+    /// Free text notes on the reasoning behind the prediction. Empty for scenarios saved before notes existed.
+    #[serde(default)]
+    pub notes: String,
+    /// True if a volatility event was expected. False for scenarios saved before events existed.
+    #[serde(default)]
+    pub use_event: bool,
+    /// Raw text of the event time and post-event volatility input boxes
+    #[serde(default)]
+    pub event_inputs: [String; 2],
+    /// State of the last calculation. None if Calculate was never pressed.
+    pub calculation: Option<Calculation>,
+    /// Variables of the open sliders, in order
+    pub sliders: Vec<Adjustables>,
+    /// Axes of the open charts, in order
+    pub charts: Vec<(PayoffYAxis, Adjustables)>,
+    /// Settings of the open sliders, in the same order. Empty for scenarios saved before these were kept.
+    #[serde(default)]
+    pub slider_configs: Vec<SliderConfig>,
+    /// Settings of the open charts, in the same order. Empty for scenarios saved before these were kept.
+    #[serde(default)]
+    pub chart_configs: Vec<ChartConfig>,
+}
+
+/// Results of a calculation alongside any slider overrides applied on top of it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Calculation {
+    /// See OptionCalculator::answers for the layout
+    pub answers: (bool, Contract, f64, f64, f64),
+    /// See OptionCalculator::alternative. None for scenarios saved before both option types were compared.
+    #[serde(default)]
+    pub alternative: Option<(bool, Contract, f64, f64, f64)>,
+    pub start_env: Environment,
+    pub end_env: Environment,
+    /// See OptionCalculator::vol_event
+    #[serde(default)]
+    pub vol_event: Option<VolEvent>,
+    pub movement: Movement,
+    pub contract: Contract,
+    pub ranges: [RangeInclusive<f64>; Adjustables::COUNT],
+    pub valuation_date: Option<NaiveDate>,
+}
+impl Scenario {
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string_pretty(self).expect("scenario is always serializable");
+    }
+
+    pub fn from_json(bytes: &[u8]) -> Result<Self, String> {
+        let scenario: Self = serde_json::from_slice(bytes).map_err(|e| e.to_string())?;
+        if scenario.version > SCENARIO_VERSION {
+            return Err(String::from("Scenario was saved by a newer version of the app"));
+        }
+        return Ok(scenario);
+    }
+}
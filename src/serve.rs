@@ -0,0 +1,164 @@
+//! Headless HTTP mode exposing the pricing core as a small JSON API. Only compiled with the `serve` feature.
+//!
+//! Started with `which_option --serve [ADDRESS]` (default address is `127.0.0.1:8080`). Every endpoint
+//! takes a JSON body by POST:
+//!  - `/price`: `{"is_call", "env", "contract"}` to `{"price", "buy", "sell"}`
+//!  - `/optimize`: `{"start_env", "end_env"?, "movement"}` to the answer the calculator would give (the better
+//!    of the best call and best put)
+//!  - `/sweep`: `{"is_call", "start_env", "end_env"?, "movement", "contract", "y_axis", "x_axis", "range", "samples"?}`
+//!    to the points of the equivalent payoff chart
+//!
+//! Environments, contracts and movements use the same field names as the blackscholes module. Errors
+//! are given as `{"error": "..."}` with a 4xx status.
+
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::blackscholes::{Contract, Environment, Movement, OptionType, VolEvent};
+use crate::{Adjustables, Parameterisation, PayoffYAxis, exit_env, solve};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+/// Samples taken by a sweep if not specified. Same as the default number of points in a payoff chart.
+const DEFAULT_SAMPLES: usize = 501;
+const MAX_SAMPLES: usize = 10_000;
+
+#[derive(Deserialize)]
+struct PriceRequest {
+    is_call: bool,
+    env: Environment,
+    contract: Contract,
+}
+
+#[derive(Deserialize)]
+struct OptimizeRequest {
+    start_env: Environment,
+    /// Defaults to start_env
+    end_env: Option<Environment>,
+    movement: Movement,
+    /// Volatility event replacing end_env's volatility once passed. None if no event is expected.
+    #[serde(default)]
+    event: Option<VolEvent>,
+}
+
+#[derive(Serialize)]
+struct OptimizeResponse {
+    is_call: bool,
+    contract: Contract,
+    buy: f64,
+    sell: f64,
+    roi: f64,
+    /// Caveats about the answer. Same as shown in the status bar of the GUI.
+    warnings: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SweepRequest {
+    is_call: bool,
+    start_env: Environment,
+    /// Defaults to start_env
+    end_env: Option<Environment>,
+    movement: Movement,
+    contract: Contract,
+    y_axis: PayoffYAxis,
+    x_axis: Adjustables,
+    range: (f64, f64),
+    samples: Option<usize>,
+    /// See OptimizeRequest::event
+    #[serde(default)]
+    event: Option<VolEvent>,
+}
+
+/// Address to serve on if `--serve` was passed on the command line
+pub fn requested_address() -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--serve");
+    args.next()?;
+    return Some(args.next().unwrap_or(String::from(DEFAULT_ADDRESS)));
+}
+
+/// Serves requests on the given address until the process is killed
+pub fn run(address: &str) -> Result<(), String> {
+    let server = tiny_http::Server::http(address).map_err(|e| e.to_string())?;
+    println!("Serving pricing API on http://{}", address);
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let (status, response) = if *request.method() != tiny_http::Method::Post {
+            (405, json!({"error": "Only POST requests are supported"}))
+        } else if let Err(e) = request.as_reader().read_to_string(&mut body) {
+            (400, json!({"error": e.to_string()}))
+        } else {
+            match handle(request.url(), &body) {
+                Ok(response) => (200, response),
+                Err((status, e)) => (status, json!({"error": e})),
+            }
+        };
+        let response = tiny_http::Response::from_string(response.to_string())
+            .with_status_code(status)
+            .with_header(header("Content-Type", "application/json"))
+            .with_header(header("Access-Control-Allow-Origin", "*"));
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to respond: {}", e);
+        }
+    }
+    return Ok(());
+}
+
+fn header(field: &str, value: &str) -> tiny_http::Header {
+    tiny_http::Header::from_bytes(field, value).expect("header is valid ASCII")
+}
+
+fn parse<'a, T: Deserialize<'a>>(body: &'a str) -> Result<T, (u16, String)> {
+    serde_json::from_str(body).map_err(|e| (400, e.to_string()))
+}
+
+/// Computes the response to a request body sent to the url
+fn handle(url: &str, body: &str) -> Result<serde_json::Value, (u16, String)> {
+    match url.split('?').next().unwrap_or_default() {
+        "/price" => {
+            let request: PriceRequest = parse(body)?;
+            let pricer = OptionType::from_is_call(request.is_call);
+            let (price, buy, sell) = (
+                pricer.bsm_price(&request.env, &request.contract),
+                pricer.bsm_price_buy(&request.env, &request.contract),
+                pricer.bsm_price_sell(&request.env, &request.contract),
+            );
+            return Ok(json!({
+                "price": price,
+                "buy": buy.to_f64().unwrap_or(0.0),
+                "sell": sell.to_f64().unwrap_or(0.0),
+            }));
+        }
+        "/optimize" => {
+            let request: OptimizeRequest = parse(body)?;
+            let end_env = request.end_env.unwrap_or(request.start_env.clone());
+            let end_env = exit_env(&end_env, &request.movement, request.event);
+            let ((is_call, contract, buy, sell, roi), diagnostics) = solve(&request.start_env, &end_env, &request.movement);
+            return Ok(json!(OptimizeResponse { is_call, contract, buy, sell, roi, warnings: diagnostics.warnings }));
+        }
+        "/sweep" => {
+            let request: SweepRequest = parse(body)?;
+            let samples = request.samples.unwrap_or(DEFAULT_SAMPLES);
+            if !(2..=MAX_SAMPLES).contains(&samples) {
+                return Err((400, format!("samples must be between 2 and {}", MAX_SAMPLES)));
+            }
+            let end_env = request.end_env.unwrap_or(request.start_env.clone());
+            let func = Parameterisation {
+                pricer: OptionType::from_is_call(request.is_call),
+                out: request.y_axis,
+                var: request.x_axis,
+                start_env: request.start_env,
+                end_env,
+                contract: request.contract,
+                movement: request.movement,
+                event: request.event,
+            }.into_func();
+            let (start, end) = request.range;
+            let points: Vec<(f64, f64)> = (0..samples)
+                .map(|i| start + (end - start) * i as f64 / (samples - 1) as f64)
+                .map(|x| (x, func(x)))
+                .collect();
+            return Ok(json!({"points": points}));
+        }
+        _ => return Err((404, String::from("Unknown endpoint"))),
+    }
+}
@@ -0,0 +1,311 @@
+//! Application wide preferences, persisted between sessions through the platform storage.
+
+use std::fmt;
+
+use iced::{Color, Theme};
+use iced::theme::Palette;
+use serde::{Deserialize, Serialize};
+
+use crate::custom_widgets::ChartStyle;
+use crate::storage::Storage;
+use crate::units::{TimeUnit, VolUnit};
+
+/// Storage key the settings are saved under
+const SETTINGS_KEY: &str = "settings";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Name of the chosen theme
+    pub theme: String,
+    /// Factor every size in the interface is scaled by
+    pub scale: UiScale,
+    /// Recalculate automatically whenever the inputs change
+    pub live: bool,
+    /// Number of shares a single option contract covers
+    pub multiplier: f64,
+    /// Whether nominal charts show prices per share or per contract
+    pub nominal_scale: NominalScale,
+    /// Number of columns the payoff charts are laid out in
+    pub chart_columns: ChartColumns,
+    /// Number of points sampled for payoff charts without a resolution of their own
+    pub chart_resolution: ChartResolution,
+    /// Set of line colours charts are drawn with
+    pub chart_palette: ChartPalette,
+    /// Decimal places numbers are shown to
+    pub precision: Precision,
+    /// Units the volatility and duration are entered in at the start of a new session
+    pub vol_unit: VolUnit,
+    pub time_unit: TimeUnit,
+    /// How far slider and chart ranges extend either side of the answer by default
+    pub range_span: RangeSpan,
+    /// Width of the input panel left of the charts, in logical pixels
+    pub panel_width: f32,
+    /// Convention FX option premiums are shown in
+    pub premium_quote: PremiumQuote,
+}
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::Light.to_string(),
+            scale: UiScale(1.0),
+            live: false,
+            multiplier: 100.0,
+            nominal_scale: NominalScale::PerShare,
+            chart_columns: ChartColumns::One,
+            chart_resolution: ChartResolution(501),
+            chart_palette: ChartPalette::Standard,
+            precision: Precision::default(),
+            vol_unit: VolUnit::default(),
+            time_unit: TimeUnit::default(),
+            range_span: RangeSpan(1.0),
+            panel_width: 350.0,
+            premium_quote: PremiumQuote::default(),
+        }
+    }
+}
+
+/// Decimal places each kind of number is shown to
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Precision {
+    /// Values typed into the inputs and sliders, and the variables on chart x-axes
+    pub inputs: usize,
+    /// Prices of options and stocks
+    pub prices: usize,
+    pub roi: usize,
+    pub greeks: usize,
+}
+impl Default for Precision {
+    fn default() -> Self {
+        Self {
+            inputs: 3,
+            prices: 2,
+            roi: 3,
+            greeks: 4,
+        }
+    }
+}
+impl Precision {
+    /// Decimal places that can be chosen for each kind of number
+    pub const CHOICES: [usize; 7] = [0, 1, 2, 3, 4, 5, 6];
+}
+
+/// Scale factor of the interface. (E.g 1.5 draws everything 50% larger).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UiScale(pub f32);
+impl fmt::Display for UiScale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.0}%", self.0 * 100.0)
+    }
+}
+impl UiScale {
+    const COUNT: usize = 9;
+
+    pub fn everything() -> [Self; Self::COUNT] {
+        [0.75, 1.0, 1.25, 1.5, 1.75, 2.0, 2.25, 2.5, 3.0].map(Self)
+    }
+}
+
+/// Number of points sampled along the x-axis of a payoff chart
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChartResolution(pub usize);
+impl fmt::Display for ChartResolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} points", self.0)
+    }
+}
+impl ChartResolution {
+    const COUNT: usize = 5;
+
+    pub fn everything() -> [Self; Self::COUNT] {
+        [101, 251, 501, 1001, 2001].map(Self)
+    }
+}
+
+/// Fraction of a value the default slider and chart ranges extend either side of it.
+/// (E.g 1.0 ranges a strike of 10 over 0 to 20).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RangeSpan(pub f64);
+impl fmt::Display for RangeSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "±{:.0}%", self.0 * 100.0)
+    }
+}
+impl RangeSpan {
+    const COUNT: usize = 4;
+
+    pub fn everything() -> [Self; Self::COUNT] {
+        [0.25, 0.5, 1.0, 2.0].map(Self)
+    }
+
+    /// Range over the value spread either side of it, never below 0
+    pub fn around(&self, value: f64) -> std::ops::RangeInclusive<f64> {
+        return (value * (1.0 - self.0)).max(0.0)..=value * (1.0 + self.0);
+    }
+}
+
+/// Set of line colours charts are drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ChartPalette {
+    Standard,
+    /// Colours that stay distinguishable with colour blindness
+    ColorBlind,
+}
+impl fmt::Display for ChartPalette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Standard => "Standard colours",
+            Self::ColorBlind => "Colour-blind safe",
+        })
+    }
+}
+impl ChartPalette {
+    const COUNT: usize = 2;
+
+    pub fn everything() -> [Self; Self::COUNT] {
+        [Self::Standard, Self::ColorBlind]
+    }
+}
+
+/// Scale of the prices in nominal charts
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NominalScale {
+    PerShare,
+    /// Prices multiplied by the contract multiplier
+    PerContract,
+}
+impl fmt::Display for NominalScale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::PerShare => "Per share",
+            Self::PerContract => "Per contract",
+        })
+    }
+}
+impl NominalScale {
+    const COUNT: usize = 2;
+
+    pub fn everything() -> [Self; Self::COUNT] {
+        [Self::PerShare, Self::PerContract]
+    }
+}
+
+/// Convention the premiums of FX options are quoted in
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum PremiumQuote {
+    /// Domestic currency per unit of foreign, the same as the spot rate
+    #[default]
+    Price,
+    /// Domestic pips (ten thousandths of the domestic currency) per unit of foreign
+    Pips,
+    /// Percentage of the foreign notional, at the spot rate when traded
+    Percent,
+}
+impl fmt::Display for PremiumQuote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Price => "Domestic per foreign",
+            Self::Pips => "Domestic pips",
+            Self::Percent => "% of foreign notional",
+        })
+    }
+}
+impl PremiumQuote {
+    const COUNT: usize = 3;
+
+    pub fn everything() -> [Self; Self::COUNT] {
+        [Self::Price, Self::Pips, Self::Percent]
+    }
+
+    /// Formats a premium in domestic currency per unit of foreign, traded at the given spot rate
+    pub fn format(&self, premium: f64, spot: f64, precision: &Precision) -> String {
+        match self {
+            Self::Price => format!("{:.*}", precision.prices, premium),
+            Self::Pips => format!("{:.1} pips", premium * 10_000.0),
+            Self::Percent => format!("{:.*}%", precision.roi, premium / spot * 100.0),
+        }
+    }
+}
+
+/// Number of columns in the grid of payoff charts
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ChartColumns {
+    One,
+    Two,
+}
+impl fmt::Display for ChartColumns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::One => "1 column",
+            Self::Two => "2 columns",
+        })
+    }
+}
+impl ChartColumns {
+    const COUNT: usize = 2;
+
+    pub fn everything() -> [Self; Self::COUNT] {
+        [Self::One, Self::Two]
+    }
+
+    pub fn count(&self) -> usize {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+}
+
+impl Settings {
+    /// The saved settings, or the defaults if none are saved. Missing fields take their default.
+    pub fn load() -> Self {
+        crate::storage::platform_storage().load(SETTINGS_KEY)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn store(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        return crate::storage::platform_storage().store(SETTINGS_KEY, &json);
+    }
+
+    /// Factor nominal chart prices are multiplied by
+    pub fn nominal_factor(&self) -> f64 {
+        match self.nominal_scale {
+            NominalScale::PerShare => 1.0,
+            NominalScale::PerContract => self.multiplier,
+        }
+    }
+
+    /// Style of the charts for the chosen chart palette on top of the colours of a theme
+    pub fn chart_style(&self, palette: Palette) -> ChartStyle {
+        match self.chart_palette {
+            ChartPalette::Standard => ChartStyle::from_palette(palette),
+            ChartPalette::ColorBlind => ChartStyle::color_blind(palette),
+        }
+    }
+
+    /// The chosen theme out of all_themes. Falls back to the light theme if it no longer exists.
+    pub fn theme(&self, themes: &[Theme]) -> Theme {
+        themes.iter()
+            .find(|theme| theme.to_string() == self.theme)
+            .cloned()
+            .unwrap_or(Theme::Light)
+    }
+}
+
+/// The application's own dark theme followed by every built-in theme of iced
+pub fn all_themes() -> Vec<Theme> {
+    let dark = Theme::custom("Which Option Dark", Palette {
+        background: Color::from_rgb8(0x1b, 0x1e, 0x24),
+        text: Color::from_rgb8(0xe4, 0xe6, 0xeb),
+        primary: Color::from_rgb8(0x00, 0xaf, 0xff),
+        success: Color::from_rgb8(0x3f, 0xb9, 0x50),
+        warning: Color::from_rgb8(0xe3, 0xb3, 0x41),
+        danger: Color::from_rgb8(0xdc, 0x14, 0x14),
+    });
+    let mut themes = vec![dark];
+    themes.extend(Theme::ALL.iter().cloned());
+    return themes;
+}
@@ -0,0 +1,190 @@
+//! Compact encoding of a calculator setup into a URL fragment, so a link on the web build
+//! can reproduce the exact same inputs, slider overrides and charts for someone else.
+//!
+//! Fragments look like `i=15,0.5,0.04,0,15,0.5;d=2027-01-01,0;s=0:11.87:0:30;c=0.0,1.2` where
+//!  - `i`: Raw text of the six parameter inputs
+//!  - `u`: Volatility and duration unit indices (only when not the defaults)
+//!  - `d`: Prediction end date and day count convention index (only when entered as a date)
+//!  - `s`: Sliders as `variable:value:min:max`
+//!  - `c`: Charts as `y_axis.x_axis`
+//!
+//! Variables, axes and conventions are encoded by their index within their `everything()` listing.
+
+use std::ops::RangeInclusive;
+
+use crate::{Adjustables, PayoffYAxis};
+use crate::calendar::DayCount;
+use crate::units::{TimeUnit, VolUnit};
+
+#[derive(Debug, Clone, Default)]
+pub struct SharedSetup {
+    /// Raw text of the six parameter input boxes
+    pub inputs: [String; 6],
+    /// Units the volatility and prediction duration inputs are entered in
+    pub units: (VolUnit, TimeUnit),
+    /// Raw text of the prediction end date and its convention. None if the end is entered as a duration.
+    pub horizon: Option<(String, DayCount)>,
+    /// Open sliders with their value and slider range
+    pub sliders: Vec<(Adjustables, f64, RangeInclusive<f64>)>,
+    /// Axes of the open charts
+    pub charts: Vec<(PayoffYAxis, Adjustables)>,
+}
+
+/// Index of a value within a listing of every possible value
+fn index_of<T: PartialEq>(all: &[T], value: &T) -> usize {
+    all.iter().position(|x| x == value).unwrap_or(0)
+}
+
+/// Value at the encoded index within a listing of every possible value
+fn from_index<T: Copy>(all: &[T], encoded: &str) -> Option<T> {
+    all.get(encoded.parse::<usize>().ok()?).copied()
+}
+
+impl SharedSetup {
+    pub fn to_fragment(&self) -> String {
+        // Thousands separators and spaces are dropped, as commas separate the inputs
+        let inputs: Vec<String> = self.inputs.iter()
+            .map(|input| input.chars().filter(|c| *c != ',' && !c.is_whitespace()).collect())
+            .collect();
+        let mut parts = vec![format!("i={}", inputs.join(","))];
+        if self.units != Default::default() {
+            let (vol_unit, time_unit) = &self.units;
+            parts.push(format!("u={},{}",
+                index_of(&VolUnit::everything(), vol_unit),
+                index_of(&TimeUnit::everything(), time_unit)));
+        }
+        if let Some((date, day_count)) = &self.horizon {
+            parts.push(format!("d={},{}", date, index_of(&DayCount::everything(), day_count)));
+        }
+        if !self.sliders.is_empty() {
+            parts.push(format!("s={}", self.sliders.iter()
+                .map(|(adj, value, range)| format!("{}:{}:{}:{}", *adj as usize, value, range.start(), range.end()))
+                .collect::<Vec<String>>()
+                .join(",")));
+        }
+        if !self.charts.is_empty() {
+            parts.push(format!("c={}", self.charts.iter()
+                .map(|(y, x)| format!("{}.{}", index_of(&PayoffYAxis::everything(), y), *x as usize))
+                .collect::<Vec<String>>()
+                .join(",")));
+        }
+        return parts.join(";");
+    }
+
+    /// Parses a fragment produced by to_fragment. A leading '#' is ignored.
+    ///
+    /// Returns None if the fragment is malformed.
+    pub fn from_fragment(fragment: &str) -> Option<Self> {
+        let mut setup = Self::default();
+        let mut has_inputs = false;
+        for part in fragment.trim_start_matches('#').split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=')?;
+            match key {
+                "i" => {
+                    let inputs: Vec<String> = value.split(',').map(String::from).collect();
+                    setup.inputs = inputs.try_into().ok()?;
+                    has_inputs = true;
+                }
+                "u" => {
+                    let (vol_unit, time_unit) = value.split_once(',')?;
+                    setup.units = (
+                        from_index(&VolUnit::everything(), vol_unit)?,
+                        from_index(&TimeUnit::everything(), time_unit)?,
+                    );
+                }
+                "d" => {
+                    let (date, day_count) = value.split_once(',')?;
+                    setup.horizon = Some((date.to_string(), from_index(&DayCount::everything(), day_count)?));
+                }
+                "s" => {
+                    for slider in value.split(',') {
+                        let fields: Vec<&str> = slider.split(':').collect();
+                        let [adj, val, start, end] = fields[..] else {
+                            return None;
+                        };
+                        setup.sliders.push((
+                            from_index(&Adjustables::everything(), adj)?,
+                            val.parse().ok()?,
+                            start.parse().ok()?..=end.parse().ok()?,
+                        ));
+                    }
+                }
+                "c" => {
+                    for chart in value.split(',') {
+                        let (y, x) = chart.split_once('.')?;
+                        setup.charts.push((
+                            from_index(&PayoffYAxis::everything(), y)?,
+                            from_index(&Adjustables::everything(), x)?,
+                        ));
+                    }
+                }
+                _ => return None,
+            }
+        }
+        return has_inputs.then_some(setup);
+    }
+}
+
+/// Simple prefill of the parameter inputs from a URL query (e.g `?spot=150&vol=0.3&target=170&days=30`), for
+/// links written by hand or by other sites rather than produced by "Share". Values are in decimals whatever
+/// units the inputs are entered in:
+///  - `spot`, `vol`, `rate` and `yield`: The current environment
+///  - `target`: Predicted stock price
+///  - `days` or `years`: Prediction duration
+///  - `calculate`: Calculates straight away if given (any value other than `0` or `false`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryPrefill {
+    /// Values of the six parameter inputs given, in decimals and years
+    pub inputs: [Option<f64>; 6],
+    pub calculate: bool,
+}
+
+impl QueryPrefill {
+    /// Parses a URL query. A leading '?' is ignored, as are parameters that aren't recognised.
+    ///
+    /// Returns None if nothing is prefilled.
+    pub fn from_query(query: &str) -> Option<Self> {
+        let mut prefill = Self::default();
+        for param in query.trim_start_matches('?').split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            if key == "calculate" {
+                prefill.calculate = value != "0" && value != "false";
+                continue;
+            }
+            let Ok(number) = value.parse::<f64>() else {
+                continue;
+            };
+            let (i, number) = match key {
+                "spot" => (0, number),
+                "vol" => (1, number),
+                "rate" => (2, number),
+                "yield" => (3, number),
+                "target" => (4, number),
+                "days" => (5, number / 365.0),
+                "years" => (5, number),
+                _ => continue,
+            };
+            prefill.inputs[i] = Some(number);
+        }
+        return (prefill != Self::default()).then_some(prefill);
+    }
+}
+
+/// Reads the query of the page's current URL
+pub fn read_url_query() -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+    return (!search.is_empty()).then_some(search);
+}
+
+/// Reads the fragment of the page's current URL
+pub fn read_url_fragment() -> Option<String> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    return (!hash.is_empty()).then_some(hash);
+}
+
+/// Replaces the fragment of the page's current URL. Returns the full resulting URL.
+pub fn write_url_fragment(fragment: &str) -> Option<String> {
+    let location = web_sys::window()?.location();
+    location.set_hash(fragment).ok()?;
+    return location.href().ok();
+}
@@ -0,0 +1,98 @@
+//! Persistent key-value storage of application state between sessions.
+//! Desktop builds write files into the platform config directory, web builds use the browser's LocalStorage.
+
+/// Backend able to persist small text values under string keys
+pub trait Storage {
+    /// Retrieves the value stored under key. None if nothing is stored or it can't be read.
+    fn load(&self, key: &str) -> Option<String>;
+    fn store(&self, key: &str, value: &str) -> Result<(), String>;
+    /// Removes the value stored under key. Removing a missing key is not an error.
+    fn remove(&self, key: &str) -> Result<(), String>;
+}
+
+/// Name of the folder/prefix all values are stored under
+const APP_NAME: &str = "which_option";
+
+/// Stores each value as a JSON file within the platform config directory
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage {
+    dir: Option<std::path::PathBuf>,
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+    pub fn new() -> Self {
+        Self { dir: dirs::config_dir().map(|dir| dir.join(APP_NAME)) }
+    }
+
+    fn path(&self, key: &str) -> Result<std::path::PathBuf, String> {
+        let dir = self.dir.as_ref().ok_or("No config directory on this platform")?;
+        return Ok(dir.join(format!("{}.json", key)));
+    }
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for FileStorage {
+    fn load(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.path(key).ok()?).ok()
+    }
+
+    fn store(&self, key: &str, value: &str) -> Result<(), String> {
+        let path = self.path(key)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        return std::fs::write(path, value).map_err(|e| e.to_string());
+    }
+
+    fn remove(&self, key: &str) -> Result<(), String> {
+        match std::fs::remove_file(self.path(key)?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Stores each value within the browser's LocalStorage
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage;
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn local_storage() -> Result<web_sys::Storage, String> {
+        web_sys::window()
+            .ok_or("No browser window")?
+            .local_storage()
+            .map_err(|e| format!("{:?}", e))?
+            .ok_or(String::from("LocalStorage is unavailable"))
+    }
+
+    fn full_key(key: &str) -> String {
+        format!("{}.{}", APP_NAME, key)
+    }
+}
+#[cfg(target_arch = "wasm32")]
+impl Storage for LocalStorage {
+    fn load(&self, key: &str) -> Option<String> {
+        Self::local_storage().ok()?.get_item(&Self::full_key(key)).ok()?
+    }
+
+    fn store(&self, key: &str, value: &str) -> Result<(), String> {
+        Self::local_storage()?.set_item(&Self::full_key(key), value).map_err(|e| format!("{:?}", e))
+    }
+
+    fn remove(&self, key: &str) -> Result<(), String> {
+        Self::local_storage()?.remove_item(&Self::full_key(key)).map_err(|e| format!("{:?}", e))
+    }
+}
+
+/// The storage backend of the platform being built for
+#[cfg(not(target_arch = "wasm32"))]
+pub fn platform_storage() -> impl Storage {
+    FileStorage::new()
+}
+#[cfg(target_arch = "wasm32")]
+pub fn platform_storage() -> impl Storage {
+    LocalStorage::new()
+}
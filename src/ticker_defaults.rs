@@ -0,0 +1,39 @@
+//! Typical current environment inputs of a ticker (e.g its usual implied volatility and dividend yield),
+//! saved by the user into storage. Unlike presets and scenarios, recalling them leaves the prediction alone.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::Storage;
+
+/// Storage key the ticker defaults are saved under
+const TICKER_DEFAULTS_KEY: &str = "ticker_defaults";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickerDefaults {
+    /// Ticker symbol, in upper case
+    pub ticker: String,
+    /// Values of the stock price, volatility, risk free rate and dividend yield inputs
+    pub inputs: [f64; 4],
+}
+impl fmt::Display for TickerDefaults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} defaults", self.ticker)
+    }
+}
+
+/// Ticker defaults previously saved, in ticker order
+pub fn load() -> Vec<TickerDefaults> {
+    let mut defaults: Vec<TickerDefaults> = crate::storage::platform_storage().load(TICKER_DEFAULTS_KEY)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    defaults.sort_by(|a, b| a.ticker.cmp(&b.ticker));
+    return defaults;
+}
+
+/// Saves the ticker defaults, replacing those previously saved
+pub fn store(defaults: &[TickerDefaults]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(defaults).map_err(|e| e.to_string())?;
+    return crate::storage::platform_storage().store(TICKER_DEFAULTS_KEY, &json);
+}
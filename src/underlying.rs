@@ -0,0 +1,87 @@
+//! Kinds of asset the options can be written on. Each is priced by its own variant of Black-Scholes, which only
+//! differ in what the dividend yield slot of the environment holds, so the inputs are relabelled to match.
+
+use serde::{Deserialize, Serialize};
+
+use crate::blackscholes::Environment;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Underlying {
+    /// Priced by Black-Scholes
+    #[default]
+    Stock,
+    /// Futures contract, priced by Black-76
+    Futures,
+    /// Currency pair, priced by Garman-Kohlhagen. The price is the spot rate in domestic currency per unit of foreign.
+    Fx,
+}
+impl std::fmt::Display for Underlying {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Stock => "Stock",
+            Self::Futures => "Futures (Black-76)",
+            Self::Fx => "FX (Garman-Kohlhagen)",
+        })
+    }
+}
+impl Underlying {
+    const COUNT: usize = 3;
+
+    pub fn everything() -> [Self; Self::COUNT] {
+        [Self::Stock, Self::Futures, Self::Fx]
+    }
+
+    /// Name of the pricing model the underlying is priced by
+    pub fn model_name(&self) -> &'static str {
+        match self {
+            Self::Stock => "Black-Scholes",
+            Self::Futures => "Black-76",
+            Self::Fx => "Garman-Kohlhagen",
+        }
+    }
+
+    /// Label of the price input
+    pub fn price_name(&self) -> &'static str {
+        match self {
+            Self::Stock => "Stock price",
+            Self::Futures => "Futures price",
+            Self::Fx => "Spot rate",
+        }
+    }
+
+    /// Label of the predicted price input
+    pub fn prediction_name(&self) -> &'static str {
+        match self {
+            Self::Stock => "Prediction stock price",
+            Self::Futures => "Prediction futures price",
+            Self::Fx => "Prediction spot rate",
+        }
+    }
+
+    /// Label of the risk free rate input
+    pub fn rate_name(&self) -> &'static str {
+        match self {
+            Self::Fx => "Domestic risk free rate",
+            _ => "Risk free rate",
+        }
+    }
+
+    /// Label of the dividend yield input, or None if the underlying has no use for it
+    pub fn yield_name(&self) -> Option<&'static str> {
+        match self {
+            Self::Stock => Some("Dividend yield"),
+            Self::Futures => None,
+            Self::Fx => Some("Foreign risk free rate"),
+        }
+    }
+
+    /// Environment the options are priced in, from the values of the price, volatility, rate and yield inputs.
+    /// The yield is ignored if the underlying has no use for it.
+    pub fn environment(&self, price: f64, vol: f64, risk_free: f64, yield_: f64) -> Environment {
+        match self {
+            Self::Stock => Environment { stock: price, risk_free, vol, div_yield: yield_ },
+            Self::Futures => Environment::black76(price, risk_free, vol),
+            Self::Fx => Environment::garman_kohlhagen(price, risk_free, yield_, vol),
+        }
+    }
+}
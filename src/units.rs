@@ -0,0 +1,127 @@
+//! Units the volatility and prediction duration inputs can be entered in. The pricing model
+//! always works in decimals and years, so entered values are converted on the way in and out.
+
+use serde::{Deserialize, Serialize};
+
+/// Unit a volatility is entered in
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum VolUnit {
+    /// E.g 0.3 for 30%
+    #[default]
+    Decimal,
+    /// E.g 30 for 30%
+    Percent,
+}
+impl std::fmt::Display for VolUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Decimal => "decimal",
+            Self::Percent => "%",
+        })
+    }
+}
+impl VolUnit {
+    const COUNT: usize = 2;
+
+    pub fn everything() -> [Self; Self::COUNT] {
+        [Self::Decimal, Self::Percent]
+    }
+
+    /// Number of this unit that make up a decimal of 1
+    fn per_decimal(&self) -> f64 {
+        match self {
+            Self::Decimal => 1.0,
+            Self::Percent => 100.0,
+        }
+    }
+
+    /// Converts a value entered in this unit into a decimal
+    pub fn decimal_of(&self, value: f64) -> f64 {
+        value / self.per_decimal()
+    }
+
+    /// Converts a decimal into this unit
+    pub fn expressed(&self, value: f64) -> f64 {
+        value * self.per_decimal()
+    }
+
+    /// Amount an input in this unit is stepped by. A volatility of 1%.
+    pub fn step(&self) -> f64 {
+        self.expressed(0.01)
+    }
+
+    /// Suffixes a volatility can be typed with, and the factor converting them into this unit
+    pub fn suffixes(&self) -> Vec<(&'static str, f64)> {
+        vec![("%", self.expressed(0.01))]
+    }
+}
+
+/// Unit a duration is entered in
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum TimeUnit {
+    #[default]
+    Years,
+    /// 365 days to a year
+    CalendarDays,
+    /// 252 days to a year
+    TradingDays,
+}
+impl std::fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Years => "years",
+            Self::CalendarDays => "days",
+            Self::TradingDays => "trading days",
+        })
+    }
+}
+impl TimeUnit {
+    const COUNT: usize = 3;
+
+    pub fn everything() -> [Self; Self::COUNT] {
+        [Self::Years, Self::CalendarDays, Self::TradingDays]
+    }
+
+    /// Number of this unit that make up a year
+    fn per_year(&self) -> f64 {
+        match self {
+            Self::Years => 1.0,
+            Self::CalendarDays => 365.0,
+            Self::TradingDays => 252.0,
+        }
+    }
+
+    /// Converts a duration entered in this unit into years
+    pub fn years_of(&self, value: f64) -> f64 {
+        value / self.per_year()
+    }
+
+    /// Converts a duration in years into this unit
+    pub fn expressed(&self, value: f64) -> f64 {
+        value * self.per_year()
+    }
+
+    /// Suffixes a duration can be typed with (days, weeks, months and years), and the factor converting them
+    /// into this unit. Days are trading days when entering trading days, otherwise calendar days.
+    pub fn suffixes(&self) -> Vec<(&'static str, f64)> {
+        let (days_per_year, days_per_week) = match self {
+            Self::TradingDays => (252.0, 5.0),
+            Self::Years | Self::CalendarDays => (365.0, 7.0),
+        };
+        let day = self.per_year() / days_per_year;
+        vec![
+            ("d", day),
+            ("w", day * days_per_week),
+            ("m", self.per_year() / 12.0),
+            ("y", self.per_year()),
+        ]
+    }
+
+    /// Amount an input in this unit is stepped by
+    pub fn step(&self) -> f64 {
+        match self {
+            Self::Years => 0.1,
+            Self::CalendarDays | Self::TradingDays => 1.0,
+        }
+    }
+}
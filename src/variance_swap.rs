@@ -0,0 +1,40 @@
+//! Fair strikes of variance swaps, which pay the difference between the variance the stock realises and the strike.
+//! The strike is replicated from a strip of out of the money options, so it reflects the whole volatility smile rather
+//! than the volatility of a single strike. Comparing it against a volatility input gives a sense of whether the input
+//! is rich or cheap relative to the market.
+
+use crate::blackscholes::{Contract, Environment, OptionType};
+
+/// Standard deviations of the log strike either side of the forward the option strip spans
+const STRIP_WIDTH: f64 = 8.0;
+/// Number of strikes the option strip is integrated over
+const STRIP_STRIKES: usize = 2001;
+
+/// Fair annualised variance strike of a variance swap over the given time, replicated from the out of the money puts
+/// and calls priced at the implied volatility of each strike.
+///
+/// With the same volatility at every strike this is that volatility squared. The fair strike of a volatility swap
+/// is at most its square root, as the square root is concave.
+pub fn fair_variance(env: &Environment, time: f64, vol_at: impl Fn(f64) -> f64) -> f64 {
+    if time <= 0.0 {
+        return vol_at(env.stock).powi(2);
+    }
+    let forward = env.stock * ((env.risk_free - env.div_yield) * time).exp();
+    let spread = vol_at(forward) * time.sqrt();
+    if spread.is_nan() || spread <= 0.0 {
+        return 0.0;
+    }
+    // Integrated over log strikes, where the strip is evenly spread
+    let (low, high) = (forward.ln() - STRIP_WIDTH * spread, forward.ln() + STRIP_WIDTH * spread);
+    let step = (high - low) / (STRIP_STRIKES - 1) as f64;
+    let integral: f64 = (0..STRIP_STRIKES).map(|i| {
+        let strike = (low + i as f64 * step).exp();
+        let option = if strike < forward { OptionType::Put } else { OptionType::Call };
+        let env = Environment { vol: vol_at(strike), ..env.clone() };
+        let price = option.bsm_price(&env, &Contract { strike, expiry: time });
+        // Ends of the trapezoidal rule count half
+        let weight = if i == 0 || i == STRIP_STRIKES - 1 { 0.5 } else { 1.0 };
+        weight * price / strike * step
+    }).sum();
+    return 2.0 * (env.risk_free * time).exp() / time * integral;
+}
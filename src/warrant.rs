@@ -0,0 +1,52 @@
+//! Warrants, calls a company writes on its own stock. Exercising one issues a new share at the strike, diluting the
+//! existing shareholders, so a warrant is worth less than a traded call on the same terms.
+
+use crate::blackscholes::{Contract, Environment, OptionType};
+
+/// Most fixed point iterations the value is solved in
+const VALUE_ITERATIONS: usize = 100;
+/// Change in the value between iterations it's taken to have converged at
+const VALUE_TOLERANCE: f64 = 1e-10;
+
+/// Terms of a warrant issue
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warrant {
+    pub strike: f64,
+    /// Years until the warrants expire
+    pub maturity: f64,
+    /// Shares outstanding before any warrant is exercised
+    pub shares: f64,
+    /// Warrants issued, each exercisable into one new share
+    pub warrants: f64,
+}
+impl Warrant {
+    /// Fraction of a traded call's value a warrant is worth once the new shares it issues are shared out.
+    /// 1 if there are no shares to dilute.
+    pub fn dilution_factor(&self) -> f64 {
+        if self.shares + self.warrants <= 0.0 {
+            return 1.0;
+        }
+        return self.shares / (self.shares + self.warrants);
+    }
+
+    /// Value of a warrant in the environment given, whose stock price already reflects the warrants being issued.
+    ///
+    /// The equity per share the warrants are written on is then the stock price plus each share's part of the
+    /// warrants' value, S + W·M/N, so the value solves W = N/(N+M) · C(S + W·M/N). It's found by fixed point
+    /// iteration, which converges as the dilution factor and the call's delta are both below 1.
+    pub fn value(&self, env: &Environment) -> f64 {
+        let contract = Contract { strike: self.strike, expiry: self.maturity };
+        let dilution = self.dilution_factor();
+        let per_share = if self.shares > 0.0 { self.warrants / self.shares } else { 0.0 };
+        let mut value = dilution * OptionType::Call.bsm_price(env, &contract);
+        for _ in 0..VALUE_ITERATIONS {
+            let equity = Environment { stock: env.stock + per_share * value, ..env.clone() };
+            let next = dilution * OptionType::Call.bsm_price(&equity, &contract);
+            if !next.is_finite() || (next - value).abs() <= VALUE_TOLERANCE {
+                return if next.is_finite() { next } else { value };
+            }
+            value = next;
+        }
+        return value;
+    }
+}